@@ -3,12 +3,16 @@
 //! This module handles loading and parsing `.claude/claudifier.json` configuration files.
 
 use crate::error::{NotificationError, Result};
+use crate::event::Event;
+use crate::matcher::Matcher;
+use chrono::{DateTime, Datelike, NaiveDateTime, NaiveTime, Utc, Weekday};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Project-specific override configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,15 +24,66 @@ pub struct ProjectOverride {
     pub handlers: Vec<HandlerConfig>,
 }
 
+/// User-level TOML config, relative to the home directory.
+pub const USER_TOML_CONFIG: &str = ".config/boopifier/config.toml";
+
+/// Project-level TOML config, relative to the project directory.
+pub const PROJECT_TOML_CONFIG: &str = ".boopifier.toml";
+
 /// Main configuration structure.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct Config {
     /// List of notification handlers to configure
+    #[serde(default)]
     pub handlers: Vec<HandlerConfig>,
 
     /// Optional project-specific overrides based on path patterns
     #[serde(default)]
     pub overrides: Option<Vec<ProjectOverride>>,
+
+    /// Hook types to run handlers for (optional - if None, all hooks are enabled)
+    #[serde(default)]
+    pub enabled_hooks: Option<Vec<String>>,
+
+    /// Hook types whose handlers never run, even if listed in `enabled_hooks`
+    #[serde(default)]
+    pub disabled_hooks: Vec<String>,
+
+    /// Global cap on notifications per time window, across all hooks (optional)
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+
+    /// Local-time ranges during which handlers don't run
+    #[serde(default)]
+    pub quiet_hours: Vec<QuietHours>,
+
+    /// Ignore events whose timestamp is older than this (optional)
+    #[serde(default)]
+    pub max_event_age: Option<MaxEventAge>,
+
+    /// Rules that select named handlers; see `Route`
+    #[serde(default)]
+    pub routes: Vec<Route>,
+
+    /// Whether every matching route or only the first one selects handlers
+    /// (defaults to "all")
+    #[serde(default)]
+    pub route_mode: Option<RouteMode>,
+
+    /// Names of handlers that run only when no other handler matched
+    #[serde(default)]
+    pub fallback: Vec<String>,
+
+    /// Exit with code 2 and report handler errors on stderr for hooks that
+    /// feed stderr back to Claude (defaults to true; set false so errors
+    /// never block, see `blocks_on_error`)
+    #[serde(default)]
+    pub block_on_error: Option<bool>,
+
+    /// Count events and handler outcomes for `--metrics` (defaults to false;
+    /// see `records_metrics`)
+    #[serde(default)]
+    pub metrics: Option<bool>,
 }
 
 impl fmt::Debug for Config {
@@ -36,24 +91,31 @@ impl fmt::Debug for Config {
         f.debug_struct("Config")
             .field("handler_count", &self.handlers.len())
             .field("handlers", &self.handlers)
+            .field("enabled_hooks", &self.enabled_hooks)
+            .field("disabled_hooks", &self.disabled_hooks)
+            .field("rate_limit", &self.rate_limit)
+            .field("quiet_hours", &self.quiet_hours)
+            .field("max_event_age", &self.max_event_age)
+            .field("routes", &self.routes)
+            .field("route_mode", &self.route_mode)
+            .field("fallback", &self.fallback)
+            .field("block_on_error", &self.block_on_error)
+            .field("metrics", &self.metrics)
             .finish()
     }
 }
 
 /// Match type for string matching
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum MatchType {
     /// Exact string match (default)
+    #[default]
     Exact,
     /// Regular expression match
     Regex,
-}
-
-impl Default for MatchType {
-    fn default() -> Self {
-        MatchType::Exact
-    }
+    /// Shell-style glob match (`*`, `?`, `[...]`)
+    Glob,
 }
 
 /// Configuration for a single notification handler.
@@ -74,16 +136,383 @@ pub struct HandlerConfig {
     #[serde(default)]
     pub match_type: MatchType,
 
+    /// Compare strings case-insensitively (defaults to false)
+    #[serde(default)]
+    pub ignore_case: bool,
+
+    /// Suppress repeats of the same hook type and session within a window (optional)
+    #[serde(default)]
+    pub debounce: Option<Debounce>,
+
+    /// Batch matched events into one notification on Stop or SessionEnd (defaults to false)
+    #[serde(default)]
+    pub digest: bool,
+
+    /// Abandon the handler after this many milliseconds (defaults to 10 seconds)
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+
+    /// Event fields to mask or remove before the handler sees the event (optional)
+    #[serde(default)]
+    pub redact: Option<Redact>,
+
     /// Handler-specific configuration
     pub config: HashMap<String, Value>,
 }
 
+/// Handler config keys that map hook type names to per-hook values.
+const HOOK_MAP_KEYS: &[&str] = &["hook_files", "hook_icons", "hook_urgency", "hook_actions", "hook_sounds"];
+
+impl HandlerConfig {
+    /// Default time a handler may run before it is abandoned.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Returns how long this handler may run.
+    pub fn timeout(&self) -> Duration {
+        self.timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or(Self::DEFAULT_TIMEOUT)
+    }
+
+    /// Returns this handler's unknown hook names, missing files and unknown
+    /// sound names (see `Config::problems`).
+    fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        let mut hook_names: Vec<String> = HOOK_MAP_KEYS
+            .iter()
+            .filter_map(|key| self.config.get(*key)?.as_object())
+            .flat_map(|map| map.keys().cloned())
+            .collect();
+        if let (Some(rules), MatchType::Exact) = (&self.match_rules, &self.match_type) {
+            rule_hook_names(&Matcher::from_rules(rules), &mut hook_names);
+        }
+        for name in hook_names {
+            if !crate::hooks::is_known_hook_type(&name) {
+                problems.push(format!("Handler '{}' references unknown hook type: {}", self.name, name));
+            }
+        }
+
+        for file in self.referenced_files() {
+            if !Path::new(shellexpand::tilde(file).as_ref()).exists() {
+                problems.push(format!("Handler '{}' references a missing file: {}", self.name, file));
+            }
+        }
+
+        if self.handler_type == "desktop" {
+            for name in crate::handlers::desktop::unknown_sound_names(&self.config) {
+                problems.push(format!("Handler '{}' references an unknown macOS sound: {}", self.name, name));
+            }
+        }
+
+        if self.handler_type == "bash_guard" {
+            for pattern in crate::handlers::bash_guard::invalid_patterns(&self.config) {
+                problems.push(format!("Handler '{}' has an invalid deny_patterns regex: {}", self.name, pattern));
+            }
+        }
+
+        problems
+    }
+
+    /// Sound files and icon image paths this handler would read.
+    fn referenced_files(&self) -> Vec<&str> {
+        let strings = |key: &str| -> Vec<&str> {
+            match self.config.get(key) {
+                Some(Value::String(s)) => vec![s.as_str()],
+                Some(Value::Array(items)) => items.iter().filter_map(Value::as_str).collect(),
+                Some(Value::Object(map)) => map.values().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            }
+        };
+
+        match self.handler_type.as_str() {
+            "sound" => ["file", "files", "hook_files"].into_iter().flat_map(strings).collect(),
+            // Like the desktop handler, only values that look like paths are files; others are icon names
+            "desktop" => ["icon", "hook_icons"]
+                .into_iter()
+                .flat_map(strings)
+                .filter(|icon| icon.contains('/') || icon.starts_with('~'))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Collects the string values exact rules expect for `hook_event_name`.
+fn rule_hook_names(matcher: &Matcher, names: &mut Vec<String>) {
+    match matcher {
+        Matcher::All(children) | Matcher::Any(children) => {
+            children.iter().for_each(|child| rule_hook_names(child, names));
+        }
+        Matcher::Not(child) => rule_hook_names(child, names),
+        Matcher::Field { path, expected: Value::String(name) } if path == "hook_event_name" => {
+            names.push(name.clone());
+        }
+        _ => {}
+    }
+}
+
+/// Debounce setting for a handler.
+///
+/// `true` uses the default window; a number sets the window in milliseconds.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum Debounce {
+    /// Enable (`true`) or disable (`false`) debouncing with the default window
+    Enabled(bool),
+    /// Debounce window in milliseconds
+    Millis(u64),
+}
+
+impl Debounce {
+    /// Default debounce window when set to `true`.
+    pub const DEFAULT_WINDOW: Duration = Duration::from_secs(3);
+
+    /// Returns the debounce window, or `None` if debouncing is disabled.
+    pub fn window(&self) -> Option<Duration> {
+        match self {
+            Debounce::Enabled(true) => Some(Self::DEFAULT_WINDOW),
+            Debounce::Enabled(false) | Debounce::Millis(0) => None,
+            Debounce::Millis(ms) => Some(Duration::from_millis(*ms)),
+        }
+    }
+}
+
+/// Event fields to hide from a handler, e.g. secrets in `tool_input` that
+/// shouldn't reach a webhook.
+///
+/// Paths are dotted like match rule fields (`tool_input.env.API_KEY`) and may
+/// use `*` segments. Matching still sees the full event; only the handler
+/// gets the redacted copy.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Redact {
+    /// Paths whose values are replaced with `"[redacted]"`
+    #[serde(default)]
+    pub mask: Vec<String>,
+
+    /// Paths that are removed from the event
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+impl Redact {
+    /// Value that masked fields are replaced with.
+    pub const MASK: &'static str = "[redacted]";
+
+    /// Masks and removes the configured paths in `event`.
+    pub fn apply(&self, event: &mut Event) {
+        let mask = Value::String(Self::MASK.to_string());
+        for path in &self.mask {
+            event.mask_path(path, &mask);
+        }
+        for path in &self.remove {
+            event.remove_path(path);
+        }
+    }
+}
+
+/// Global rate limit: at most `max` notifying events per window.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RateLimit {
+    /// Maximum number of events that may run handlers within the window
+    pub max: u32,
+
+    /// Sliding window length in milliseconds (defaults to one minute)
+    #[serde(default = "RateLimit::default_window_ms")]
+    pub window_ms: u64,
+}
+
+impl RateLimit {
+    fn default_window_ms() -> u64 {
+        60_000
+    }
+
+    /// Returns the sliding window length.
+    pub fn window(&self) -> Duration {
+        Duration::from_millis(self.window_ms)
+    }
+}
+
+/// How `routes` are evaluated.
+///
+/// Routes are always checked in the order they appear in the config file.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RouteMode {
+    /// Every matching route contributes its handlers (default)
+    #[default]
+    All,
+    /// Only the first matching route contributes handlers; later routes
+    /// aren't evaluated
+    First,
+}
+
+/// A match rule that routes events to a named set of handlers.
+///
+/// Handlers named by any route only run when one of those routes matches
+/// (and their own `match_rules`, if any, also match). When several routes
+/// match, their handlers are combined and each runs once. Handlers that no
+/// route names keep matching on their own rules alone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Route {
+    /// Event matching criteria (optional - if None, matches all events)
+    #[serde(default)]
+    pub match_rules: Option<MatchRules>,
+
+    /// Match type for string fields (defaults to "exact")
+    #[serde(default)]
+    pub match_type: MatchType,
+
+    /// Compare strings case-insensitively (defaults to false)
+    #[serde(default)]
+    pub ignore_case: bool,
+
+    /// Names of the handlers to run when this route matches
+    pub handlers: Vec<String>,
+}
+
+impl Route {
+    /// Returns true if the route's rules accept the event.
+    pub fn matches(&self, event: &Event) -> bool {
+        crate::matcher::matches_with_case(event, &self.match_rules, &self.match_type, self.ignore_case)
+    }
+}
+
+/// Age limit for events, read from a timestamp field in the payload.
+///
+/// Hook systems that replay events after a restart can deliver them long
+/// after they happened; events older than `seconds` are dropped without
+/// running handlers. Events without a readable timestamp are never stale.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MaxEventAge {
+    /// Maximum age in seconds
+    pub seconds: u64,
+
+    /// Event field holding the timestamp, as a dot path (defaults to "timestamp").
+    /// Values may be epoch seconds (number or numeric string) or RFC 3339.
+    #[serde(default = "MaxEventAge::default_timestamp_field")]
+    pub timestamp_field: String,
+}
+
+impl MaxEventAge {
+    /// Timestamp field used when `timestamp_field` isn't set.
+    pub const DEFAULT_TIMESTAMP_FIELD: &'static str = "timestamp";
+
+    fn default_timestamp_field() -> String {
+        Self::DEFAULT_TIMESTAMP_FIELD.to_string()
+    }
+
+    /// Creates a limit of `seconds` on the default timestamp field.
+    pub fn new(seconds: u64) -> Self {
+        Self {
+            seconds,
+            timestamp_field: Self::default_timestamp_field(),
+        }
+    }
+
+    /// Returns the event's timestamp, if the field is present and parses.
+    pub fn timestamp(&self, event: &Event) -> Option<DateTime<Utc>> {
+        match event.get_nested_value(&self.timestamp_field)? {
+            Value::Number(n) => from_epoch_secs(n.as_f64()?),
+            Value::String(s) => match s.trim().parse::<f64>() {
+                Ok(secs) => from_epoch_secs(secs),
+                Err(_) => DateTime::parse_from_rfc3339(s.trim()).ok().map(|t| t.with_timezone(&Utc)),
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns true if the event's timestamp is more than `seconds` before `now`.
+    pub fn is_stale(&self, event: &Event, now: DateTime<Utc>) -> bool {
+        self.timestamp(event)
+            .is_some_and(|at| now.signed_duration_since(at).num_milliseconds() > self.seconds as i64 * 1000)
+    }
+}
+
+fn from_epoch_secs(secs: f64) -> Option<DateTime<Utc>> {
+    if !secs.is_finite() {
+        return None;
+    }
+    DateTime::from_timestamp_millis((secs * 1000.0) as i64)
+}
+
+/// A daily quiet-hours range in local time, e.g. 22:00 to 07:00.
+///
+/// Ranges whose end is before their start wrap past midnight. `days`
+/// restricts the range to the weekdays it starts on (e.g. `["Fri", "Sat"]`);
+/// empty means every day.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuietHours {
+    /// Start time, "HH:MM"
+    pub start: String,
+
+    /// End time, "HH:MM" (exclusive)
+    pub end: String,
+
+    /// Weekdays the range starts on ("Mon", "Tuesday", ...); empty means every day
+    #[serde(default)]
+    pub days: Vec<String>,
+}
+
+impl QuietHours {
+    /// Returns true if `now` (local time) falls within this range.
+    ///
+    /// Ranges that fail to parse never match; `Config::from_json` rejects them.
+    pub fn contains(&self, now: NaiveDateTime) -> bool {
+        let (Ok(start), Ok(end)) = (parse_clock(&self.start), parse_clock(&self.end)) else {
+            return false;
+        };
+        let time = now.time();
+        let today = now.weekday();
+
+        if start <= end {
+            self.starts_on(today) && start <= time && time < end
+        } else {
+            // Wraps past midnight: the early-morning part belongs to yesterday's range
+            (self.starts_on(today) && time >= start) || (self.starts_on(today.pred()) && time < end)
+        }
+    }
+
+    fn starts_on(&self, day: Weekday) -> bool {
+        self.days.is_empty()
+            || self
+                .days
+                .iter()
+                .any(|d| d.parse::<Weekday>().is_ok_and(|d| d == day))
+    }
+
+    fn validate(&self) -> Result<()> {
+        for clock in [&self.start, &self.end] {
+            parse_clock(clock).map_err(|_| {
+                NotificationError::InvalidConfig(format!(
+                    "Invalid quiet_hours time {:?} (expected HH:MM)",
+                    clock
+                ))
+            })?;
+        }
+        for day in &self.days {
+            day.parse::<Weekday>().map_err(|_| {
+                NotificationError::InvalidConfig(format!("Invalid quiet_hours day {:?}", day))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_clock(clock: &str) -> std::result::Result<NaiveTime, chrono::ParseError> {
+    NaiveTime::parse_from_str(clock.trim(), "%H:%M")
+}
+
 impl fmt::Debug for HandlerConfig {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("HandlerConfig")
             .field("name", &self.name)
             .field("type", &self.handler_type)
             .field("match_rules", &self.match_rules)
+            .field("debounce", &self.debounce)
+            .field("digest", &self.digest)
+            .field("timeout_ms", &self.timeout_ms)
+            .field("redact", &self.redact)
             .finish_non_exhaustive()
     }
 }
@@ -115,22 +544,190 @@ pub enum MatchRules {
     },
 }
 
+/// Fails if a regex or glob rule in `rules` doesn't compile; `owner` names
+/// the handler or route in the error.
+fn check_patterns(owner: &str, rules: &Option<MatchRules>, match_type: &MatchType) -> Result<()> {
+    let Some(rules) = rules else {
+        return Ok(());
+    };
+
+    for pattern in Matcher::from_rules(rules).patterns() {
+        let error = match match_type {
+            MatchType::Regex => regex::Regex::new(pattern).err().map(|e| e.to_string()),
+            MatchType::Glob => glob::Pattern::new(pattern).err().map(|e| e.to_string()),
+            MatchType::Exact => None,
+        };
+
+        if let Some(e) = error {
+            return Err(NotificationError::InvalidConfig(format!(
+                "{} has an invalid {:?} pattern {:?}: {}",
+                owner, match_type, pattern, e
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Prefix for environment variables that override handler config.
+pub const ENV_PREFIX: &str = "BOOPIFIER_";
+
+/// Parses an environment override to the type of the value it replaces.
+fn parse_env_value(name: &str, raw: &str, existing: Option<&Value>) -> Result<Value> {
+    let invalid = |expected: &str| {
+        NotificationError::InvalidConfig(format!(
+            "{} must be {}, got {:?}",
+            name, expected, raw
+        ))
+    };
+
+    match existing {
+        Some(Value::Bool(_)) => match raw.trim() {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            _ => Err(invalid("a boolean (true or false)")),
+        },
+        Some(Value::Number(_)) => serde_json::from_str::<serde_json::Number>(raw.trim())
+            .map(Value::Number)
+            .map_err(|_| invalid("a number")),
+        Some(Value::Array(_)) | Some(Value::Object(_)) => {
+            serde_json::from_str(raw).map_err(|_| invalid("JSON"))
+        }
+        _ => Ok(Value::String(raw.to_string())),
+    }
+}
+
 impl Config {
     /// Loads configuration from a file path and resolves secrets.
     ///
     /// # Errors
     ///
     /// Returns an error if the file cannot be read, parsed, or secrets cannot be resolved.
+    /// Files ending in `.toml` are parsed as TOML, everything else as JSON.
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let content = fs::read_to_string(path.as_ref()).map_err(|e| {
+        let mut config = Self::parse_file(path.as_ref())?;
+        config.resolve_secrets()?;
+        Ok(config)
+    }
+
+    /// Loads and merges layered config files, later paths taking precedence.
+    ///
+    /// Missing files are skipped; returns `None` if none of them exist.
+    /// See [`Config::merge`] for how layers combine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the file if any existing layer is invalid.
+    pub fn load_layered<P: AsRef<Path>>(paths: &[P]) -> Result<Option<Self>> {
+        let mut merged: Option<Config> = None;
+
+        for path in paths.iter().map(AsRef::as_ref).filter(|p| p.exists()) {
+            let layer = Self::parse_file(path)?;
+            match &mut merged {
+                Some(config) => config.merge(layer),
+                None => merged = Some(layer),
+            }
+        }
+
+        if let Some(config) = &mut merged {
+            config.resolve_secrets()?;
+        }
+        Ok(merged)
+    }
+
+    /// Returns the default TOML layers: user config, then project config.
+    pub fn default_toml_layers(home: &Path, project_dir: Option<&Path>) -> Vec<PathBuf> {
+        let mut layers = vec![home.join(USER_TOML_CONFIG)];
+        if let Some(project_dir) = project_dir {
+            layers.push(project_dir.join(PROJECT_TOML_CONFIG));
+        }
+        layers
+    }
+
+    fn parse_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).map_err(|e| {
             NotificationError::InvalidConfig(format!("Failed to read config file: {}", e))
         })?;
 
-        let mut config = Self::from_json(&content)?;
-        config.resolve_secrets()?;
+        if path.extension().is_some_and(|ext| ext == "toml") {
+            Self::from_toml(&content).map_err(|e| {
+                NotificationError::InvalidConfig(format!("{}: {}", path.display(), e))
+            })
+        } else {
+            Self::from_json(&content)
+        }
+    }
+
+    /// Parses configuration from a TOML string.
+    ///
+    /// The structure mirrors the JSON format: `[[handlers]]` tables with
+    /// `name`, `type`, optional `match_rules`, and a `config` table.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error (including the line and column) if the TOML is invalid.
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        let config: Config = toml::from_str(toml).map_err(|e| {
+            NotificationError::InvalidConfig(format!("Invalid TOML: {}", e))
+        })?;
+
+        config.validate_patterns()?;
+        config.quiet_hours.iter().try_for_each(QuietHours::validate)?;
+        config.warn_unknown_hooks();
         Ok(config)
     }
 
+    /// Merges a higher-precedence layer into this config.
+    ///
+    /// A handler in `other` replaces the handler with the same name here;
+    /// new handlers are appended. Overrides are appended, so with
+    /// last-match-wins the higher layer's overrides take precedence.
+    /// `enabled_hooks`, `rate_limit`, `quiet_hours`, `max_event_age`,
+    /// `routes`, `route_mode`, `fallback`, `block_on_error` and `metrics`
+    /// are replaced if set; `disabled_hooks` accumulate.
+    pub fn merge(&mut self, other: Config) {
+        for handler in other.handlers {
+            match self.handlers.iter_mut().find(|h| h.name == handler.name) {
+                Some(existing) => *existing = handler,
+                None => self.handlers.push(handler),
+            }
+        }
+
+        if let Some(overrides) = other.overrides {
+            self.overrides.get_or_insert_with(Vec::new).extend(overrides);
+        }
+
+        if other.enabled_hooks.is_some() {
+            self.enabled_hooks = other.enabled_hooks;
+        }
+        self.disabled_hooks.extend(other.disabled_hooks);
+
+        if other.rate_limit.is_some() {
+            self.rate_limit = other.rate_limit;
+        }
+        if !other.quiet_hours.is_empty() {
+            self.quiet_hours = other.quiet_hours;
+        }
+        if other.max_event_age.is_some() {
+            self.max_event_age = other.max_event_age;
+        }
+        if !other.routes.is_empty() {
+            self.routes = other.routes;
+        }
+        if other.route_mode.is_some() {
+            self.route_mode = other.route_mode;
+        }
+        if !other.fallback.is_empty() {
+            self.fallback = other.fallback;
+        }
+        if other.block_on_error.is_some() {
+            self.block_on_error = other.block_on_error;
+        }
+        if other.metrics.is_some() {
+            self.metrics = other.metrics;
+        }
+    }
+
     /// Parses configuration from a JSON string.
     ///
     /// # Errors
@@ -141,9 +738,147 @@ impl Config {
             NotificationError::InvalidConfig(format!("Invalid JSON: {}", e))
         })?;
 
+        config.validate_patterns()?;
+        config.quiet_hours.iter().try_for_each(QuietHours::validate)?;
+        config.warn_unknown_hooks();
         Ok(config)
     }
 
+    /// Returns true if `now` (local time) is within any quiet-hours range.
+    pub fn in_quiet_hours(&self, now: NaiveDateTime) -> bool {
+        self.quiet_hours.iter().any(|range| range.contains(now))
+    }
+
+    /// Returns true if handler errors should block hooks that feed stderr back to Claude.
+    pub fn blocks_on_error(&self) -> bool {
+        self.block_on_error.unwrap_or(true)
+    }
+
+    /// Returns true if events and handler outcomes should be counted for `--metrics`.
+    pub fn records_metrics(&self) -> bool {
+        self.metrics.unwrap_or(false)
+    }
+
+    /// Returns true if handlers should run for `hook_type`.
+    ///
+    /// A hook is enabled unless it is in `disabled_hooks`, or `enabled_hooks`
+    /// is set and doesn't list it.
+    pub fn hook_enabled(&self, hook_type: &str) -> bool {
+        if self.disabled_hooks.iter().any(|h| h == hook_type) {
+            return false;
+        }
+
+        self.enabled_hooks
+            .as_ref()
+            .is_none_or(|enabled| enabled.iter().any(|h| h == hook_type))
+    }
+
+    /// Returns hook names in `enabled_hooks`/`disabled_hooks` that boopifier doesn't know.
+    pub fn unknown_hooks(&self) -> Vec<&str> {
+        self.enabled_hooks
+            .iter()
+            .flatten()
+            .chain(self.disabled_hooks.iter())
+            .map(String::as_str)
+            .filter(|name| !crate::hooks::is_known_hook_type(name))
+            .collect()
+    }
+
+    /// Logs a warning for each unknown hook name; a typo shouldn't break every hook.
+    fn warn_unknown_hooks(&self) {
+        for name in self.unknown_hooks() {
+            log::warn!("Unknown hook type in enabled_hooks/disabled_hooks: {}", name);
+        }
+    }
+
+    /// Checks that every regex or glob in the match rules compiles.
+    ///
+    /// This turns a typo in a pattern into a config error at load time
+    /// instead of a handler that silently never matches.
+    fn validate_patterns(&self) -> Result<()> {
+        let override_handlers = self
+            .overrides
+            .iter()
+            .flatten()
+            .flat_map(|o| o.handlers.iter());
+
+        for handler in self.handlers.iter().chain(override_handlers) {
+            check_patterns(&format!("Handler '{}'", handler.name), &handler.match_rules, &handler.match_type)?;
+        }
+        for (i, route) in self.routes.iter().enumerate() {
+            check_patterns(&format!("Route {}", i + 1), &route.match_rules, &route.match_type)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns every problem `--validate-config` reports, in config order.
+    ///
+    /// Covers unknown hook type names (in `enabled_hooks`/`disabled_hooks`,
+    /// handler `hook_*` maps, and exact `hook_event_name` rules) and sound or
+    /// icon files that don't exist, and routes or `fallback` entries naming
+    /// handlers that aren't configured. Invalid patterns already fail parsing.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems: Vec<String> = self
+            .unknown_hooks()
+            .into_iter()
+            .map(|name| format!("Unknown hook type in enabled_hooks/disabled_hooks: {}", name))
+            .collect();
+
+        let override_handlers = self
+            .overrides
+            .iter()
+            .flatten()
+            .flat_map(|o| o.handlers.iter());
+
+        for handler in self.handlers.iter().chain(override_handlers) {
+            problems.extend(handler.problems());
+        }
+
+        // Overrides replace the handler list, so their names count too
+        let known: Vec<&str> = self
+            .handlers
+            .iter()
+            .chain(self.overrides.iter().flatten().flat_map(|o| o.handlers.iter()))
+            .map(|h| h.name.as_str())
+            .collect();
+        for (i, route) in self.routes.iter().enumerate() {
+            for name in &route.handlers {
+                if !known.contains(&name.as_str()) {
+                    problems.push(format!("Route {} names unknown handler '{}'", i + 1, name));
+                }
+            }
+        }
+        for name in &self.fallback {
+            if !known.contains(&name.as_str()) {
+                problems.push(format!("fallback names unknown handler '{}'", name));
+            }
+        }
+
+        problems
+    }
+
+    /// Returns the routes that select handlers for the event, in config order.
+    ///
+    /// In `first` mode this is at most one route, and evaluation stops at it.
+    pub fn matching_routes<'a>(&'a self, event: &'a Event) -> Vec<&'a Route> {
+        let mut matched = self.routes.iter().filter(move |route| route.matches(event));
+        match self.route_mode.unwrap_or_default() {
+            RouteMode::All => matched.collect(),
+            RouteMode::First => matched.next().into_iter().collect(),
+        }
+    }
+
+    /// Returns true if the handler is listed in `fallback`.
+    pub fn is_fallback(&self, handler_name: &str) -> bool {
+        self.fallback.iter().any(|h| h == handler_name)
+    }
+
+    /// Returns true if any route names the handler.
+    pub fn is_routed(&self, handler_name: &str) -> bool {
+        self.routes.iter().any(|r| r.handlers.iter().any(|h| h == handler_name))
+    }
+
     /// Applies project-specific overrides based on the current project path.
     ///
     /// If multiple patterns match, the last match wins. If a pattern matches,
@@ -162,7 +897,7 @@ impl Config {
         for override_config in overrides {
             if glob::Pattern::new(&override_config.path_pattern)
                 .ok()
-                .and_then(|pattern| Some(pattern.matches(project_path)))
+                .map(|pattern| pattern.matches(project_path))
                 .unwrap_or(false)
             {
                 last_match = Some(override_config);
@@ -175,6 +910,44 @@ impl Config {
         }
     }
 
+    /// Overrides handler config values from `BOOPIFIER_<TYPE>_<KEY>` variables.
+    ///
+    /// `BOOPIFIER_WEBHOOK_URL` sets `url` on every `webhook` handler and
+    /// `BOOPIFIER_SOUND_FILE` sets `file` on every `sound` handler. The key is
+    /// the rest of the name, lowercased. Variables whose type part doesn't name
+    /// a configured handler type are ignored.
+    ///
+    /// Values are parsed to match the type already in the config: booleans
+    /// must be `true`/`false`, numbers must parse as numbers, and arrays or
+    /// objects must be JSON. New keys are set as strings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the variable if a value doesn't parse.
+    pub fn apply_env_overrides<I>(&mut self, vars: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        for (name, raw) in vars {
+            let Some(rest) = name.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+
+            for handler in &mut self.handlers {
+                let type_prefix = format!("{}_", handler.handler_type.to_uppercase());
+                let Some(key) = rest.strip_prefix(&type_prefix).filter(|k| !k.is_empty()) else {
+                    continue;
+                };
+
+                let key = key.to_lowercase();
+                let value = parse_env_value(&name, &raw, handler.config.get(&key))?;
+                handler.config.insert(key, value);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Resolves secrets in configuration values.
     ///
     /// Supports:
@@ -274,6 +1047,513 @@ mod tests {
         assert_eq!(config.handlers[0].handler_type, "desktop");
     }
 
+    #[test]
+    fn test_parse_toml_config() {
+        let toml = r#"
+            [[handlers]]
+            name = "stop-sound"
+            type = "sound"
+            match_rules = { hook_event_name = "Stop" }
+
+            [handlers.config]
+            file = "~/sounds/done.wav"
+            volume = 0.5
+        "#;
+
+        let config = Config::from_toml(toml).unwrap();
+        assert_eq!(config.handlers.len(), 1);
+        assert_eq!(config.handlers[0].handler_type, "sound");
+        assert_eq!(config.handlers[0].config["volume"], serde_json::json!(0.5));
+        assert!(config.handlers[0].match_rules.is_some());
+    }
+
+    #[test]
+    fn test_layered_project_overrides_user() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let user = dir.path().join("user.toml");
+        let project = dir.path().join("project.toml");
+        fs::write(
+            &user,
+            r#"
+            [[handlers]]
+            name = "sound"
+            type = "sound"
+            config = { file = "user.wav" }
+
+            [[handlers]]
+            name = "desktop"
+            type = "desktop"
+            config = {}
+            "#,
+        )
+        .unwrap();
+        fs::write(
+            &project,
+            r#"
+            [[handlers]]
+            name = "sound"
+            type = "sound"
+            config = { file = "project.wav" }
+
+            [[handlers]]
+            name = "webhook"
+            type = "webhook"
+            config = {}
+            "#,
+        )
+        .unwrap();
+
+        let missing = dir.path().join("missing.toml");
+        let config = Config::load_layered(&[&user, &missing, &project]).unwrap().unwrap();
+
+        let names: Vec<&str> = config.handlers.iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(names, vec!["sound", "desktop", "webhook"]);
+        assert_eq!(config.handlers[0].config["file"], "project.wav");
+    }
+
+    #[test]
+    fn test_merge_replaces_block_on_error() {
+        assert!(Config::from_json(r#"{"handlers": []}"#).unwrap().blocks_on_error());
+        let blocking = || Config::from_json(r#"{"handlers": [], "block_on_error": true}"#).unwrap();
+
+        let mut config = blocking();
+        config.merge(Config::from_json(r#"{"handlers": [], "block_on_error": false}"#).unwrap());
+        assert!(!config.blocks_on_error());
+
+        // A layer that doesn't set it keeps the lower layer's value
+        let mut config = blocking();
+        config.merge(Config::from_json(r#"{"handlers": []}"#).unwrap());
+        assert!(config.blocks_on_error());
+    }
+
+    #[test]
+    fn test_merge_replaces_metrics() {
+        let mut config = Config::from_json(r#"{"handlers": [], "metrics": true}"#).unwrap();
+        config.merge(Config::from_json(r#"{"handlers": [], "metrics": false}"#).unwrap());
+        assert!(!config.records_metrics());
+
+        let mut config = Config::from_json(r#"{"handlers": [], "metrics": true}"#).unwrap();
+        config.merge(Config::from_json(r#"{"handlers": []}"#).unwrap());
+        assert!(config.records_metrics());
+    }
+
+    #[test]
+    fn test_merge_resets_route_mode() {
+        let mut config = Config::from_json(r#"{"handlers": [], "route_mode": "first"}"#).unwrap();
+        config.merge(Config::from_json(r#"{"handlers": [], "route_mode": "all"}"#).unwrap());
+        assert_eq!(config.route_mode, Some(RouteMode::All));
+
+        let mut config = Config::from_json(r#"{"handlers": [], "route_mode": "first"}"#).unwrap();
+        config.merge(Config::from_json(r#"{"handlers": []}"#).unwrap());
+        assert_eq!(config.route_mode, Some(RouteMode::First));
+    }
+
+    #[test]
+    fn test_layered_no_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let layers = Config::default_toml_layers(dir.path(), Some(dir.path()));
+        assert!(Config::load_layered(&layers).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_malformed_toml_reports_path_and_line() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[[handlers]]\nname = \"sound\"\ntype = \n").unwrap();
+
+        let err = Config::load_layered(&[&path]).unwrap_err().to_string();
+        assert!(err.contains(&path.display().to_string()), "{}", err);
+        assert!(err.contains("line 3"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_redact() {
+        let config = Config::from_json(
+            r#"{"handlers": [
+                {"name": "slack", "type": "webhook", "redact": {"mask": ["tool_input.env.*"], "remove": ["tool_input.content"]}, "config": {}},
+                {"name": "beep", "type": "sound", "config": {}}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(config.handlers[1].redact, None);
+
+        let mut event = Event::from_json(
+            r#"{"tool_name": "Write", "tool_input": {"file_path": "a.env", "content": "KEY=1", "env": {"KEY": "1"}}}"#,
+        )
+        .unwrap();
+        config.handlers[0].redact.as_ref().unwrap().apply(&mut event);
+
+        assert_eq!(
+            event.as_value(),
+            serde_json::json!({
+                "tool_name": "Write",
+                "tool_input": {"file_path": "a.env", "env": {"KEY": "[redacted]"}}
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_debounce() {
+        let json = r#"{
+            "handlers": [
+                {"name": "a", "type": "sound", "config": {}},
+                {"name": "b", "type": "sound", "debounce": true, "config": {}},
+                {"name": "c", "type": "sound", "debounce": 10000, "config": {}},
+                {"name": "d", "type": "sound", "debounce": false, "config": {}}
+            ]
+        }"#;
+
+        let config = Config::from_json(json).unwrap();
+        let windows: Vec<Option<Duration>> = config
+            .handlers
+            .iter()
+            .map(|h| h.debounce.as_ref().and_then(|d| d.window()))
+            .collect();
+
+        assert_eq!(
+            windows,
+            vec![None, Some(Debounce::DEFAULT_WINDOW), Some(Duration::from_secs(10)), None]
+        );
+    }
+
+    #[test]
+    fn test_invalid_regex_is_config_error() {
+        let json = r#"{
+            "handlers": [{
+                "name": "bad",
+                "type": "sound",
+                "match_type": "regex",
+                "match_rules": {"message": "([unclosed"},
+                "config": {}
+            }]
+        }"#;
+
+        let err = Config::from_json(json).unwrap_err();
+        assert!(matches!(err, NotificationError::InvalidConfig(_)));
+        assert!(err.to_string().contains("Handler 'bad' has an invalid Regex pattern"));
+    }
+
+    #[test]
+    fn test_invalid_regex_in_override_any_rule() {
+        let json = r#"{
+            "handlers": [],
+            "overrides": [{
+                "path_pattern": "/work/*",
+                "handlers": [{
+                    "name": "bad",
+                    "type": "sound",
+                    "match_type": "regex",
+                    "match_rules": {"any": [{"message": "ok"}, {"message": "*oops"}]},
+                    "config": {}
+                }]
+            }]
+        }"#;
+
+        assert!(Config::from_json(json).is_err());
+    }
+
+    #[test]
+    fn test_valid_regex_and_exact_specials_accepted() {
+        let json = r#"{
+            "handlers": [
+                {"name": "re", "type": "sound", "match_type": "regex",
+                 "match_rules": {"message": ".*permission.*"}, "config": {}},
+                {"name": "exact", "type": "sound",
+                 "match_rules": {"message": "([not a regex"}, "config": {}}
+            ]
+        }"#;
+
+        assert!(Config::from_json(json).is_ok());
+    }
+
+    #[test]
+    fn test_parse_rate_limit() {
+        let config = Config::from_json(r#"{"handlers": [], "rate_limit": {"max": 5}}"#).unwrap();
+        let limit = config.rate_limit.unwrap();
+        assert_eq!(limit.max, 5);
+        assert_eq!(limit.window(), Duration::from_secs(60));
+    }
+
+    fn quiet(start: &str, end: &str, days: &[&str]) -> QuietHours {
+        QuietHours {
+            start: start.to_string(),
+            end: end.to_string(),
+            days: days.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    /// 2024-01-01 was a Monday.
+    fn monday_at(day_offset: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        chrono::NaiveDate::from_ymd_opt(2024, 1, 1 + day_offset)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_quiet_hours_same_day_range() {
+        let range = quiet("12:00", "13:30", &[]);
+        assert!(range.contains(monday_at(0, 12, 0)));
+        assert!(range.contains(monday_at(0, 13, 29)));
+        assert!(!range.contains(monday_at(0, 13, 30)));
+        assert!(!range.contains(monday_at(0, 11, 59)));
+    }
+
+    #[test]
+    fn test_quiet_hours_wrap_past_midnight() {
+        let range = quiet("22:00", "07:00", &[]);
+        assert!(range.contains(monday_at(0, 23, 0)));
+        assert!(range.contains(monday_at(1, 0, 30)));
+        assert!(range.contains(monday_at(1, 6, 59)));
+        assert!(!range.contains(monday_at(1, 7, 0)));
+        assert!(!range.contains(monday_at(1, 21, 59)));
+    }
+
+    #[test]
+    fn test_quiet_hours_days_follow_start_day() {
+        // Friday night into Saturday morning only
+        let range = quiet("22:00", "07:00", &["Fri"]);
+        assert!(range.contains(monday_at(4, 23, 0))); // Fri 23:00
+        assert!(range.contains(monday_at(5, 3, 0))); // Sat 03:00
+        assert!(!range.contains(monday_at(5, 23, 0))); // Sat 23:00
+        assert!(!range.contains(monday_at(4, 3, 0))); // Fri 03:00 belongs to Thursday
+    }
+
+    #[test]
+    fn test_quiet_hours_config() {
+        let config = Config::from_json(
+            r#"{"handlers": [], "quiet_hours": [{"start": "22:00", "end": "07:00", "days": ["Monday"]}]}"#,
+        )
+        .unwrap();
+        assert!(config.in_quiet_hours(monday_at(0, 22, 30)));
+        assert!(!config.in_quiet_hours(monday_at(2, 22, 30)));
+
+        assert!(Config::from_json(r#"{"handlers": [], "quiet_hours": [{"start": "10pm", "end": "07:00"}]}"#).is_err());
+        assert!(Config::from_json(r#"{"handlers": [], "quiet_hours": [{"start": "22:00", "end": "07:00", "days": ["Funday"]}]}"#).is_err());
+    }
+
+    #[test]
+    fn test_route_patterns_are_validated() {
+        let err = Config::from_json(
+            r#"{"handlers": [], "routes": [{"match_type": "regex", "match_rules": {"tool_name": "("}, "handlers": []}]}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Route 1 has an invalid Regex pattern"));
+    }
+
+    #[test]
+    fn test_parse_route_mode() {
+        let config = Config::from_json(r#"{"handlers": []}"#).unwrap();
+        assert_eq!(config.route_mode, None);
+
+        let config = Config::from_json(r#"{"handlers": [], "route_mode": "first"}"#).unwrap();
+        assert_eq!(config.route_mode, Some(RouteMode::First));
+
+        assert!(Config::from_json(r#"{"handlers": [], "route_mode": "best"}"#).is_err());
+    }
+
+    #[test]
+    fn test_max_event_age_config() {
+        let config = Config::from_json(r#"{"handlers": [], "max_event_age": {"seconds": 30}}"#).unwrap();
+        assert_eq!(config.max_event_age, Some(MaxEventAge::new(30)));
+
+        let config = Config::from_json(
+            r#"{"handlers": [], "max_event_age": {"seconds": 30, "timestamp_field": "meta.sent_at"}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.max_event_age.unwrap().timestamp_field, "meta.sent_at");
+    }
+
+    #[test]
+    fn test_max_event_age_fresh_and_stale() {
+        use serde_json::json;
+
+        let limit = MaxEventAge::new(60);
+        let now = DateTime::parse_from_rfc3339("2025-06-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let at = |timestamp: Value| {
+            Event::from_value(json!({"hook_event_name": "Stop", "timestamp": timestamp})).unwrap()
+        };
+
+        // Epoch seconds, as a number or a string, and RFC 3339 with any offset
+        assert!(!limit.is_stale(&at(json!(now.timestamp() - 30)), now));
+        assert!(limit.is_stale(&at(json!(now.timestamp() - 90)), now));
+        assert!(limit.is_stale(&at(json!((now.timestamp() - 90).to_string())), now));
+        assert!(!limit.is_stale(&at(json!("2025-06-01T13:59:30+02:00")), now));
+        assert!(limit.is_stale(&at(json!("2025-06-01T11:58:00Z")), now));
+
+        // Timestamps in the future are fresh
+        assert!(!limit.is_stale(&at(json!(now.timestamp() + 3600)), now));
+    }
+
+    #[test]
+    fn test_max_event_age_missing_timestamp() {
+        let limit = MaxEventAge::new(60);
+        let now = Utc::now();
+
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        assert_eq!(limit.timestamp(&event), None);
+        assert!(!limit.is_stale(&event, now));
+
+        let event = Event::from_json(r#"{"hook_event_name": "Stop", "timestamp": "yesterday"}"#).unwrap();
+        assert!(!limit.is_stale(&event, now));
+    }
+
+    #[test]
+    fn test_hook_enabled() {
+        let config = Config::from_json(r#"{"handlers": [], "disabled_hooks": ["Notification"]}"#).unwrap();
+        assert!(!config.hook_enabled("Notification"));
+        assert!(config.hook_enabled("Stop"));
+
+        let config = Config::from_json(
+            r#"{"handlers": [], "enabled_hooks": ["Stop", "Notification"], "disabled_hooks": ["Notification"]}"#,
+        )
+        .unwrap();
+        assert!(config.hook_enabled("Stop"));
+        assert!(!config.hook_enabled("Notification"));
+        assert!(!config.hook_enabled("PreToolUse"));
+    }
+
+    #[test]
+    fn test_unknown_hook_names_are_not_fatal() {
+        let config = Config::from_json(r#"{"handlers": [], "disabled_hooks": ["Notificaton", "Stop"]}"#).unwrap();
+        assert_eq!(config.unknown_hooks(), vec!["Notificaton"]);
+        assert!(config.hook_enabled("Notification"));
+    }
+
+    #[test]
+    fn test_problems_clean_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let sound = dir.path().join("boop.wav");
+        fs::write(&sound, b"").unwrap();
+
+        let config = Config::from_json(
+            &serde_json::json!({
+                "enabled_hooks": ["Stop"],
+                "handlers": [
+                    {"name": "boop", "type": "sound", "match_rules": {"hook_event_name": "Stop"},
+                     "config": {"file": sound, "hook_files": {"Stop": sound}}},
+                    {"name": "popup", "type": "desktop",
+                     "config": {"icon": "dialog-information", "hook_urgency": {"PreToolUse": "low"},
+                                "sound_name": "Glass", "hook_sounds": {"Stop": "ping"}}}
+                ],
+                "routes": [{"match_rules": {"hook_event_name": "Stop"}, "handlers": ["boop", "popup"]}]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        assert!(config.problems().is_empty(), "{:?}", config.problems());
+    }
+
+    #[test]
+    fn test_problems_lists_unknown_hooks_and_missing_files() {
+        let config = Config::from_json(
+            r#"{
+                "disabled_hooks": ["Notificaton"],
+                "handlers": [
+                    {"name": "boop", "type": "sound",
+                     "match_rules": {"any": [{"hook_event_name": "Stop"}, {"hook_event_name": "Stopp"}]},
+                     "config": {"files": ["/no/such/boop.wav"]}},
+                    {"name": "popup", "type": "desktop",
+                     "config": {"hook_icons": {"Stop": "dialog-ok", "Notification": "~/no/such/icon.png"},
+                                "hook_sounds": {"Stop": "Chime"}}},
+                    {"name": "hook", "type": "webhook", "config": {"hook_actions": {"Finish": {}}}},
+                    {"name": "guard", "type": "bash_guard", "config": {"deny_patterns": ["rm\\s+-rf", "(force"]}}
+                ],
+                "routes": [{"handlers": ["boop", "slack"]}],
+                "fallback": ["bloop"]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.problems(),
+            vec![
+                "Unknown hook type in enabled_hooks/disabled_hooks: Notificaton",
+                "Handler 'boop' references unknown hook type: Stopp",
+                "Handler 'boop' references a missing file: /no/such/boop.wav",
+                "Handler 'popup' references a missing file: ~/no/such/icon.png",
+                "Handler 'popup' references an unknown macOS sound: Chime",
+                "Handler 'hook' references unknown hook type: Finish",
+                "Handler 'guard' has an invalid deny_patterns regex: (force",
+                "Route 1 names unknown handler 'slack'",
+                "fallback names unknown handler 'bloop'",
+            ]
+        );
+    }
+
+    fn env(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn test_env_overrides_by_handler_type() {
+        let json = r#"{
+            "handlers": [
+                {"name": "hook", "type": "webhook", "config": {"url": "https://old", "timeout": 1000}},
+                {"name": "beep", "type": "sound", "config": {"file": "a.wav"}}
+            ]
+        }"#;
+
+        let mut config = Config::from_json(json).unwrap();
+        config
+            .apply_env_overrides(env(&[
+                ("BOOPIFIER_WEBHOOK_URL", "https://new"),
+                ("BOOPIFIER_WEBHOOK_TIMEOUT", "2500"),
+                ("BOOPIFIER_SOUND_VOLUME", "0.5"),
+                ("BOOPIFIER_LOG", "debug"),
+                ("HOME", "/home/user"),
+            ]))
+            .unwrap();
+
+        assert_eq!(config.handlers[0].config["url"], "https://new");
+        assert_eq!(config.handlers[0].config["timeout"], serde_json::json!(2500));
+        assert_eq!(config.handlers[1].config["file"], "a.wav");
+        // New keys are strings
+        assert_eq!(config.handlers[1].config["volume"], "0.5");
+    }
+
+    #[test]
+    fn test_env_override_type_errors() {
+        let json = r#"{
+            "handlers": [{"name": "d", "type": "discord", "config": {"embed": false, "timeout": 10}}]
+        }"#;
+
+        let mut config = Config::from_json(json).unwrap();
+        let err = config
+            .apply_env_overrides(env(&[("BOOPIFIER_DISCORD_EMBED", "yes")]))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid configuration: BOOPIFIER_DISCORD_EMBED must be a boolean (true or false), got \"yes\""
+        );
+
+        let err = config
+            .apply_env_overrides(env(&[("BOOPIFIER_DISCORD_TIMEOUT", "soon")]))
+            .unwrap_err();
+        assert!(err.to_string().contains("BOOPIFIER_DISCORD_TIMEOUT must be a number"));
+
+        config
+            .apply_env_overrides(env(&[("BOOPIFIER_DISCORD_EMBED", "true")]))
+            .unwrap();
+        assert_eq!(config.handlers[0].config["embed"], serde_json::json!(true));
+    }
+
+    #[test]
+    fn test_env_overrides_from_process_env() {
+        let json = r#"{"handlers": [{"name": "s", "type": "signal", "config": {"recipient": "+1"}}]}"#;
+
+        std::env::set_var("BOOPIFIER_SIGNAL_RECIPIENT", "+2");
+        let mut config = Config::from_json(json).unwrap();
+        config.apply_env_overrides(std::env::vars()).unwrap();
+        assert_eq!(config.handlers[0].config["recipient"], "+2");
+
+        std::env::remove_var("BOOPIFIER_SIGNAL_RECIPIENT");
+        let mut config = Config::from_json(json).unwrap();
+        config.apply_env_overrides(std::env::vars()).unwrap();
+        assert_eq!(config.handlers[0].config["recipient"], "+1");
+    }
+
     #[test]
     fn test_env_var_resolution() {
         std::env::set_var("TEST_VAR", "secret_value");
@@ -0,0 +1,134 @@
+//! Debouncing of rapid repeated notifications.
+//!
+//! The time each key last fired is kept in a `StateFile` (see `state`).
+
+use crate::event::Event;
+use crate::state::StateFile;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Entries older than this are dropped from the state file.
+const PRUNE_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Tracks when each debounce key last fired.
+pub struct Debouncer {
+    state: StateFile,
+}
+
+impl Debouncer {
+    /// Creates a debouncer that persists state to `state_path`.
+    pub fn new(state_path: impl Into<PathBuf>) -> Self {
+        Self {
+            state: StateFile::new(state_path),
+        }
+    }
+
+    /// Creates a debouncer that keeps its state in memory, e.g. for the daemon.
+    pub fn in_memory() -> Self {
+        Self {
+            state: StateFile::in_memory(),
+        }
+    }
+
+    /// Default state file location (`$TMPDIR/boopifier-debounce.json`).
+    pub fn default_path() -> PathBuf {
+        std::env::temp_dir().join("boopifier-debounce.json")
+    }
+
+    /// Builds the debounce key for a handler and event (handler + hook type + session id).
+    pub fn key(handler_name: &str, event: &Event) -> String {
+        format!(
+            "{}:{}:{}",
+            handler_name,
+            event.event_type().unwrap_or("unknown"),
+            event.get_str("session_id").unwrap_or("")
+        )
+    }
+
+    /// Returns true if `key` already fired within `window` of `now`.
+    ///
+    /// Otherwise records `now` as the last fire time and returns false. Suppressed
+    /// calls don't extend the window, so a steady stream still fires once per window.
+    /// State file errors are ignored - debouncing is best-effort.
+    pub fn should_suppress(&self, key: &str, window: Duration, now: SystemTime) -> bool {
+        let now_ms = millis_since_epoch(now);
+
+        self.state.update(|state: &mut HashMap<String, u64>| {
+            if let Some(&last_ms) = state.get(key) {
+                if now_ms.saturating_sub(last_ms) < window.as_millis() as u64 {
+                    return true;
+                }
+            }
+
+            let prune_ms = PRUNE_AFTER.as_millis() as u64;
+            state.retain(|_, &mut last_ms| now_ms.saturating_sub(last_ms) < prune_ms);
+            state.insert(key.to_string(), now_ms);
+            false
+        })
+    }
+}
+
+pub(crate) fn millis_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_700_000_000 + secs)
+    }
+
+    #[test]
+    fn test_suppresses_within_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let debouncer = Debouncer::new(dir.path().join("state.json"));
+        let window = Duration::from_secs(3);
+
+        assert!(!debouncer.should_suppress("sound:Stop:abc", window, at(0)));
+        assert!(debouncer.should_suppress("sound:Stop:abc", window, at(1)));
+        assert!(debouncer.should_suppress("sound:Stop:abc", window, at(2)));
+    }
+
+    #[test]
+    fn test_fires_outside_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let debouncer = Debouncer::new(dir.path().join("state.json"));
+        let window = Duration::from_secs(3);
+
+        assert!(!debouncer.should_suppress("sound:Stop:abc", window, at(0)));
+        assert!(debouncer.should_suppress("sound:Stop:abc", window, at(2)));
+        assert!(!debouncer.should_suppress("sound:Stop:abc", window, at(3)));
+    }
+
+    #[test]
+    fn test_keys_are_independent() {
+        let dir = tempfile::tempdir().unwrap();
+        let debouncer = Debouncer::new(dir.path().join("state.json"));
+        let window = Duration::from_secs(3);
+
+        assert!(!debouncer.should_suppress("sound:Stop:abc", window, at(0)));
+        assert!(!debouncer.should_suppress("sound:Stop:def", window, at(1)));
+        assert!(!debouncer.should_suppress("sound:Notification:abc", window, at(1)));
+    }
+
+    #[test]
+    fn test_state_persists_across_instances() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let window = Duration::from_secs(3);
+
+        assert!(!Debouncer::new(&path).should_suppress("k", window, at(0)));
+        assert!(Debouncer::new(&path).should_suppress("k", window, at(1)));
+    }
+
+    #[test]
+    fn test_key_uses_hook_type_and_session() {
+        let event = Event::from_json(r#"{"hook_event_name": "Stop", "session_id": "abc"}"#).unwrap();
+        assert_eq!(Debouncer::key("sound", &event), "sound:Stop:abc");
+    }
+}
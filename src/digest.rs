@@ -0,0 +1,199 @@
+//! Digest batching of notifications within a session.
+//!
+//! Handlers with `digest` set don't fire for every event. Each event they
+//! match is counted by hook type for its session instead, and the next
+//! `Stop` or `SessionEnd` fires the handler once with a summary of the counts,
+//! which accumulate in a `StateFile` until then.
+
+use crate::debounce::millis_since_epoch;
+use crate::event::Event;
+use crate::state::StateFile;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// Digests not flushed for this long are dropped, e.g. for sessions that crashed.
+const PRUNE_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Event field holding the rendered digest (e.g. "5 events: 3 PreToolUse, 2 Notification").
+pub const DIGEST_FIELD: &str = "digest";
+
+/// Event field holding the number of events in the digest.
+pub const DIGEST_COUNT_FIELD: &str = "digest_count";
+
+/// Hook types that flush a session's digest.
+pub const FLUSH_HOOKS: &[&str] = &["Stop", "SessionEnd"];
+
+/// Events counted for one handler and session.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Entry {
+    updated_ms: u64,
+    counts: BTreeMap<String, u64>,
+}
+
+/// Accumulates per-session event counts for digest handlers.
+pub struct DigestStore {
+    state: StateFile,
+}
+
+impl DigestStore {
+    /// Creates a store that persists state to `state_path`.
+    pub fn new(state_path: impl Into<PathBuf>) -> Self {
+        Self {
+            state: StateFile::new(state_path),
+        }
+    }
+
+    /// Default state file location (`$TMPDIR/boopifier-digest.json`).
+    pub fn default_path() -> PathBuf {
+        std::env::temp_dir().join("boopifier-digest.json")
+    }
+
+    /// Builds the digest key for a handler and event (handler + session id).
+    pub fn key(handler_name: &str, event: &Event) -> String {
+        format!("{}:{}", handler_name, event.get_str("session_id").unwrap_or(""))
+    }
+
+    /// Counts one `hook_type` event under `key`.
+    ///
+    /// State file errors are ignored - digests are best-effort.
+    pub fn record(&self, key: &str, hook_type: &str, now: SystemTime) {
+        let now_ms = millis_since_epoch(now);
+        let prune_ms = PRUNE_AFTER.as_millis() as u64;
+
+        self.state.update(|state: &mut HashMap<String, Entry>| {
+            state.retain(|_, entry| now_ms.saturating_sub(entry.updated_ms) < prune_ms);
+            let entry = state.entry(key.to_string()).or_default();
+            entry.updated_ms = now_ms;
+            *entry.counts.entry(hook_type.to_string()).or_default() += 1;
+        });
+    }
+
+    /// Removes and returns the counts recorded under `key`, by hook type.
+    pub fn take(&self, key: &str) -> BTreeMap<String, u64> {
+        self.state
+            .update(|state: &mut HashMap<String, Entry>| state.remove(key))
+            .map(|entry| entry.counts)
+            .unwrap_or_default()
+    }
+
+    /// Records the event for a digest handler, or flushes its digest.
+    ///
+    /// Returns true if the handler should run now: for `Stop` and
+    /// `SessionEnd` events, which get `digest` and `digest_count` set when
+    /// earlier events were counted. Other events are counted and return false.
+    pub fn prepare(&self, handler_name: &str, event: &mut Event, now: SystemTime) -> bool {
+        let key = Self::key(handler_name, event);
+        let hook_type = event.event_type().unwrap_or("unknown").to_string();
+
+        if !FLUSH_HOOKS.contains(&hook_type.as_str()) {
+            self.record(&key, &hook_type, now);
+            return false;
+        }
+
+        let counts = self.take(&key);
+        if let Some(digest) = render(&counts) {
+            let total: u64 = counts.values().sum();
+            event.data.insert(DIGEST_FIELD.to_string(), Value::String(digest));
+            event.data.insert(DIGEST_COUNT_FIELD.to_string(), Value::from(total));
+        }
+        true
+    }
+}
+
+/// Summarizes counts by hook type, most frequent first, e.g.
+/// "5 events: 3 PreToolUse, 2 Notification".
+///
+/// Returns `None` if nothing was counted.
+pub fn render(counts: &BTreeMap<String, u64>) -> Option<String> {
+    let total: u64 = counts.values().sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut by_count: Vec<(&String, &u64)> = counts.iter().collect();
+    by_count.sort_by(|a, b| b.1.cmp(a.1));
+    let parts: Vec<String> = by_count
+        .into_iter()
+        .map(|(hook_type, count)| format!("{} {}", count, hook_type))
+        .collect();
+
+    let noun = if total == 1 { "event" } else { "events" };
+    Some(format!("{} {}: {}", total, noun, parts.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_700_000_000 + secs)
+    }
+
+    fn event(hook: &str, session_id: &str) -> Event {
+        Event::from_json(&serde_json::json!({"hook_event_name": hook, "session_id": session_id}).to_string())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_accumulates_across_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("digest.json");
+
+        for (i, hook) in ["PreToolUse", "PreToolUse", "Notification", "PreToolUse"].iter().enumerate() {
+            // A fresh store each time, like separate invocations
+            let fire = DigestStore::new(&path).prepare("popup", &mut event(hook, "abc"), at(i as u64));
+            assert!(!fire, "{} should be batched", hook);
+        }
+        DigestStore::new(&path).prepare("popup", &mut event("Notification", "other"), at(5));
+
+        let counts = DigestStore::new(&path).take("popup:abc");
+        assert_eq!(counts.get("PreToolUse"), Some(&3));
+        assert_eq!(counts.get("Notification"), Some(&1));
+
+        // Taking consumes the digest; other sessions are kept
+        assert!(DigestStore::new(&path).take("popup:abc").is_empty());
+        assert_eq!(DigestStore::new(&path).take("popup:other").get("Notification"), Some(&1));
+    }
+
+    #[test]
+    fn test_stop_flushes_digest() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = DigestStore::new(dir.path().join("digest.json"));
+
+        store.prepare("popup", &mut event("Notification", "abc"), at(0));
+        store.prepare("popup", &mut event("PostToolUse", "abc"), at(1));
+        store.prepare("popup", &mut event("PostToolUse", "abc"), at(2));
+        // Another handler's digest is separate
+        store.prepare("log", &mut event("Notification", "abc"), at(3));
+
+        let mut stop = event("Stop", "abc");
+        assert!(store.prepare("popup", &mut stop, at(4)));
+        assert_eq!(stop.get_str(DIGEST_FIELD), Some("3 events: 2 PostToolUse, 1 Notification"));
+        assert_eq!(stop.get_i64(DIGEST_COUNT_FIELD), Some(3));
+
+        // Nothing left to summarize for the next stop
+        let mut stop = event("SessionEnd", "abc");
+        assert!(store.prepare("popup", &mut stop, at(5)));
+        assert_eq!(stop.get_str(DIGEST_FIELD), None);
+        assert_eq!(stop.get_i64(DIGEST_COUNT_FIELD), None);
+    }
+
+    #[test]
+    fn test_render() {
+        let counts = |pairs: &[(&str, u64)]| -> BTreeMap<String, u64> {
+            pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+        };
+
+        assert_eq!(render(&counts(&[])), None);
+        assert_eq!(render(&counts(&[("Notification", 1)])).as_deref(), Some("1 event: 1 Notification"));
+        // Ties keep hook type order
+        assert_eq!(
+            render(&counts(&[("PreToolUse", 2), ("Notification", 2), ("SessionStart", 5)])).as_deref(),
+            Some("9 events: 5 SessionStart, 2 Notification, 2 PreToolUse")
+        );
+    }
+}
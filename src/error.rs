@@ -44,5 +44,35 @@ pub enum NotificationError {
     Handler(String),
 }
 
+/// Errors from reading an event and resolving its hook type.
+///
+/// Returned by `Event::from_json`, `Event::validate` and `hook_from_event`,
+/// so library users can tell the failure kinds apart.
+#[derive(Error, Debug)]
+pub enum BoopError {
+    /// The event isn't valid JSON, isn't an object, or nests too deeply.
+    #[error("Failed to parse event JSON: {0}")]
+    EventParse(String),
+
+    /// The event names a hook type boopifier doesn't handle.
+    #[error("Unknown hook type: {0}")]
+    UnknownHook(String),
+
+    /// `BOOPIFIER_FORCE_HOOK` names a hook type boopifier doesn't handle.
+    #[error("Invalid {env} {name:?}: expected one of {expected}")]
+    InvalidForcedHook {
+        env: &'static str,
+        name: String,
+        expected: String,
+    },
+
+    /// The event lacks fields Claude Code always sends for its hook type.
+    #[error("{hook_type} event is missing required fields: {}", missing.join(", "))]
+    MissingFields {
+        hook_type: String,
+        missing: Vec<String>,
+    },
+}
+
 /// Result type alias for notification operations.
 pub type Result<T> = std::result::Result<T, NotificationError>;
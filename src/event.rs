@@ -2,10 +2,21 @@
 //!
 //! This module defines the event structure received from Claude Code hooks via stdin.
 
+use crate::error::BoopError;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
+/// Claude Code fields and the dotted paths OpenCode uses for them, tried in order.
+///
+/// `from_value` copies the first alias found to the Claude Code field, so
+/// e.g. `{{session_id}}` works for OpenCode events too.
+pub const FIELD_ALIASES: &[(&str, &[&str])] = &[("session_id", &["sessionID", "session.id"])];
+
+/// `tool_input` keys that may hold the file a tool acts on, tried in order
+/// by `Event::file_path`. Claude Code uses `file_path`; OpenCode `filePath`.
+pub const FILE_PATH_KEYS: &[&str] = &["file_path", "filePath", "notebook_path", "path"];
+
 /// A Claude Code event received from stdin.
 ///
 /// Events are flexible JSON objects that can contain any fields.
@@ -18,15 +29,209 @@ pub struct Event {
 }
 
 impl Event {
+    /// Default limit on how deeply an event's objects and arrays may nest.
+    ///
+    /// Real hook events nest a handful of levels; the limit keeps pathological
+    /// input from exhausting the stack in recursive code such as cloning or
+    /// dropping the value.
+    pub const MAX_DEPTH: usize = 64;
+
     /// Creates a new event from a JSON string.
     ///
     /// # Errors
     ///
-    /// Returns an error if the JSON is invalid.
-    pub fn from_json(json: &str) -> anyhow::Result<Self> {
-        let event = serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_str(json))
-            .map_err(|e| anyhow::anyhow!("Failed to parse event JSON: {}", e))?;
-        Ok(event)
+    /// Returns an error if the JSON is invalid or nests deeper than `MAX_DEPTH`.
+    pub fn from_json(json: &str) -> Result<Self, BoopError> {
+        let value: Value = serde_path_to_error::deserialize(&mut serde_json::Deserializer::from_str(json))
+            .map_err(|e| BoopError::EventParse(e.to_string()))?;
+        Self::from_value(value)
+    }
+
+    /// Finds the first complete JSON object in `input`, for recovering events
+    /// from a damaged pipe.
+    ///
+    /// Leading bytes before the first `{` and anything after the object's
+    /// closing brace are dropped. Returns `None` if no complete object is
+    /// present, e.g. when the input was cut off mid-object.
+    pub fn complete_json(input: &str) -> Option<&str> {
+        let start = input.find('{')?;
+        let rest = &input[start..];
+        let mut values = serde_json::Deserializer::from_str(rest).into_iter::<Value>();
+        match values.next() {
+            Some(Ok(_)) => Some(&rest[..values.byte_offset()]),
+            _ => None,
+        }
+    }
+
+    /// Creates a new event from an already-parsed JSON value.
+    ///
+    /// OpenCode tool fields are copied to Claude Code's top-level `tool_name`
+    /// and `tool_input` (see `normalize_opencode_tool`), and other OpenCode
+    /// spellings to their Claude Code field (see `FIELD_ALIASES`), so matchers
+    /// and templates written for either agent work on both.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not a JSON object or nests deeper
+    /// than `MAX_DEPTH`.
+    pub fn from_value(value: Value) -> Result<Self, BoopError> {
+        Self::from_value_with_max_depth(value, Self::MAX_DEPTH)
+    }
+
+    /// Like `from_value`, but with a custom nesting limit.
+    ///
+    /// The top-level object is depth 1.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is not a JSON object or nests deeper
+    /// than `max_depth`.
+    pub fn from_value_with_max_depth(value: Value, max_depth: usize) -> Result<Self, BoopError> {
+        if exceeds_depth(&value, max_depth) {
+            return Err(BoopError::EventParse(format!("nested deeper than {} levels", max_depth)));
+        }
+
+        match value {
+            Value::Object(map) => {
+                let mut event = Self {
+                    data: map.into_iter().collect(),
+                };
+                event.normalize_opencode_tool();
+                event.normalize_aliases();
+                Ok(event)
+            }
+            other => Err(BoopError::EventParse(format!("expected an object, got {}", other))),
+        }
+    }
+
+    /// Fills in `tool_name` and `tool_input` from OpenCode's tool fields.
+    ///
+    /// OpenCode events (e.g. `tool.execute.before`) carry the tool as:
+    ///
+    /// - name: `tool.name`, or `tool` itself when it is a string
+    /// - arguments: `tool.args`, `tool.input`, or a top-level `args`
+    ///
+    /// Locations are tried in that order. Fields already present (Claude
+    /// Code events) are never overwritten.
+    fn normalize_opencode_tool(&mut self) {
+        if !self.data.contains_key("tool_name") {
+            let name = self
+                .get_nested_value("tool.name")
+                .filter(|v| v.is_string())
+                .or_else(|| self.data.get("tool").filter(|v| v.is_string()))
+                .cloned();
+            if let Some(name) = name {
+                self.data.insert("tool_name".to_string(), name);
+            }
+        }
+
+        if !self.data.contains_key("tool_input") {
+            let input = self
+                .get_nested_value("tool.args")
+                .or_else(|| self.get_nested_value("tool.input"))
+                .or_else(|| self.data.get("args"))
+                .cloned();
+            if let Some(input) = input {
+                self.data.insert("tool_input".to_string(), input);
+            }
+        }
+
+        // OpenCode file tools name the path `filePath`
+        if let Some(Value::Object(input)) = self.data.get_mut("tool_input") {
+            if !input.contains_key("file_path") {
+                if let Some(path) = input.get("filePath").filter(|v| v.is_string()).cloned() {
+                    input.insert("file_path".to_string(), path);
+                }
+            }
+        }
+    }
+
+    /// Fills in each `FIELD_ALIASES` field that is missing from its first
+    /// alias holding a string. Fields already present are never overwritten.
+    fn normalize_aliases(&mut self) {
+        for (field, aliases) in FIELD_ALIASES {
+            if self.data.contains_key(*field) {
+                continue;
+            }
+            let value = aliases
+                .iter()
+                .find_map(|alias| self.get_nested_value(alias).filter(|v| v.is_string()))
+                .cloned();
+            if let Some(value) = value {
+                self.data.insert(field.to_string(), value);
+            }
+        }
+    }
+
+    /// Iterates over the event's top-level keys, in no particular order.
+    ///
+    /// Includes fields `from_value` filled in, such as `tool_name` for
+    /// OpenCode events.
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.data.keys()
+    }
+
+    /// Returns true if the event has a top-level field named `key`.
+    pub fn contains(&self, key: &str) -> bool {
+        self.data.contains_key(key)
+    }
+
+    /// Gets the hook event name (e.g., "Stop", "PreToolUse").
+    ///
+    /// This is the single place that knows which field carries the hook type.
+    pub fn event_type(&self) -> Option<&str> {
+        self.get_str("hook_event_name")
+    }
+
+    /// Gets the name of the tool a tool-use event refers to.
+    ///
+    /// Reads `tool_name`, which `from_value` fills in for OpenCode events.
+    pub fn tool_name(&self) -> Option<&str> {
+        self.get_str("tool_name")
+    }
+
+    /// Gets the input a tool-use event passes to its tool.
+    ///
+    /// Reads `tool_input`, which `from_value` fills in for OpenCode events.
+    pub fn tool_input(&self) -> Option<&Value> {
+        self.data.get("tool_input")
+    }
+
+    /// Gets the directory the agent was working in, from `cwd`.
+    pub fn cwd(&self) -> Option<&str> {
+        self.get_str("cwd")
+    }
+
+    /// Gets the file a tool-use event acts on, from the first of
+    /// `FILE_PATH_KEYS` in `tool_input` that holds a string.
+    pub fn file_path(&self) -> Option<&str> {
+        let input = self.tool_input()?;
+        FILE_PATH_KEYS.iter().find_map(|key| input.get(*key)?.as_str())
+    }
+
+    /// Like `file_path`, but relative to the event's `cwd` when the file is
+    /// inside it (e.g. `src/main.rs` rather than `/home/me/app/src/main.rs`).
+    pub fn relative_file_path(&self) -> Option<&str> {
+        let path = self.file_path()?;
+        let relative = self
+            .cwd()
+            .and_then(|cwd| path.strip_prefix(cwd.trim_end_matches('/')))
+            .and_then(|rest| rest.strip_prefix('/'));
+        Some(relative.unwrap_or(path))
+    }
+
+    /// Gets the text a `Notification` event asks to show, from `message`.
+    pub fn message(&self) -> Option<&str> {
+        self.get_str("message")
+    }
+
+    /// Whether Claude Code is already continuing because of a Stop hook.
+    ///
+    /// Reads `stop_hook_active`; a missing or non-boolean field counts as false.
+    /// Handlers that would make Claude continue should do nothing when this is
+    /// true, or Claude can loop forever.
+    pub fn stop_hook_active(&self) -> bool {
+        self.get_bool("stop_hook_active").unwrap_or(false)
     }
 
     /// Gets a field value as a string reference.
@@ -34,22 +239,168 @@ impl Event {
         self.data.get(key)?.as_str()
     }
 
+    /// Gets a field value as a bool.
+    ///
+    /// Only JSON booleans count; the string `"true"` returns `None`.
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        self.data.get(key)?.as_bool()
+    }
+
+    /// Gets a field value as an i64.
+    ///
+    /// Only JSON integers in range count; floats and numeric strings return `None`.
+    pub fn get_i64(&self, key: &str) -> Option<i64> {
+        self.data.get(key)?.as_i64()
+    }
+
+    /// Gets a bool at a dotted path, with the same coercion rules as `get_bool`.
+    pub fn get_nested_bool(&self, path: &str) -> Option<bool> {
+        self.get_nested_value(path)?.as_bool()
+    }
+
+    /// Gets an i64 at a dotted path, with the same coercion rules as `get_i64`.
+    pub fn get_nested_i64(&self, path: &str) -> Option<i64> {
+        self.get_nested_value(path)?.as_i64()
+    }
+
     /// Gets a field value as a string, with nested path support (e.g., "tool.name").
+    ///
+    /// Numeric path segments index into arrays (e.g., "tool_calls.0.name").
     pub fn get_nested_str(&self, path: &str) -> Option<String> {
-        let parts: Vec<&str> = path.split('.').collect();
-        let value = Value::Object(
-            self.data
-                .iter()
-                .map(|(k, v)| (k.clone(), v.clone()))
-                .collect(),
-        );
+        self.get_nested_value(path)?.as_str().map(|s| s.to_string())
+    }
+
+    /// Gets a reference to the value at a dotted path (e.g., "tool.input.timeout").
+    ///
+    /// Numeric path segments index into arrays. A `*` segment matches any
+    /// child (see `get_nested_values`); the first match in document order
+    /// is returned. Returns `None` if any segment is missing.
+    pub fn get_nested_value(&self, path: &str) -> Option<&Value> {
+        self.get_nested_values(path).into_iter().next()
+    }
+
+    /// Gets every value at a dotted path, expanding `*` segments.
+    ///
+    /// A `*` segment matches every value of an object and every element of an
+    /// array, so `tool_input.*.path` finds `path` under each child of
+    /// `tool_input`. Branches that don't have the rest of the path are dropped.
+    /// Nested objects yield keys in sorted order and arrays in index order; the
+    /// order of top-level fields is unspecified. Without wildcards this returns
+    /// at most one value.
+    pub fn get_nested_values(&self, path: &str) -> Vec<&Value> {
+        // No value sits deeper than the event itself may nest
+        if path.split('.').count() > Self::MAX_DEPTH {
+            return Vec::new();
+        }
+
+        let mut parts = path.split('.');
+        let Some(first) = parts.next() else {
+            return Vec::new();
+        };
+        let mut current: Vec<&Value> = match first {
+            "*" => self.data.values().collect(),
+            key => self.data.get(key).into_iter().collect(),
+        };
 
-        let mut current = &value;
         for part in parts {
-            current = current.get(part)?;
+            current = current
+                .into_iter()
+                .flat_map(|value| nested_children(value, part))
+                .collect();
+            if current.is_empty() {
+                break;
+            }
+        }
+
+        current
+    }
+
+    /// Replaces every value at a dotted path with `replacement`.
+    ///
+    /// Paths use the same syntax as `get_nested_values`, including `*`
+    /// segments. Missing paths are ignored.
+    pub fn mask_path(&mut self, path: &str, replacement: &Value) {
+        self.edit_path(path, |parent, key| {
+            for value in nested_children_mut(parent, key) {
+                *value = replacement.clone();
+            }
+        });
+    }
+
+    /// Removes every value at a dotted path (see `mask_path`).
+    ///
+    /// Removing an array element shifts the elements after it.
+    pub fn remove_path(&mut self, path: &str) {
+        self.edit_path(path, |parent, key| match (parent, key) {
+            (Value::Object(map), "*") => map.clear(),
+            (Value::Object(map), key) => {
+                map.remove(key);
+            }
+            (Value::Array(items), "*") => items.clear(),
+            (Value::Array(items), index) => {
+                if let Some(i) = index.parse::<usize>().ok().filter(|&i| i < items.len()) {
+                    items.remove(i);
+                }
+            }
+            _ => {}
+        });
+    }
+
+    /// Calls `edit` with each parent value and final segment selected by `path`.
+    fn edit_path(&mut self, path: &str, mut edit: impl FnMut(&mut Value, &str)) {
+        let parts: Vec<&str> = path.split('.').collect();
+        let Some((last, parents)) = parts.split_last() else {
+            return;
+        };
+        if parts.len() > Self::MAX_DEPTH {
+            return;
+        }
+
+        let mut root = Value::Object(self.data.drain().collect());
+        let mut current = vec![&mut root];
+        for part in parents {
+            current = current
+                .into_iter()
+                .flat_map(|value| nested_children_mut(value, part))
+                .collect();
+        }
+        for parent in current {
+            edit(parent, last);
+        }
+
+        if let Value::Object(map) = root {
+            self.data = map.into_iter().collect();
+        }
+    }
+
+    /// Checks that the event has every field Claude Code sends for `hook_type`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BoopError::MissingFields` listing all missing fields.
+    pub fn validate(&self, hook_type: &str) -> Result<(), BoopError> {
+        let missing: Vec<String> = crate::hooks::required_fields(hook_type)
+            .iter()
+            .filter(|field| !self.data.contains_key(**field))
+            .map(|field| field.to_string())
+            .collect();
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(BoopError::MissingFields {
+                hook_type: hook_type.to_string(),
+                missing,
+            })
         }
+    }
 
-        current.as_str().map(|s| s.to_string())
+    /// Serializes the event back to a JSON string.
+    ///
+    /// Equivalent to `serde_json::to_string(&event)`, but infallible: the
+    /// data is always a map with string keys.
+    pub fn to_json(&self) -> String {
+        self.as_value().to_string()
     }
 
     /// Gets the entire event data as a reference.
@@ -63,6 +414,56 @@ impl Event {
     }
 }
 
+/// Returns true if objects and arrays in `value` nest deeper than `max_depth`.
+///
+/// Walks the value with an explicit stack, so it can't overflow itself.
+fn exceeds_depth(value: &Value, max_depth: usize) -> bool {
+    let mut stack = vec![(value, 1)];
+    while let Some((value, depth)) = stack.pop() {
+        let children: Vec<&Value> = match value {
+            Value::Object(map) => map.values().collect(),
+            Value::Array(items) => items.iter().collect(),
+            _ => continue,
+        };
+        if depth > max_depth {
+            return true;
+        }
+        stack.extend(children.into_iter().map(|child| (child, depth + 1)));
+    }
+    false
+}
+
+/// Returns the children of `value` selected by one path segment.
+fn nested_children<'a>(value: &'a Value, part: &str) -> Vec<&'a Value> {
+    match (value, part) {
+        (Value::Object(map), "*") => map.values().collect(),
+        (Value::Array(items), "*") => items.iter().collect(),
+        (Value::Array(items), index) => index
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| items.get(i))
+            .into_iter()
+            .collect(),
+        (_, key) => value.get(key).into_iter().collect(),
+    }
+}
+
+/// Mutable counterpart of `nested_children`.
+fn nested_children_mut<'a>(value: &'a mut Value, part: &str) -> Vec<&'a mut Value> {
+    match (value, part) {
+        (Value::Object(map), "*") => map.values_mut().collect(),
+        (Value::Array(items), "*") => items.iter_mut().collect(),
+        (Value::Array(items), index) => index
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| items.get_mut(i))
+            .into_iter()
+            .collect(),
+        (Value::Object(map), key) => map.get_mut(key).into_iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -75,6 +476,180 @@ mod tests {
         assert_eq!(event.get_str("status"), Some("success"));
     }
 
+    #[test]
+    fn test_event_type() {
+        let event = Event::from_json(r#"{"hook_event_name": "Stop", "session_id": "abc"}"#).unwrap();
+        assert_eq!(event.event_type(), Some("Stop"));
+
+        let event = Event::from_json(r#"{"session_id": "abc"}"#).unwrap();
+        assert_eq!(event.event_type(), None);
+    }
+
+    #[test]
+    fn test_tool_accessors_claude_code() {
+        let json = r#"{"hook_event_name": "PreToolUse", "tool_name": "Bash", "tool_input": {"command": "ls"}}"#;
+        let event = Event::from_json(json).unwrap();
+        assert_eq!(event.tool_name(), Some("Bash"));
+        assert_eq!(event.tool_input(), Some(&serde_json::json!({"command": "ls"})));
+    }
+
+    #[test]
+    fn test_tool_accessors_opencode() {
+        let json = r#"{"type": "tool.execute.before", "tool": {"name": "bash", "input": {"command": "ls"}}}"#;
+        let event = Event::from_json(json).unwrap();
+        assert_eq!(event.tool_name(), Some("bash"));
+        assert_eq!(event.tool_input(), Some(&serde_json::json!({"command": "ls"})));
+
+        let event = Event::from_json(r#"{"tool": "read"}"#).unwrap();
+        assert_eq!(event.tool_name(), Some("read"));
+        assert_eq!(event.tool_input(), None);
+    }
+
+    #[test]
+    fn test_opencode_tool_fields_normalized() {
+        let json = r#"{"type": "tool.execute.before", "tool": {"name": "bash", "args": {"command": "ls"}}}"#;
+        let event = Event::from_json(json).unwrap();
+        assert_eq!(event.get_str("tool_name"), Some("bash"));
+        assert_eq!(event.data["tool_input"], serde_json::json!({"command": "ls"}));
+
+        // A bare tool string with top-level args
+        let event = Event::from_json(r#"{"tool": "edit", "args": {"filePath": "a.rs"}}"#).unwrap();
+        assert_eq!(event.get_str("tool_name"), Some("edit"));
+        assert_eq!(event.get_nested_str("tool_input.filePath"), Some("a.rs".to_string()));
+
+        // The original OpenCode fields are kept
+        assert_eq!(event.get_str("tool"), Some("edit"));
+
+        // ...and filePath is also available as file_path
+        assert_eq!(event.get_nested_str("tool_input.file_path"), Some("a.rs".to_string()));
+    }
+
+    #[test]
+    fn test_file_path() {
+        let event = Event::from_json(
+            r#"{"cwd": "/home/me/app", "tool_name": "Edit", "tool_input": {"file_path": "/home/me/app/src/main.rs"}}"#,
+        )
+        .unwrap();
+        assert_eq!(event.file_path(), Some("/home/me/app/src/main.rs"));
+        assert_eq!(event.relative_file_path(), Some("src/main.rs"));
+
+        // OpenCode, and paths outside cwd (or no cwd) stay as they are
+        let event = Event::from_json(
+            r#"{"cwd": "/home/me/app", "tool": {"name": "write", "args": {"filePath": "/home/me/app-old/.env"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(event.relative_file_path(), Some("/home/me/app-old/.env"));
+        let event = Event::from_json(r#"{"tool_input": {"notebook_path": "nb.ipynb"}}"#).unwrap();
+        assert_eq!(event.relative_file_path(), Some("nb.ipynb"));
+
+        let event = Event::from_json(r#"{"tool_input": {"command": "ls"}}"#).unwrap();
+        assert_eq!(event.file_path(), None);
+    }
+
+    #[test]
+    fn test_claude_code_tool_fields_not_overwritten() {
+        let json = r#"{"tool_name": "Bash", "tool_input": {"command": "ls"}, "tool": {"name": "other", "args": {}}}"#;
+        let event = Event::from_json(json).unwrap();
+        assert_eq!(event.tool_name(), Some("Bash"));
+        assert_eq!(event.tool_input(), Some(&serde_json::json!({"command": "ls"})));
+    }
+
+    #[test]
+    fn test_complete_json() {
+        let valid = r#"{"hook_event_name": "Stop", "nested": {"a": "}"}}"#;
+        assert_eq!(Event::complete_json(valid), Some(valid));
+
+        // Whitespace and junk around the object are dropped
+        let padded = format!("\n  {}  \r\n\t", valid);
+        assert_eq!(Event::complete_json(&padded), Some(valid));
+        assert_eq!(Event::complete_json(&format!("\u{feff}{}\0\0garbage", valid)), Some(valid));
+
+        // Cut off mid-object
+        assert_eq!(Event::complete_json(r#"{"hook_event_name": "Stop", "nested": {"a""#), None);
+        assert_eq!(Event::complete_json(""), None);
+    }
+
+    #[test]
+    fn test_keys_and_contains() {
+        let event = Event::from_json(r#"{"hook_event_name": "PreToolUse", "sessionID": "s1", "tool": {"name": "bash"}}"#)
+            .unwrap();
+
+        let mut keys: Vec<&str> = event.keys().map(String::as_str).collect();
+        keys.sort();
+        // tool_name and session_id are filled in from the OpenCode fields
+        assert_eq!(keys, ["hook_event_name", "sessionID", "session_id", "tool", "tool_name"]);
+
+        assert!(event.contains("hook_event_name"));
+        assert!(event.contains("tool_name"));
+        assert!(!event.contains("tool_input"));
+        // Only top-level keys, not dot paths
+        assert!(!event.contains("tool.name"));
+    }
+
+    #[test]
+    fn test_message() {
+        let event = Event::from_json(r#"{"hook_event_name": "Notification", "message": "Claude is waiting for your input"}"#)
+            .unwrap();
+        assert_eq!(event.message(), Some("Claude is waiting for your input"));
+
+        let event = Event::from_json(r#"{"hook_event_name": "Notification", "message": 3}"#).unwrap();
+        assert_eq!(event.message(), None);
+    }
+
+    #[test]
+    fn test_session_id_aliases() {
+        for json in [
+            r#"{"session_id": "ses_1"}"#,
+            r#"{"sessionID": "ses_1"}"#,
+            r#"{"session": {"id": "ses_1", "title": "fix bug"}}"#,
+        ] {
+            let event = Event::from_json(json).unwrap();
+            assert_eq!(event.get_str("session_id"), Some("ses_1"), "{}", json);
+        }
+
+        // The Claude Code field wins, and non-string aliases are ignored
+        let event = Event::from_json(r#"{"session_id": "a", "sessionID": "b"}"#).unwrap();
+        assert_eq!(event.get_str("session_id"), Some("a"));
+        let event = Event::from_json(r#"{"session": {"id": 7}}"#).unwrap();
+        assert_eq!(event.get_str("session_id"), None);
+    }
+
+    #[test]
+    fn test_stop_hook_active() {
+        let event = Event::from_json(r#"{"hook_event_name": "Stop", "stop_hook_active": true}"#).unwrap();
+        assert!(event.stop_hook_active());
+
+        let event = Event::from_json(r#"{"hook_event_name": "Stop", "stop_hook_active": false}"#).unwrap();
+        assert!(!event.stop_hook_active());
+
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        assert!(!event.stop_hook_active());
+    }
+
+    #[test]
+    fn test_typed_accessors() {
+        let json = r#"{"success": true, "duration_ms": 1500, "stats": {"ok": false, "tokens": -3}}"#;
+        let event = Event::from_json(json).unwrap();
+
+        assert_eq!(event.get_bool("success"), Some(true));
+        assert_eq!(event.get_i64("duration_ms"), Some(1500));
+        assert_eq!(event.get_nested_bool("stats.ok"), Some(false));
+        assert_eq!(event.get_nested_i64("stats.tokens"), Some(-3));
+    }
+
+    #[test]
+    fn test_typed_accessors_wrong_type_or_missing() {
+        let json = r#"{"success": "true", "duration_ms": "1500", "ratio": 1.5, "stats": {"ok": 1}}"#;
+        let event = Event::from_json(json).unwrap();
+
+        assert_eq!(event.get_bool("success"), None);
+        assert_eq!(event.get_i64("duration_ms"), None);
+        assert_eq!(event.get_i64("ratio"), None);
+        assert_eq!(event.get_nested_bool("stats.ok"), None);
+        assert_eq!(event.get_bool("missing"), None);
+        assert_eq!(event.get_nested_i64("stats.missing"), None);
+    }
+
     #[test]
     fn test_nested_access() {
         let json = r#"{"tool": {"name": "bash", "status": "success"}}"#;
@@ -82,9 +657,264 @@ mod tests {
         assert_eq!(event.get_nested_str("tool.name"), Some("bash".to_string()));
     }
 
+    #[test]
+    fn test_nested_array_index() {
+        let json = r#"{"tool_calls": [{"name": "bash"}, {"name": "read"}]}"#;
+        let event = Event::from_json(json).unwrap();
+        assert_eq!(event.get_nested_str("tool_calls.0.name"), Some("bash".to_string()));
+        assert_eq!(event.get_nested_str("tool_calls.1.name"), Some("read".to_string()));
+    }
+
+    #[test]
+    fn test_nested_mixed_object_array_path() {
+        let json = r#"{"messages": [{"parts": ["hello", "world"]}]}"#;
+        let event = Event::from_json(json).unwrap();
+        assert_eq!(event.get_nested_str("messages.0.parts.1"), Some("world".to_string()));
+    }
+
+    #[test]
+    fn test_nested_array_index_out_of_range() {
+        let json = r#"{"tool_calls": [{"name": "bash"}], "tool": {"name": "bash"}}"#;
+        let event = Event::from_json(json).unwrap();
+        assert_eq!(event.get_nested_str("tool_calls.5.name"), None);
+        assert_eq!(event.get_nested_str("tool_calls.-1.name"), None);
+        assert_eq!(event.get_nested_str("tool_calls.first.name"), None);
+        // Numeric segments don't index into objects
+        assert_eq!(event.get_nested_str("tool.0"), None);
+    }
+
+    #[test]
+    fn test_nested_value_object() {
+        let json = r#"{"tool": {"input": {"command": "ls", "timeout": 30}}}"#;
+        let event = Event::from_json(json).unwrap();
+        let input = event.get_nested_value("tool.input").unwrap();
+        assert!(input.is_object());
+        assert_eq!(input["command"], "ls");
+    }
+
+    #[test]
+    fn test_nested_value_integer() {
+        let json = r#"{"tool": {"input": {"timeout": 30}}, "tool_calls": [{"id": 7}]}"#;
+        let event = Event::from_json(json).unwrap();
+        assert_eq!(event.get_nested_value("tool.input.timeout").and_then(|v| v.as_i64()), Some(30));
+        assert_eq!(event.get_nested_value("tool_calls.0.id").and_then(|v| v.as_i64()), Some(7));
+        assert_eq!(event.get_nested_value("tool.missing"), None);
+    }
+
+    #[test]
+    fn test_nested_wildcard_objects() {
+        let json = r#"{"tool_input": {"edits": {"first": {"path": "a.rs"}, "second": {"path": "b.rs"}}, "mode": "fast"}}"#;
+        let event = Event::from_json(json).unwrap();
+
+        let paths: Vec<_> = event
+            .get_nested_values("tool_input.edits.*.path")
+            .into_iter()
+            .filter_map(|v| v.as_str())
+            .collect();
+        assert_eq!(paths, vec!["a.rs", "b.rs"]);
+        // Accessors return the first match
+        assert_eq!(event.get_nested_str("tool_input.edits.*.path"), Some("a.rs".to_string()));
+        // Branches without the rest of the path are dropped
+        assert_eq!(event.get_nested_values("tool_input.*.first.path").len(), 1);
+        assert!(event.get_nested_values("tool_input.*.missing").is_empty());
+    }
+
+    #[test]
+    fn test_nested_wildcard_arrays_and_top_level() {
+        let json = r#"{"tool_calls": [{"name": "bash"}, {"id": 2}, {"name": "read"}], "tool": {"name": "x"}}"#;
+        let event = Event::from_json(json).unwrap();
+
+        // `*` over an array expands to every element
+        let names: Vec<_> = event
+            .get_nested_values("tool_calls.*.name")
+            .into_iter()
+            .filter_map(|v| v.as_str())
+            .collect();
+        assert_eq!(names, vec!["bash", "read"]);
+        assert_eq!(event.get_nested_value("*.name"), Some(&serde_json::json!("x")));
+        assert_eq!(event.get_nested_value("tool.*"), Some(&serde_json::json!("x")));
+        assert!(event.get_nested_values("tool.name.*").is_empty());
+    }
+
+    #[test]
+    fn test_nested_missing_intermediate_key() {
+        let json = r#"{"tool": {"name": "bash"}, "status": "ok"}"#;
+        let event = Event::from_json(json).unwrap();
+        assert_eq!(event.get_nested_str("missing.name"), None);
+        assert_eq!(event.get_nested_str("tool.input.command"), None);
+        assert_eq!(event.get_nested_str("status.name"), None);
+    }
+
+    #[test]
+    fn test_nested_repeated_calls_on_large_event() {
+        let mut data = serde_json::Map::new();
+        for i in 0..1000 {
+            data.insert(format!("field_{}", i), Value::String(format!("value_{}", i)));
+        }
+        data.insert("tool".to_string(), serde_json::json!({"name": "bash"}));
+        let event = Event::from_json(&Value::Object(data).to_string()).unwrap();
+
+        for _ in 0..1000 {
+            assert_eq!(event.get_nested_str("tool.name"), Some("bash".to_string()));
+            assert_eq!(event.get_nested_str("field_999"), Some("value_999".to_string()));
+            assert_eq!(event.get_nested_str("tool.missing"), None);
+        }
+    }
+
+    #[test]
+    fn test_validate_pre_tool_use() {
+        let json = r#"{"hook_event_name": "PreToolUse", "tool_name": "Bash", "tool_input": {"command": "ls"}}"#;
+        let event = Event::from_json(json).unwrap();
+        assert!(event.validate("PreToolUse").is_ok());
+    }
+
+    #[test]
+    fn test_validate_pre_tool_use_missing_fields() {
+        let event = Event::from_json(r#"{"hook_event_name": "PreToolUse"}"#).unwrap();
+        let err = event.validate("PreToolUse").unwrap_err();
+        match &err {
+            BoopError::MissingFields { hook_type, missing } => {
+                assert_eq!(hook_type, "PreToolUse");
+                assert_eq!(missing, &["tool_name", "tool_input"]);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert_eq!(err.to_string(), "PreToolUse event is missing required fields: tool_name, tool_input");
+    }
+
+    #[test]
+    fn test_from_value_matches_from_json() {
+        let json = r#"{"hook_event_name": "PreToolUse", "tool_name": "Bash", "tool_input": {"command": "ls"}}"#;
+        let from_json = Event::from_json(json).unwrap();
+        let from_value = Event::from_value(serde_json::from_str(json).unwrap()).unwrap();
+
+        assert_eq!(from_json.data, from_value.data);
+        assert_eq!(from_value.event_type(), Some("PreToolUse"));
+    }
+
+    #[test]
+    fn test_from_value_rejects_non_object() {
+        assert!(Event::from_value(serde_json::json!([1, 2])).is_err());
+        assert!(Event::from_json(r#""just a string""#).is_err());
+    }
+
+    #[test]
+    fn test_mask_path() {
+        let mut event = Event::from_json(
+            r#"{"tool_input": {"command": "deploy", "env": {"API_KEY": "s3cret", "REGION": "eu"}}, "token": "t"}"#,
+        )
+        .unwrap();
+        let mask = Value::String("[redacted]".to_string());
+
+        event.mask_path("tool_input.env.API_KEY", &mask);
+        event.mask_path("token", &mask);
+        event.mask_path("tool_input.missing.key", &mask);
+
+        assert_eq!(
+            event.as_value(),
+            serde_json::json!({
+                "tool_input": {"command": "deploy", "env": {"API_KEY": "[redacted]", "REGION": "eu"}},
+                "token": "[redacted]"
+            })
+        );
+    }
+
+    #[test]
+    fn test_mask_path_wildcard() {
+        let mut event = Event::from_json(
+            r#"{"headers": [{"name": "a", "value": "1"}, {"name": "b", "value": "2"}]}"#,
+        )
+        .unwrap();
+
+        event.mask_path("headers.*.value", &Value::String("[redacted]".to_string()));
+        assert_eq!(
+            event.get_nested_values("headers.*.value"),
+            vec!["[redacted]", "[redacted]"]
+        );
+        assert_eq!(event.get_nested_str("headers.1.name").as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn test_remove_path() {
+        let mut event = Event::from_json(
+            r#"{"tool_input": {"command": "ls", "password": "p"}, "args": ["a", "b", "c"], "secret": 1}"#,
+        )
+        .unwrap();
+
+        event.remove_path("tool_input.password");
+        event.remove_path("args.1");
+        event.remove_path("secret");
+        event.remove_path("args.9");
+
+        assert_eq!(
+            event.as_value(),
+            serde_json::json!({"tool_input": {"command": "ls"}, "args": ["a", "c"]})
+        );
+    }
+
+    #[test]
+    fn test_to_json_round_trips() {
+        let json = r#"{"hook_event_name": "Stop", "session_id": "abc", "stop_hook_active": false, "nested": {"n": [1, 2]}}"#;
+        let event = Event::from_json(json).unwrap();
+
+        let out = event.to_json();
+        assert!(out.contains(r#""hook_event_name":"Stop""#));
+        assert_eq!(Event::from_json(&out).unwrap().data, event.data);
+
+        // The derived Serialize impl flattens `data` the same way
+        let serialized = serde_json::to_string(&event).unwrap();
+        let reparsed: Value = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(reparsed, event.as_value());
+    }
+
+    /// JSON with `depth` levels of nested objects: {"a": {"a": ... {} ...}}.
+    fn nested_json(depth: usize) -> String {
+        format!("{}{{}}{}", r#"{"a": "#.repeat(depth - 1), "}".repeat(depth - 1))
+    }
+
+    #[test]
+    fn test_deeply_nested_event_rejected() {
+        assert!(Event::from_json(&nested_json(Event::MAX_DEPTH)).is_ok());
+
+        let err = Event::from_json(&nested_json(Event::MAX_DEPTH + 1)).unwrap_err();
+        assert!(err.to_string().contains("nested deeper than 64 levels"), "{}", err);
+
+        // Far past serde_json's own recursion limit: still an error, not a stack overflow
+        assert!(Event::from_json(&nested_json(100_000)).is_err());
+    }
+
+    #[test]
+    fn test_custom_max_depth() {
+        let value: Value = serde_json::from_str(r#"{"a": [{"b": 1}]}"#).unwrap();
+        assert!(Event::from_value_with_max_depth(value.clone(), 3).is_ok());
+        assert!(Event::from_value_with_max_depth(value, 2).is_err());
+    }
+
+    #[test]
+    fn test_nested_path_longer_than_max_depth() {
+        let event = Event::from_json(&nested_json(10)).unwrap();
+        assert!(event.get_nested_value(&["a"; 8].join(".")).is_some());
+
+        let path = vec!["a"; 100_000].join(".");
+        assert_eq!(event.get_nested_value(&path), None);
+
+        let mut event = event;
+        event.remove_path(&path);
+        assert!(event.get_nested_value(&["a"; 8].join(".")).is_some());
+    }
+
     #[test]
     fn test_invalid_json() {
         let json = r#"{"invalid": }"#;
-        assert!(Event::from_json(json).is_err());
+        let err = Event::from_json(json).unwrap_err();
+        assert!(matches!(err, BoopError::EventParse(_)));
+        assert!(err.to_string().starts_with("Failed to parse event JSON: "), "{}", err);
+
+        let err = Event::from_json("[1, 2]").unwrap_err();
+        assert!(matches!(err, BoopError::EventParse(_)));
+        assert_eq!(err.to_string(), "Failed to parse event JSON: expected an object, got [1,2]");
+
+        let err = Event::from_value_with_max_depth(serde_json::json!({"a": {"b": {}}}), 2).unwrap_err();
+        assert_eq!(err.to_string(), "Failed to parse event JSON: nested deeper than 2 levels");
     }
 }
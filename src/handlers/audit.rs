@@ -0,0 +1,204 @@
+//! JSONL audit log handler.
+//!
+//! Appends every event it handles to a log file, one JSON object per line,
+//! so there is an on-disk history of what boopifier processed.
+
+use crate::error::NotificationError;
+use crate::event::Event;
+use crate::handlers::{Handler, HandlerResult};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Handler that appends events to a JSONL file.
+///
+/// Config:
+/// - `path`: log file (required, tilde-expanded); it and its parent
+///   directories are created as needed
+/// - `max_bytes`: once the file reaches this size, it is moved to
+///   `<path>.1` (replacing any earlier one) before the next append (optional)
+///
+/// Each line is the event as boopifier saw it, after normalization and any
+/// `redact` rules. An exclusive lock on `<path>.lock` is held while rotating
+/// and appending, so concurrent invocations never interleave or lose lines.
+pub struct AuditHandler;
+
+#[async_trait]
+impl Handler for AuditHandler {
+    fn handler_type(&self) -> &str {
+        "audit"
+    }
+
+    async fn handle(&self, event: &Event, config: &HashMap<String, Value>) -> HandlerResult<()> {
+        let path = config
+            .get("path")
+            .and_then(|v| v.as_str())
+            .map(|p| PathBuf::from(shellexpand::tilde(p).as_ref()))
+            .ok_or_else(|| {
+                NotificationError::InvalidConfig("Audit handler requires 'path' configuration".to_string())
+            })?;
+        let max_bytes = config.get("max_bytes").and_then(|v| v.as_u64());
+        let line = format!("{}\n", event.to_json());
+
+        // Waiting for the lock blocks, so keep it off the async runtime
+        tokio::task::spawn_blocking(move || append(&path, &line, max_bytes))
+            .await
+            .map_err(|e| NotificationError::Handler(format!("Audit log task failed: {}", e)))?
+    }
+}
+
+/// Appends `line` to `path` under an exclusive lock, rotating first if the
+/// file has reached `max_bytes`.
+///
+/// The lock is on a `<path>.lock` sidecar rather than the log, because
+/// rotation renames the log: a writer waiting on the old file's lock would
+/// otherwise get it after the rotation and rotate again.
+fn append(path: &Path, line: &str, max_bytes: Option<u64>) -> HandlerResult<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    let lock = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(sibling(path, ".lock"))?;
+    lock.lock()?;
+
+    if max_bytes.is_some_and(|max| fs::metadata(path).is_ok_and(|m| m.len() >= max)) {
+        fs::rename(path, rotated_path(path))?;
+    }
+
+    open(path)?.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+fn open(path: &Path) -> std::io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Where a full log is moved on rotation: `<path>.1`.
+fn rotated_path(path: &Path) -> PathBuf {
+    sibling(path, ".1")
+}
+
+/// `path` with `suffix` appended to its file name.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(path: &Path) -> HashMap<String, Value> {
+        let mut config = HashMap::new();
+        config.insert("path".to_string(), json!(path.to_str().unwrap()));
+        config
+    }
+
+    fn read_events(path: &Path) -> Vec<Event> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| Event::from_json(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_appends_events_as_jsonl() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs/nested/audit.jsonl");
+
+        let events = [
+            json!({"hook_event_name": "SessionStart", "session_id": "abc", "source": "startup"}),
+            json!({"hook_event_name": "PreToolUse", "tool_name": "Bash", "tool_input": {"command": "ls\necho"}}),
+            json!({"hook_event_name": "Stop", "session_id": "abc"}),
+        ];
+        for event in &events {
+            let event = Event::from_value(event.clone()).unwrap();
+            AuditHandler.handle(&event, &config(&path)).await.unwrap();
+        }
+
+        let logged: Vec<Value> = read_events(&path).iter().map(Event::as_value).collect();
+        assert_eq!(logged, events);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_appends_do_not_interleave() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let padding = "x".repeat(64 * 1024);
+
+        let writes = (0..16).map(|i| {
+            let config = config(&path);
+            let event = Event::from_value(json!({"hook_event_name": "Stop", "n": i, "padding": padding})).unwrap();
+            async move { AuditHandler.handle(&event, &config).await }
+        });
+        for result in futures::future::join_all(writes).await {
+            result.unwrap();
+        }
+
+        let mut seen: Vec<i64> = read_events(&path).iter().map(|e| e.get_i64("n").unwrap()).collect();
+        seen.sort();
+        assert_eq!(seen, (0..16).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn test_rotates_at_max_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let mut config = config(&path);
+        config.insert("max_bytes".to_string(), json!(50));
+
+        for n in 0..3 {
+            let event = Event::from_value(json!({"hook_event_name": "Stop", "n": n, "pad": "0123456789"})).unwrap();
+            AuditHandler.handle(&event, &config).await.unwrap();
+        }
+
+        // Each line is over 50 bytes, so every append after the first rotates
+        let current = read_events(&path);
+        let rotated = read_events(&rotated_path(&path));
+        assert_eq!(current.len(), 1);
+        assert_eq!(current[0].get_i64("n"), Some(2));
+        assert_eq!(rotated.len(), 1);
+        assert_eq!(rotated[0].get_i64("n"), Some(1));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_concurrent_rotation_keeps_full_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let event = |n: i64| Event::from_value(json!({"hook_event_name": "Stop", "n": n})).unwrap();
+
+        // Equal-length lines, rotating after every 8
+        let line_len = event(100).to_json().len() as u64 + 1;
+        let mut config = config(&path);
+        config.insert("max_bytes".to_string(), json!(8 * line_len));
+
+        let writes = (100..164).map(|n| {
+            let config = config.clone();
+            async move { AuditHandler.handle(&event(n), &config).await }
+        });
+        for result in futures::future::join_all(writes).await {
+            result.unwrap();
+        }
+
+        // Only ever a full log is rotated, and the last 8 lines are current
+        assert_eq!(read_events(&rotated_path(&path)).len(), 8);
+        assert_eq!(read_events(&path).len(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_missing_path() {
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        let err = AuditHandler.handle(&event, &HashMap::new()).await.unwrap_err();
+        assert!(err.to_string().contains("requires 'path'"));
+    }
+}
@@ -0,0 +1,230 @@
+//! Bash command guard handler.
+//!
+//! Denies `Bash` tool calls whose `tool_input.command` contains a blocked
+//! substring or matches a blocked regex, e.g. `rm -rf` or `git push --force`.
+
+use crate::error::NotificationError;
+use crate::event::Event;
+use crate::handlers::{template, Handler, HandlerResult};
+use crate::hooks::{HandlerOutcome, InteractiveResponse, PermissionDecision};
+use async_trait::async_trait;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Reason given with the deny when `reason` isn't configured.
+const DEFAULT_REASON: &str = "Blocked command: {{blocked}}";
+
+/// Handler that denies dangerous Bash commands.
+///
+/// Config:
+/// - `deny`: substrings that block a command (optional)
+/// - `deny_patterns`: regexes that block a command (optional)
+/// - `reason`: template for the deny reason (optional); `{{blocked}}` is the
+///   substring or pattern that matched. Defaults to "Blocked command: {{blocked}}"
+///
+/// Only `Bash` tool calls are checked. A command that nothing blocks gets no
+/// decision, so other handlers (or the passive default) decide.
+pub struct BashGuardHandler;
+
+#[async_trait]
+impl Handler for BashGuardHandler {
+    fn handler_type(&self) -> &str {
+        "bash_guard"
+    }
+
+    fn decides_permissions(&self) -> bool {
+        true
+    }
+
+    async fn handle(&self, event: &Event, config: &HashMap<String, Value>) -> HandlerResult<()> {
+        self.handle_outcome(event, config).await.map(|_| ())
+    }
+
+    async fn handle_outcome(
+        &self,
+        event: &Event,
+        config: &HashMap<String, Value>,
+    ) -> HandlerResult<HandlerOutcome> {
+        let blocked = match event.tool_name() {
+            Some("Bash") => event
+                .get_nested_str("tool_input.command")
+                .map(|command| blocked_by(&command, config))
+                .transpose()?
+                .flatten(),
+            _ => None,
+        };
+        let Some(blocked) = blocked else {
+            return Ok(HandlerOutcome::Success);
+        };
+
+        let reason = config.get("reason").and_then(|v| v.as_str()).unwrap_or(DEFAULT_REASON);
+        let mut event = event.clone();
+        event.data.insert("blocked".to_string(), Value::String(blocked));
+
+        Ok(HandlerOutcome::Interactive(InteractiveResponse {
+            decision: PermissionDecision::Deny,
+            reason: Some(template::render_template(reason, &event)),
+        }))
+    }
+}
+
+/// Returns the first `deny` substring or `deny_patterns` regex that matches
+/// `command`, substrings first.
+fn blocked_by(command: &str, config: &HashMap<String, Value>) -> HandlerResult<Option<String>> {
+    if let Some(substring) = strings(config, "deny").into_iter().find(|s| command.contains(s)) {
+        return Ok(Some(substring.to_string()));
+    }
+
+    for pattern in strings(config, "deny_patterns") {
+        let regex = Regex::new(pattern).map_err(|e| {
+            NotificationError::InvalidConfig(format!("Invalid deny_patterns regex {:?}: {}", pattern, e))
+        })?;
+        if regex.is_match(command) {
+            return Ok(Some(pattern.to_string()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Configured `deny_patterns` that aren't valid regexes.
+pub(crate) fn invalid_patterns(config: &HashMap<String, Value>) -> Vec<&str> {
+    strings(config, "deny_patterns")
+        .into_iter()
+        .filter(|pattern| Regex::new(pattern).is_err())
+        .collect()
+}
+
+/// The string items of the list under `key`.
+fn strings<'a>(config: &'a HashMap<String, Value>, key: &str) -> Vec<&'a str> {
+    config
+        .get(key)
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config() -> HashMap<String, Value> {
+        let mut config = HashMap::new();
+        config.insert("deny".to_string(), json!(["rm -rf"]));
+        config.insert("deny_patterns".to_string(), json!([r"git\s+push\s+.*(--force|-f\b)"]));
+        config
+    }
+
+    fn bash(command: &str) -> Event {
+        Event::from_value(json!({
+            "hook_event_name": "PreToolUse",
+            "tool_name": "Bash",
+            "tool_input": {"command": command}
+        }))
+        .unwrap()
+    }
+
+    async fn decide(event: &Event, config: &HashMap<String, Value>) -> Option<InteractiveResponse> {
+        match BashGuardHandler.handle_outcome(event, config).await.unwrap() {
+            HandlerOutcome::Interactive(response) => Some(response),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blocks_substring_and_pattern() {
+        let response = decide(&bash("cd /tmp && rm -rf build"), &config()).await.unwrap();
+        assert_eq!(response.decision, PermissionDecision::Deny);
+        assert_eq!(response.reason.as_deref(), Some("Blocked command: rm -rf"));
+
+        let response = decide(&bash("git push origin main --force"), &config()).await.unwrap();
+        assert_eq!(response.decision, PermissionDecision::Deny);
+    }
+
+    #[tokio::test]
+    async fn test_allows_other_commands() {
+        assert!(decide(&bash("git push origin main"), &config()).await.is_none());
+        assert!(decide(&bash("rm build.log"), &config()).await.is_none());
+
+        // Only Bash commands are checked
+        let event = Event::from_value(json!({
+            "hook_event_name": "PreToolUse",
+            "tool_name": "Write",
+            "tool_input": {"command": "rm -rf /", "file_path": "notes.md"}
+        }))
+        .unwrap();
+        assert!(decide(&event, &config()).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_custom_reason() {
+        let mut config = config();
+        config.insert("reason".to_string(), json!("{{tool_name}}: '{{blocked}}' is not allowed"));
+
+        let response = decide(&bash("rm -rf ~"), &config).await.unwrap();
+        assert_eq!(response.reason.as_deref(), Some("Bash: 'rm -rf' is not allowed"));
+    }
+
+    #[tokio::test]
+    async fn test_deny_flows_into_pre_tool_use_response() {
+        use crate::hooks::{tool_use::PreToolUseHook, Hook};
+
+        let event = bash("rm -rf /");
+        let outcome = BashGuardHandler.handle_outcome(&event, &config()).await.unwrap();
+        let response = PreToolUseHook::from_event(&event).unwrap().generate_response(&[outcome]);
+        assert_eq!(response["hookSpecificOutput"]["permissionDecision"], "deny");
+        assert_eq!(response["hookSpecificOutput"]["permissionDecisionReason"], "Blocked command: rm -rf");
+
+        let outcome = BashGuardHandler.handle_outcome(&bash("ls"), &config()).await.unwrap();
+        let response = PreToolUseHook::from_event(&event).unwrap().generate_response(&[outcome]);
+        assert_eq!(response["hookSpecificOutput"]["permissionDecision"], "allow");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_pattern() {
+        let mut config = HashMap::new();
+        config.insert("deny_patterns".to_string(), json!(["("]));
+
+        let err = BashGuardHandler.handle_outcome(&bash("ls"), &config).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid deny_patterns regex"));
+        assert_eq!(invalid_patterns(&config), ["("]);
+        assert!(invalid_patterns(&self::config()).is_empty());
+    }
+
+    /// Runs `event_json` through `process_event` and returns the PreToolUse decision.
+    async fn pre_tool_use_decision(config_json: &str, event_json: &str) -> Value {
+        use crate::hooks::hook_from_event;
+
+        let config = crate::Config::from_json(config_json).unwrap();
+        let outcomes = crate::process_event(event_json, &config, &crate::HandlerRegistry::new())
+            .await
+            .unwrap();
+        let hook = hook_from_event(&Event::from_json(event_json).unwrap()).unwrap();
+        hook.generate_response(&outcomes)["hookSpecificOutput"]["permissionDecision"].clone()
+    }
+
+    #[tokio::test]
+    async fn test_guard_ignores_suppression() {
+        // Quiet all day, digested and debounced: none of it may let the command through
+        let config = r#"{
+            "quiet_hours": [{"start": "00:00", "end": "23:59"}, {"start": "23:59", "end": "00:00"}],
+            "handlers": [{"name": "guard", "type": "bash_guard", "debounce": 60000, "digest": true,
+                          "config": {"deny": ["rm -rf"]}}]
+        }"#;
+        let event = r#"{"hook_event_name": "PreToolUse", "session_id": "guard-test", "tool_name": "Bash", "tool_input": {"command": "rm -rf /"}}"#;
+
+        for _ in 0..2 {
+            assert_eq!(pre_tool_use_decision(config, event).await, "deny");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_broken_guard_asks() {
+        let config = r#"{"handlers": [{"name": "guard", "type": "bash_guard", "config": {"deny_patterns": ["("]}}]}"#;
+        let event = r#"{"hook_event_name": "PreToolUse", "tool_name": "Bash", "tool_input": {"command": "rm -rf /"}}"#;
+
+        assert_eq!(pre_tool_use_decision(config, event).await, "ask");
+    }
+}
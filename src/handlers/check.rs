@@ -0,0 +1,236 @@
+//! Post-edit check handler.
+//!
+//! Runs a project check (e.g. `cargo check` or a test suite) after Claude
+//! edits a file, and feeds failures back to Claude through the PostToolUse
+//! block response.
+
+use crate::error::NotificationError;
+use crate::event::Event;
+use crate::handlers::{handler_command, Handler, HandlerResult};
+use crate::hooks::HandlerOutcome;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::process::Stdio;
+
+/// Tools that change files; other tools never trigger a check.
+///
+/// Compared case-insensitively, so OpenCode's `edit` and `write` count too.
+const EDIT_TOOLS: &[&str] = &["Edit", "MultiEdit", "Write", "NotebookEdit", "patch"];
+
+/// Output fed back to Claude is cut to this many bytes unless `max_output` is set.
+const DEFAULT_MAX_OUTPUT: usize = 4000;
+
+/// Handler that runs a check command after file edits.
+///
+/// Config:
+/// - `command`: executable to run (required, tilde-expanded)
+/// - `args`: list of arguments (optional)
+/// - `paths`: globs the edited file must match, relative to the event's
+///   `cwd` or absolute (optional, defaults to every file)
+/// - `max_output`: bytes of output to feed back (optional, defaults to 4000)
+///
+/// Only `PostToolUse` events for file-editing tools run the command, in the
+/// event's `cwd` with the edited file in `BOOPIFIER_FILE_PATH`. A non-zero
+/// exit returns `Block` with the command's stdout and stderr, which
+/// `PostToolUseHook` sends back to Claude. Skipped edits and passing checks
+/// return `Success`.
+pub struct CheckHandler;
+
+#[async_trait]
+impl Handler for CheckHandler {
+    fn handler_type(&self) -> &str {
+        "check"
+    }
+
+    async fn handle(&self, event: &Event, config: &HashMap<String, Value>) -> HandlerResult<()> {
+        self.handle_outcome(event, config).await.map(|_| ())
+    }
+
+    async fn handle_outcome(
+        &self,
+        event: &Event,
+        config: &HashMap<String, Value>,
+    ) -> HandlerResult<HandlerOutcome> {
+        let command = config
+            .get("command")
+            .and_then(|v| v.as_str())
+            .map(|c| shellexpand::tilde(c).into_owned())
+            .ok_or_else(|| {
+                NotificationError::InvalidConfig("Check handler requires 'command' configuration".to_string())
+            })?;
+
+        let Some(file_path) = edited_file(event, config)? else {
+            return Ok(HandlerOutcome::Success);
+        };
+
+        let args: Vec<&str> = config
+            .get("args")
+            .and_then(|v| v.as_array())
+            .map(|args| args.iter().filter_map(|a| a.as_str()).collect())
+            .unwrap_or_default();
+
+        let mut child = handler_command(&command);
+        child
+            .args(&args)
+            .env("BOOPIFIER_FILE_PATH", file_path)
+            .stdin(Stdio::null());
+        if let Some(cwd) = event.cwd() {
+            child.current_dir(cwd);
+        }
+
+        let output = child
+            .output()
+            .await
+            .map_err(|e| NotificationError::Handler(format!("Failed to execute {}: {}", command, e)))?;
+        if output.status.success() {
+            return Ok(HandlerOutcome::Success);
+        }
+
+        let max_output = config
+            .get("max_output")
+            .and_then(|v| v.as_u64())
+            .map_or(DEFAULT_MAX_OUTPUT, |n| n as usize);
+        let combined = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let mut reason = format!("{} failed ({}) after editing {}", command, output.status, file_path);
+        let combined = truncate(combined.trim(), max_output);
+        if !combined.is_empty() {
+            reason.push_str(":\n");
+            reason.push_str(&combined);
+        }
+        Ok(HandlerOutcome::Block(reason))
+    }
+}
+
+/// Returns the file a PostToolUse edit changed, if it should be checked.
+fn edited_file<'a>(event: &'a Event, config: &HashMap<String, Value>) -> HandlerResult<Option<&'a str>> {
+    let is_edit = event.event_type() == Some("PostToolUse")
+        && event
+            .tool_name()
+            .is_some_and(|tool| EDIT_TOOLS.iter().any(|edit| edit.eq_ignore_ascii_case(tool)));
+    let (true, Some(path), Some(relative)) = (is_edit, event.file_path(), event.relative_file_path()) else {
+        return Ok(None);
+    };
+
+    let Some(patterns) = config.get("paths").and_then(|v| v.as_array()) else {
+        return Ok(Some(path));
+    };
+    for pattern in patterns.iter().filter_map(|p| p.as_str()) {
+        let glob = glob::Pattern::new(pattern).map_err(|e| {
+            NotificationError::InvalidConfig(format!("Invalid paths glob {:?}: {}", pattern, e))
+        })?;
+        if glob.matches(relative) || glob.matches(path) {
+            return Ok(Some(path));
+        }
+    }
+
+    log::debug!("{} doesn't match any check paths; skipping", path);
+    Ok(None)
+}
+
+/// Cuts `text` to at most `max` bytes (on a char boundary), noting the cut.
+fn truncate(text: &str, max: usize) -> String {
+    if text.len() <= max {
+        return text.to_string();
+    }
+
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}\n... (output truncated)", &text[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn edit(tool_name: &str, file_path: &str) -> Event {
+        Event::from_value(json!({
+            "hook_event_name": "PostToolUse",
+            "cwd": std::env::temp_dir(),
+            "tool_name": tool_name,
+            "tool_input": {"file_path": file_path}
+        }))
+        .unwrap()
+    }
+
+    /// Runs `script` with `sh -c`.
+    fn config(script: &str) -> HashMap<String, Value> {
+        let mut config = HashMap::new();
+        config.insert("command".to_string(), json!("sh"));
+        config.insert("args".to_string(), json!(["-c", script]));
+        config
+    }
+
+    #[tokio::test]
+    async fn test_passing_check() {
+        let outcome = CheckHandler.handle_outcome(&edit("Edit", "src/main.rs"), &config("exit 0")).await.unwrap();
+        assert!(matches!(outcome, HandlerOutcome::Success));
+    }
+
+    #[tokio::test]
+    async fn test_failing_check_blocks_with_output() {
+        use crate::hooks::{tool_use::PostToolUseHook, Hook};
+
+        let config = config("echo \"checking $BOOPIFIER_FILE_PATH\"; echo 'error[E0425]: cannot find value `x`' >&2; exit 101");
+        let outcome = CheckHandler.handle_outcome(&edit("Edit", "src/main.rs"), &config).await.unwrap();
+
+        let HandlerOutcome::Block(reason) = &outcome else {
+            panic!("unexpected outcome {:?}", outcome);
+        };
+        assert!(reason.starts_with("sh failed (exit status: 101) after editing src/main.rs:\n"), "{}", reason);
+        assert!(reason.contains("checking src/main.rs"), "{}", reason);
+        assert!(reason.contains("error[E0425]"), "{}", reason);
+
+        let response = PostToolUseHook.generate_response(std::slice::from_ref(&outcome));
+        assert_eq!(response["decision"], "block");
+        assert_eq!(response["reason"], json!(reason));
+    }
+
+    #[tokio::test]
+    async fn test_only_checks_edits_in_scope() {
+        let mut config = config("exit 1");
+        config.insert("paths".to_string(), json!(["src/**/*.rs"]));
+
+        let blocked = |outcome: HandlerOutcome| matches!(outcome, HandlerOutcome::Block(_));
+        assert!(blocked(CheckHandler.handle_outcome(&edit("Write", "src/lib.rs"), &config).await.unwrap()));
+        assert!(!blocked(CheckHandler.handle_outcome(&edit("Edit", "README.md"), &config).await.unwrap()));
+        // Reading a file isn't an edit
+        assert!(!blocked(CheckHandler.handle_outcome(&edit("Read", "src/lib.rs"), &config).await.unwrap()));
+
+        // Only PostToolUse runs the check
+        let event = Event::from_value(json!({
+            "hook_event_name": "PreToolUse",
+            "tool_name": "Edit",
+            "tool_input": {"file_path": "src/lib.rs"}
+        }))
+        .unwrap();
+        assert!(!blocked(CheckHandler.handle_outcome(&event, &config).await.unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_config() {
+        let err = CheckHandler.handle_outcome(&edit("Edit", "a.rs"), &HashMap::new()).await.unwrap_err();
+        assert!(err.to_string().contains("requires 'command'"));
+
+        let mut config = config("exit 0");
+        config.insert("paths".to_string(), json!(["[src"]));
+        let err = CheckHandler.handle_outcome(&edit("Edit", "a.rs"), &config).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid paths glob"));
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("abcdef", 3), "abc\n... (output truncated)");
+        // Never splits a character
+        assert_eq!(truncate("aé", 2), "a\n... (output truncated)");
+    }
+}
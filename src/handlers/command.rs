@@ -0,0 +1,297 @@
+//! External command handler.
+//!
+//! Runs a configured executable with the event JSON on its stdin, so any
+//! script can act as a handler plugin.
+
+use crate::error::NotificationError;
+use crate::event::Event;
+use crate::handlers::{handler_command, Handler, HandlerResult};
+use crate::hooks::{HandlerOutcome, InteractiveResponse, PermissionDecision};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::process::{Output, Stdio};
+use tokio::io::AsyncWriteExt;
+
+/// Handler that runs an external command.
+///
+/// Config:
+/// - `command`: executable to run (required, tilde-expanded)
+/// - `args`: list of arguments (optional)
+///
+/// The child gets the event as JSON on stdin and `BOOPIFIER_HOOK_TYPE`,
+/// `BOOPIFIER_SESSION_ID`, `BOOPIFIER_TOOL_NAME` and `BOOPIFIER_CWD` in its
+/// environment (unset fields are empty). A non-zero exit is a handler error
+/// carrying the child's stderr. If stdout is a JSON object with a `decision`
+/// of "allow", "deny" or "ask" (and an optional `reason`), it becomes an
+/// `Interactive` outcome. Any other non-empty JSON object becomes a
+/// `Response` outcome, shallow-merged into the hook response with the
+/// script's keys winning. Other stdout is ignored.
+pub struct CommandHandler;
+
+#[async_trait]
+impl Handler for CommandHandler {
+    fn handler_type(&self) -> &str {
+        "command"
+    }
+
+    async fn handle(&self, event: &Event, config: &HashMap<String, Value>) -> HandlerResult<()> {
+        run(event, config).await.map(|_| ())
+    }
+
+    async fn handle_outcome(
+        &self,
+        event: &Event,
+        config: &HashMap<String, Value>,
+    ) -> HandlerResult<HandlerOutcome> {
+        let output = run(event, config).await?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        if let Some(response) = parse_decision(&stdout) {
+            return Ok(HandlerOutcome::Interactive(response));
+        }
+
+        Ok(match serde_json::from_str(stdout.trim()) {
+            Ok(Value::Object(fields)) if !fields.is_empty() => HandlerOutcome::Response(fields),
+            _ => HandlerOutcome::Success,
+        })
+    }
+}
+
+/// Runs the configured command and returns its output if it exited successfully.
+async fn run(event: &Event, config: &HashMap<String, Value>) -> HandlerResult<Output> {
+    let command = config
+        .get("command")
+        .and_then(|v| v.as_str())
+        .map(|c| shellexpand::tilde(c).into_owned())
+        .ok_or_else(|| {
+            NotificationError::InvalidConfig("Command handler requires 'command' configuration".to_string())
+        })?;
+
+    let args: Vec<&str> = config
+        .get("args")
+        .and_then(|v| v.as_array())
+        .map(|args| args.iter().filter_map(|a| a.as_str()).collect())
+        .unwrap_or_default();
+
+    let mut child = handler_command(&command)
+        .args(&args)
+        .envs(hook_env(event))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| NotificationError::Handler(format!("Failed to execute {}: {}", command, e)))?;
+
+    // Feed stdin while draining stdout and stderr: writing it all first
+    // deadlocks if the child fills an output pipe before reading the event
+    let stdin = child.stdin.take();
+    let input = event.to_json();
+    let write_input = async move {
+        // A command that ignores stdin may exit before reading it; that's not an error
+        if let Some(mut stdin) = stdin {
+            let _ = stdin.write_all(input.as_bytes()).await;
+        }
+    };
+    let ((), output) = tokio::join!(write_input, child.wait_with_output());
+    let output =
+        output.map_err(|e| NotificationError::Handler(format!("Failed to wait for {}: {}", command, e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(NotificationError::Handler(format!(
+            "{} failed ({}): {}",
+            command,
+            output.status,
+            stderr.trim()
+        )));
+    }
+
+    Ok(output)
+}
+
+/// Environment variables describing the event for the child process.
+fn hook_env(event: &Event) -> [(&'static str, String); 4] {
+    let field = |value: Option<&str>| value.unwrap_or_default().to_string();
+
+    [
+        ("BOOPIFIER_HOOK_TYPE", field(event.event_type())),
+        ("BOOPIFIER_SESSION_ID", field(event.get_str("session_id"))),
+        ("BOOPIFIER_TOOL_NAME", field(event.tool_name())),
+        ("BOOPIFIER_CWD", field(event.get_str("cwd"))),
+    ]
+}
+
+/// Parses `{"decision": "allow" | "deny" | "ask", "reason": "..."}` from stdout.
+fn parse_decision(stdout: &str) -> Option<InteractiveResponse> {
+    let value: Value = serde_json::from_str(stdout.trim()).ok()?;
+    let decision = PermissionDecision::parse(value.get("decision")?.as_str()?)?;
+    let reason = value.get("reason").and_then(|r| r.as_str()).map(str::to_string);
+
+    Some(InteractiveResponse { decision, reason })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pre_tool_use() -> Event {
+        Event::from_json(
+            r#"{"hook_event_name": "PreToolUse", "session_id": "abc", "tool_name": "Bash", "tool_input": {"command": "ls"}}"#,
+        )
+        .unwrap()
+    }
+
+    /// Writes an executable shell script into `dir` and returns a config that runs it.
+    #[cfg(unix)]
+    fn script_config(dir: &tempfile::TempDir, body: &str) -> HashMap<String, Value> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = dir.path().join("hook.sh");
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut config = HashMap::new();
+        config.insert("command".to_string(), Value::String(path.to_str().unwrap().to_string()));
+        config
+    }
+
+    #[test]
+    fn test_parse_decision() {
+        let response = parse_decision(r#"{"decision": "deny", "reason": "no rm"}"#).unwrap();
+        assert_eq!(response.decision, PermissionDecision::Deny);
+        assert_eq!(response.reason.as_deref(), Some("no rm"));
+
+        let response = parse_decision("  {\"decision\": \"ask\"}\n").unwrap();
+        assert_eq!(response.decision, PermissionDecision::Ask);
+        assert_eq!(response.reason, None);
+
+        assert!(parse_decision(r#"{"decision": "maybe"}"#).is_none());
+        assert!(parse_decision(r#"{"status": "ok"}"#).is_none());
+        assert!(parse_decision("done").is_none());
+        assert!(parse_decision("").is_none());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_script_receives_event_and_env() {
+        let dir = tempfile::tempdir().unwrap();
+        let stdin_path = dir.path().join("stdin.json");
+        let env_path = dir.path().join("env.txt");
+        let config = script_config(
+            &dir,
+            &format!(
+                "cat > {}\necho \"$BOOPIFIER_HOOK_TYPE $BOOPIFIER_SESSION_ID $BOOPIFIER_TOOL_NAME\" > {}",
+                stdin_path.display(),
+                env_path.display()
+            ),
+        );
+
+        let outcome = CommandHandler.handle_outcome(&pre_tool_use(), &config).await.unwrap();
+        assert!(matches!(outcome, HandlerOutcome::Success));
+
+        let stdin = Event::from_json(&std::fs::read_to_string(stdin_path).unwrap()).unwrap();
+        assert_eq!(stdin.data, pre_tool_use().data);
+        assert_eq!(std::fs::read_to_string(env_path).unwrap(), "PreToolUse abc Bash\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_script_decision_becomes_interactive() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = script_config(&dir, r#"echo '{"decision": "deny", "reason": "blocked by script"}'"#);
+
+        let outcome = CommandHandler.handle_outcome(&pre_tool_use(), &config).await.unwrap();
+        match outcome {
+            HandlerOutcome::Interactive(response) => {
+                assert_eq!(response.decision, PermissionDecision::Deny);
+                assert_eq!(response.reason.as_deref(), Some("blocked by script"));
+            }
+            other => panic!("unexpected outcome {:?}", other),
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_script_json_merges_into_response() {
+        use crate::hooks::{hook_from_event, merge_responses};
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = script_config(
+            &dir,
+            r#"echo '{"hookSpecificOutput": {"hookEventName": "SessionStart", "additionalContext": "from script"}}'"#,
+        );
+        let event = Event::from_json(r#"{"hook_event_name": "SessionStart"}"#).unwrap();
+
+        let outcome = CommandHandler.handle_outcome(&event, &config).await.unwrap();
+        assert!(matches!(outcome, HandlerOutcome::Response(_)));
+
+        let hook = hook_from_event(&event).unwrap();
+        let outcomes = [HandlerOutcome::Context("from boopifier".to_string()), outcome];
+        assert_eq!(
+            merge_responses(hook.as_ref(), &outcomes)["hookSpecificOutput"]["additionalContext"],
+            "from script"
+        );
+
+        // Plain text and empty objects don't produce a response
+        for body in ["echo done", "echo '{}'"] {
+            let config = script_config(&dir, body);
+            let outcome = CommandHandler.handle_outcome(&event, &config).await.unwrap();
+            assert!(matches!(outcome, HandlerOutcome::Success));
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_large_event_with_chatty_script() {
+        // More than a pipe buffer each way, with output written before stdin is read
+        let dir = tempfile::tempdir().unwrap();
+        let config = script_config(&dir, "head -c 200000 /dev/zero\ncat > /dev/null");
+        let event = Event::from_value(serde_json::json!({
+            "hook_event_name": "PostToolUse",
+            "tool_name": "Write",
+            "tool_input": {"file_path": "big.txt", "content": "x".repeat(200_000)}
+        }))
+        .unwrap();
+
+        let handled = CommandHandler.handle_outcome(&event, &config);
+        let outcome = tokio::time::timeout(std::time::Duration::from_secs(5), handled)
+            .await
+            .expect("command handler deadlocked")
+            .unwrap();
+        assert!(matches!(outcome, HandlerOutcome::Success));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_script_failure_reports_stderr() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = script_config(&dir, "echo 'lint failed' >&2\nexit 3");
+
+        let err = CommandHandler.handle_outcome(&pre_tool_use(), &config).await.unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("lint failed"), "{}", message);
+        assert!(message.contains("exit status: 3"), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn test_args_are_passed() {
+        let mut config = HashMap::new();
+        config.insert("command".to_string(), Value::String("sh".to_string()));
+        config.insert("args".to_string(), serde_json::json!(["-c", "exit 4"]));
+
+        let err = CommandHandler.handle(&pre_tool_use(), &config).await.unwrap_err();
+        assert!(err.to_string().contains("sh failed"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_command() {
+        let err = CommandHandler.handle(&pre_tool_use(), &HashMap::new()).await.unwrap_err();
+        assert!(err.to_string().contains("requires 'command'"));
+
+        let mut config = HashMap::new();
+        config.insert("command".to_string(), Value::String("boopifier-no-such-command".to_string()));
+        let err = CommandHandler.handle(&pre_tool_use(), &config).await.unwrap_err();
+        assert!(err.to_string().contains("Failed to execute"));
+    }
+}
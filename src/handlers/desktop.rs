@@ -4,14 +4,17 @@
 
 use crate::error::NotificationError;
 use crate::event::Event;
-use crate::handlers::{Handler, HandlerResult};
+use crate::handlers::{default_text, spawn_detached, template, Handler, HandlerResult};
+use crate::hooks::notification::NotificationHook;
 use async_trait::async_trait;
 use notify_rust::{Notification, Timeout};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::ffi::OsString;
+use std::time::Duration;
 
 #[cfg(target_os = "linux")]
-use notify_rust::Urgency;
+use notify_rust::{Hint, Urgency};
 
 /// Sets notification urgency (Linux/freedesktop only)
 #[cfg(target_os = "linux")]
@@ -28,8 +31,479 @@ fn apply_urgency(_notification: &mut Notification, _urgency: &str) {
     // Urgency is not supported on macOS/Windows (freedesktop spec only)
 }
 
+/// Tags the notification so servers that support it (e.g. dunst, Ubuntu's
+/// notify-osd) replace the previous one with the same tag instead of
+/// stacking another (Linux only).
+#[cfg(target_os = "linux")]
+fn apply_group(notification: &mut Notification, group: &str) {
+    notification
+        .hint(Hint::Custom("x-dunst-stack-tag".to_string(), group.to_string()))
+        .hint(Hint::Custom("x-canonical-private-synchronous".to_string(), group.to_string()));
+}
+
+#[cfg(not(target_os = "linux"))]
+fn apply_group(_notification: &mut Notification, _group: &str) {
+    // notify-rust has no grouping hint on macOS/Windows
+}
+
+/// Whether notifications can be grouped on this platform.
+fn grouping_supported() -> bool {
+    cfg!(target_os = "linux")
+}
+
+/// Derives the grouping tag for an event from its session id.
+///
+/// `Event` normalizes OpenCode's `sessionID` and `session.id` to
+/// `session_id`, so all spellings give the same tag.
+fn grouping_key(event: &Event) -> Option<String> {
+    event
+        .get_str("session_id")
+        .filter(|id| !id.is_empty())
+        .map(|id| format!("boopifier-{}", id))
+}
+
+/// Resolves the grouping tag when `group_by_session` is set and the platform
+/// supports grouping.
+fn resolve_group(config: &HashMap<String, Value>, event: &Event) -> Option<String> {
+    let enabled = config.get("group_by_session").and_then(|v| v.as_bool()).unwrap_or(false);
+    if !enabled || !grouping_supported() {
+        return None;
+    }
+
+    grouping_key(event)
+}
+
+/// Action id notification servers send when the notification body is clicked.
+const DEFAULT_ACTION: &str = "default";
+
+/// How long to wait for a click on a notification that never expires (`timeout: 0`).
+const PERSISTENT_ACTION_WAIT: Duration = Duration::from_secs(5);
+
+/// What to do when the user clicks a notification.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub enum ClickAction {
+    /// Open a URL with the desktop's default handler
+    Url(String),
+    /// Run a shell command
+    Command(String),
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+impl ClickAction {
+    /// Parses `{"url": "..."}` or `{"command": "..."}`.
+    fn from_value(value: &Value) -> Option<Self> {
+        if let Some(url) = value.get("url").and_then(|v| v.as_str()) {
+            return Some(Self::Url(url.to_string()));
+        }
+        value
+            .get("command")
+            .and_then(|v| v.as_str())
+            .map(|command| Self::Command(command.to_string()))
+    }
+
+    /// Starts the action without waiting for it to finish (see `spawn_detached`).
+    fn run(&self) -> std::io::Result<()> {
+        let mut command = match self {
+            Self::Url(url) => {
+                let mut command = std::process::Command::new("xdg-open");
+                command.arg(url);
+                command
+            }
+            Self::Command(line) => {
+                let mut command = std::process::Command::new("sh");
+                command.arg("-c").arg(line);
+                command
+            }
+        };
+        spawn_detached(&mut command).map(|_| ())
+    }
+}
+
+/// Whether the platform's notification server reports clicks back to us.
+///
+/// Only freedesktop (Linux) notifications support actions; elsewhere any
+/// action config is ignored.
+fn actions_supported() -> bool {
+    cfg!(target_os = "linux")
+}
+
+/// Resolves the click action for an event.
+///
+/// Uses the `hook_actions` mapping (e.g. `{"Stop": {"command": "code ."}}`)
+/// when it has an entry for `hook_type`, otherwise `action`. Returns `None`
+/// when nothing is configured or the platform doesn't support actions.
+fn resolve_action(config: &HashMap<String, Value>, hook_type: Option<&str>) -> Option<ClickAction> {
+    if !actions_supported() {
+        return None;
+    }
+
+    hook_type
+        .and_then(|hook_type| config.get("hook_actions").and_then(|v| v.get(hook_type)))
+        .or_else(|| config.get("action"))
+        .and_then(ClickAction::from_value)
+}
+
+/// Error for a notification the platform's notification server couldn't show,
+/// e.g. on headless Linux without a D-Bus session.
+fn backend_unavailable(detail: impl std::fmt::Display) -> NotificationError {
+    NotificationError::SendFailed(format!("notification backend unavailable: {}", detail))
+}
+
+/// How long to wait for a click: the notification timeout, or
+/// `PERSISTENT_ACTION_WAIT` if it never expires.
+fn action_wait(timeout_ms: u32) -> Duration {
+    match timeout_ms {
+        0 => PERSISTENT_ACTION_WAIT,
+        ms => Duration::from_millis(ms.into()),
+    }
+}
+
+/// Shows the notification and runs `action` if it is clicked within `wait`.
+///
+/// Waiting keeps the hook open, so it is bounded by `action_wait`.
+#[cfg(target_os = "linux")]
+async fn show_with_action(
+    notification: Notification,
+    action: ClickAction,
+    wait: Duration,
+) -> HandlerResult<()> {
+    let handle = notification.show().map_err(backend_unavailable)?;
+
+    // A detached thread rather than spawn_blocking: runtime shutdown waits for
+    // blocking tasks, and servers may never close the notification (e.g. for
+    // critical urgency), which would keep the hook process alive
+    let (clicked_tx, clicked) = futures::channel::oneshot::channel();
+    std::thread::spawn(move || {
+        let mut clicked = false;
+        handle.wait_for_action(|id| clicked = id == DEFAULT_ACTION);
+        let _ = clicked_tx.send(clicked);
+    });
+
+    if let Ok(Ok(true)) = tokio::time::timeout(wait, clicked).await {
+        action
+            .run()
+            .map_err(|e| NotificationError::Handler(format!("Failed to run click action: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+async fn show_with_action(
+    notification: Notification,
+    _action: ClickAction,
+    _wait: Duration,
+) -> HandlerResult<()> {
+    notification.show().map(|_| ()).map_err(backend_unavailable)
+}
+
+/// Options for a desktop notification beyond its title and body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotifyOptions {
+    /// Application name shown by the notification server
+    pub app_name: String,
+    /// How long the notification stays up, in milliseconds
+    pub timeout_ms: u32,
+    /// "low", "normal" or "critical" (Linux only)
+    pub urgency: String,
+    /// Icon to show, if the platform shows icons
+    pub icon: Option<Icon>,
+    /// What to do when the notification is clicked, if the platform reports clicks
+    pub action: Option<ClickAction>,
+    /// System sound to play, if the platform has named sounds
+    pub sound_name: Option<String>,
+    /// Tag that collapses notifications from the same session, if the
+    /// platform groups notifications
+    pub group: Option<String>,
+}
+
+/// Sends desktop notifications.
+///
+/// The desktop handler talks to the OS only through this trait, so tests can
+/// swap in a recording notifier.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    /// Shows a notification.
+    ///
+    /// Fails with `NotificationError::SendFailed` if no notification server
+    /// is available.
+    async fn notify(&self, title: &str, body: &str, opts: &NotifyOptions) -> HandlerResult<()>;
+}
+
+/// Notifier backed by notify-rust and the platform's notification server.
+pub struct NotifyRustNotifier;
+
+#[async_trait]
+impl Notifier for NotifyRustNotifier {
+    async fn notify(&self, title: &str, body: &str, opts: &NotifyOptions) -> HandlerResult<()> {
+        let mut notification = Notification::new();
+        notification
+            .appname(&opts.app_name)
+            .summary(title)
+            .body(body)
+            .timeout(Timeout::Milliseconds(opts.timeout_ms));
+
+        // Set urgency (Linux only)
+        apply_urgency(&mut notification, &opts.urgency);
+
+        if let Some(icon) = &opts.icon {
+            icon.apply(&mut notification);
+        }
+
+        if let Some(sound_name) = &opts.sound_name {
+            notification.sound_name(sound_name);
+        }
+
+        if let Some(group) = &opts.group {
+            apply_group(&mut notification, group);
+        }
+
+        match &opts.action {
+            Some(action) => {
+                notification.action(DEFAULT_ACTION, "Open");
+                show_with_action(notification, action.clone(), action_wait(opts.timeout_ms)).await
+            }
+            None => notification.show().map(|_| ()).map_err(backend_unavailable),
+        }
+    }
+}
+
+/// Icon shown for a notification.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Icon {
+    /// A freedesktop icon name, e.g. "dialog-error"
+    Named(String),
+    /// An image file (tilde-expanded)
+    Path(String),
+}
+
+impl Icon {
+    /// Treats values containing `/` or starting with `~` as file paths, anything else as a name.
+    fn parse(value: &str) -> Self {
+        if value.contains('/') || value.starts_with('~') {
+            Self::Path(shellexpand::tilde(value).into_owned())
+        } else {
+            Self::Named(value.to_string())
+        }
+    }
+
+    fn apply(&self, notification: &mut Notification) {
+        match self {
+            Self::Named(name) => {
+                notification.icon(name);
+            }
+            #[cfg(target_os = "linux")]
+            Self::Path(path) => {
+                notification.image_path(path);
+            }
+            #[cfg(not(target_os = "linux"))]
+            Self::Path(path) => {
+                notification.icon(path);
+            }
+        }
+    }
+}
+
+/// Icon used when neither `hook_icons` nor `icon` is configured.
+const DEFAULT_ICON: &str = "dialog-information";
+
+/// Whether the platform's notification server shows per-notification icons.
+///
+/// macOS always shows the sending application's icon, so icon config is
+/// ignored there.
+fn icons_supported() -> bool {
+    !cfg!(target_os = "macos")
+}
+
+/// Resolves the icon for an event.
+///
+/// Uses the `hook_icons` mapping (e.g. `{"Stop": "~/icons/check.png"}`) when
+/// it has an entry for `hook_type`, then a fixed `icon`, then `DEFAULT_ICON`.
+/// Returns `None` when the platform ignores icons.
+fn resolve_icon(config: &HashMap<String, Value>, hook_type: Option<&str>) -> Option<Icon> {
+    if !icons_supported() {
+        return None;
+    }
+
+    let mapped = hook_type.and_then(|hook_type| {
+        config
+            .get("hook_icons")
+            .and_then(|v| v.get(hook_type))
+            .and_then(|v| v.as_str())
+    });
+
+    let icon = mapped
+        .or_else(|| config.get("icon").and_then(|v| v.as_str()))
+        .unwrap_or(DEFAULT_ICON);
+
+    Some(Icon::parse(icon))
+}
+
+/// macOS built-in alert sounds (from /System/Library/Sounds).
+pub(crate) const MACOS_SOUNDS: &[&str] = &[
+    "Basso", "Blow", "Bottle", "Frog", "Funk", "Glass", "Hero", "Morse", "Ping", "Pop", "Purr", "Sosumi",
+    "Submarine", "Tink",
+];
+
+/// Whether the platform plays a named system sound with the notification.
+///
+/// Only macOS has a fixed set of alert sounds; `sound_name` and
+/// `hook_sounds` are ignored elsewhere.
+fn sound_names_supported() -> bool {
+    cfg!(target_os = "macos")
+}
+
+/// Looks up the configured system sound for an event, on any platform.
+///
+/// Uses the `hook_sounds` mapping (e.g. `{"Stop": "Glass"}`) when it has an
+/// entry for `hook_type`, then a fixed `sound_name`. Names are matched
+/// case-insensitively against `MACOS_SOUNDS`.
+fn lookup_sound_name(
+    config: &HashMap<String, Value>,
+    hook_type: Option<&str>,
+) -> HandlerResult<Option<&'static str>> {
+    let mapped = hook_type.and_then(|hook_type| {
+        config
+            .get("hook_sounds")
+            .and_then(|v| v.get(hook_type))
+            .and_then(|v| v.as_str())
+    });
+
+    let Some(name) = mapped.or_else(|| config.get("sound_name").and_then(|v| v.as_str())) else {
+        return Ok(None);
+    };
+
+    known_sound_name(name).map(Some).ok_or_else(|| {
+        NotificationError::InvalidConfig(format!(
+            "Unknown macOS sound {:?} (expected one of {})",
+            name,
+            MACOS_SOUNDS.join(", ")
+        ))
+    })
+}
+
+/// Returns the canonical spelling of a macOS alert sound name.
+fn known_sound_name(name: &str) -> Option<&'static str> {
+    MACOS_SOUNDS.iter().copied().find(|known| known.eq_ignore_ascii_case(name))
+}
+
+/// Configured `sound_name` and `hook_sounds` values that aren't macOS alert sounds.
+pub(crate) fn unknown_sound_names(config: &HashMap<String, Value>) -> Vec<&str> {
+    let fixed = config.get("sound_name").and_then(|v| v.as_str());
+    let mapped = config
+        .get("hook_sounds")
+        .and_then(|v| v.as_object())
+        .into_iter()
+        .flat_map(|map| map.values().filter_map(|v| v.as_str()));
+
+    fixed
+        .into_iter()
+        .chain(mapped)
+        .filter(|name| known_sound_name(name).is_none())
+        .collect()
+}
+
+/// Resolves the system sound for an event; `None` where the platform has no named sounds.
+fn resolve_sound_name(
+    config: &HashMap<String, Value>,
+    hook_type: Option<&str>,
+) -> HandlerResult<Option<String>> {
+    if !sound_names_supported() {
+        return Ok(None);
+    }
+
+    Ok(lookup_sound_name(config, hook_type)?.map(str::to_string))
+}
+
+/// What the session looks like, to decide whether a desktop notification
+/// would be seen at all.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Environment {
+    /// Connected over SSH (`SSH_CONNECTION` or `SSH_TTY` is set)
+    pub ssh: bool,
+    /// A display server is available (`DISPLAY` or `WAYLAND_DISPLAY` is set)
+    pub display: bool,
+    /// A D-Bus session bus is available (`DBUS_SESSION_BUS_ADDRESS` is set)
+    pub dbus: bool,
+}
+
+impl Environment {
+    /// An environment that can show desktop notifications.
+    pub const DESKTOP: Self = Self {
+        ssh: false,
+        display: true,
+        dbus: true,
+    };
+
+    /// Detects the environment from the process's environment variables.
+    pub fn detect() -> Self {
+        Self::from_vars(|name| std::env::var_os(name))
+    }
+
+    /// Detects the environment from `var`, which looks up a variable by name.
+    pub fn from_vars(var: impl Fn(&str) -> Option<OsString>) -> Self {
+        let set = |name: &str| var(name).is_some_and(|value| !value.is_empty());
+
+        Self {
+            ssh: set("SSH_CONNECTION") || set("SSH_TTY"),
+            display: set("DISPLAY") || set("WAYLAND_DISPLAY"),
+            dbus: set("DBUS_SESSION_BUS_ADDRESS"),
+        }
+    }
+
+    /// Whether nobody would see a desktop notification: over SSH, or on
+    /// Linux with neither a display nor a D-Bus session.
+    pub fn is_headless(&self) -> bool {
+        self.ssh || (cfg!(target_os = "linux") && !self.display && !self.dbus)
+    }
+}
+
+/// Default title template; `{{app}}` and `{{hook_type}}` are filled in per event.
+const DEFAULT_TITLE: &str = "{{app}} — {{hook_type}}";
+
+/// Default application name shown by the notification server.
+const DEFAULT_APP_NAME: &str = "Claude Code";
+
 /// Handler for desktop notifications.
-pub struct DesktopHandler;
+///
+/// In headless sessions (see `Environment::is_headless`) notifications are
+/// printed to stderr instead, unless `headless_stderr` is set to false.
+pub struct DesktopHandler {
+    notifier: Box<dyn Notifier>,
+    environment: Environment,
+}
+
+impl DesktopHandler {
+    /// Creates a handler that shows notifications with notify-rust, or
+    /// prints them if the session is headless.
+    pub fn new() -> Self {
+        Self::with_notifier(Box::new(NotifyRustNotifier)).with_environment(Environment::detect())
+    }
+
+    /// Creates a handler that sends notifications through `notifier`.
+    ///
+    /// Headless detection is skipped; use `with_environment` to apply it.
+    pub fn with_notifier(notifier: Box<dyn Notifier>) -> Self {
+        Self {
+            notifier,
+            environment: Environment::DESKTOP,
+        }
+    }
+
+    /// Uses `environment` instead of the detected one to decide whether the
+    /// session is headless.
+    pub fn with_environment(mut self, environment: Environment) -> Self {
+        self.environment = environment;
+        self
+    }
+}
+
+impl Default for DesktopHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 #[async_trait]
 impl Handler for DesktopHandler {
@@ -38,28 +512,53 @@ impl Handler for DesktopHandler {
     }
 
     async fn handle(&self, event: &Event, config: &HashMap<String, Value>) -> HandlerResult<()> {
-        // Extract configuration with defaults
-        let summary = get_string(config, "summary", "Claude Code Notification");
-        let body = render_template(config.get("body"), event);
-        let timeout_ms = get_u32(config, "timeout", 5000);
-        let urgency = get_string(config, "urgency", "normal");
+        notify_event(self.notifier.as_ref(), self.environment, event, config).await
+    }
+}
 
-        // Build and send notification
-        let mut notification = Notification::new();
-        notification
-            .appname("Claude Code")
-            .summary(&summary)
-            .body(&body)
-            .timeout(Timeout::Milliseconds(timeout_ms));
+/// Builds the notification for an event and sends it through `notifier`.
+///
+/// In a headless `environment` the notification is printed to stderr
+/// instead, unless `headless_stderr` is false. If the notification server is
+/// unavailable and `stderr_fallback` is set, the notification is printed to
+/// stderr too and the handler succeeds.
+async fn notify_event(
+    notifier: &dyn Notifier,
+    environment: Environment,
+    event: &Event,
+    config: &HashMap<String, Value>,
+) -> HandlerResult<()> {
+    // Extract configuration with defaults
+    let app_name = get_string(config, "app_name", DEFAULT_APP_NAME);
+    let title = render_title(config, event, &app_name);
+    let body = render_body(config.get("body"), event);
+    let stderr_fallback = config.get("stderr_fallback").and_then(|v| v.as_bool()).unwrap_or(false);
+    let headless_stderr = config.get("headless_stderr").and_then(|v| v.as_bool()).unwrap_or(true);
 
-        // Set urgency (Linux only)
-        apply_urgency(&mut notification, &urgency);
+    // stdout carries the hook response, so the printed notification goes to stderr
+    if headless_stderr && environment.is_headless() {
+        log::info!("Headless session ({:?}); printing notification to stderr", environment);
+        eprintln!("[boopifier] {}: {}", title, body);
+        return Ok(());
+    }
 
-        notification
-            .show()
-            .map_err(|e| NotificationError::SendFailed(format!("Failed to send desktop notification: {}", e)))?;
+    let opts = NotifyOptions {
+        timeout_ms: get_u32(config, "timeout", 5000),
+        urgency: resolve_urgency(config, event.event_type()).to_string(),
+        icon: resolve_icon(config, event.event_type()),
+        action: resolve_action(config, event.event_type()),
+        sound_name: resolve_sound_name(config, event.event_type())?,
+        group: resolve_group(config, event),
+        app_name,
+    };
 
-        Ok(())
+    match notifier.notify(&title, &body, &opts).await {
+        Err(NotificationError::SendFailed(detail)) if stderr_fallback => {
+            log::warn!("Desktop notification failed ({}); printing to stderr", detail);
+            eprintln!("[boopifier] {}: {}", title, body);
+            Ok(())
+        }
+        result => result,
     }
 }
 
@@ -81,44 +580,423 @@ fn get_u32(config: &HashMap<String, Value>, key: &str, default: u32) -> u32 {
         .unwrap_or(default)
 }
 
-/// Renders a template string with event data.
-fn render_template(template: Option<&Value>, event: &Event) -> String {
-    let template_str = match template {
-        Some(Value::String(s)) => s,
-        _ => return format!("{:?}", event.data),
-    };
+/// Default urgency for a hook type: permission prompts need attention now,
+/// completion and notification hooks are normal, and routine lifecycle hooks are low.
+fn default_urgency(hook_type: Option<&str>) -> &'static str {
+    match hook_type {
+        Some("PermissionRequest") => "critical",
+        Some("Stop" | "SubagentStop" | "Notification") | None => "normal",
+        Some(_) => "low",
+    }
+}
 
-    let mut result = template_str.clone();
+/// Resolves the urgency ("low", "normal" or "critical") for an event.
+///
+/// Uses the `hook_urgency` mapping (e.g. `{"Stop": "low"}`) when it has an
+/// entry for `hook_type`, then a fixed `urgency`, then `default_urgency`.
+/// Unrecognized values are treated as "normal".
+fn resolve_urgency<'a>(config: &'a HashMap<String, Value>, hook_type: Option<&str>) -> &'a str {
+    let mapped = hook_type.and_then(|hook_type| {
+        config
+            .get("hook_urgency")
+            .and_then(|v| v.get(hook_type))
+            .and_then(|v| v.as_str())
+    });
 
-    // Simple template substitution: {{field_name}}
-    for (key, value) in &event.data {
-        let placeholder = format!("{{{{{}}}}}", key);
-        if result.contains(&placeholder) {
-            let value_str = match value {
-                Value::String(s) => s.clone(),
-                other => other.to_string(),
-            };
-            result = result.replace(&placeholder, &value_str);
-        }
+    let urgency = mapped
+        .or_else(|| config.get("urgency").and_then(|v| v.as_str()))
+        .unwrap_or_else(|| default_urgency(hook_type));
+
+    match urgency {
+        "low" | "normal" | "critical" => urgency,
+        _ => "normal",
+    }
+}
+
+/// Renders the notification title.
+///
+/// A literal `summary` wins for backward compatibility; otherwise the `title`
+/// template (default `{{app}} — {{hook_type}}`) is rendered like any other
+/// template, with `{{app}}` and `{{hook_type}}` filled in first.
+fn render_title(config: &HashMap<String, Value>, event: &Event, app_name: &str) -> String {
+    if let Some(summary) = config.get("summary").and_then(|v| v.as_str()) {
+        return summary.to_string();
     }
 
-    result
+    let title = get_string(config, "title", DEFAULT_TITLE);
+    let hook_type = event.event_type().unwrap_or("Notification");
+    template::render_template_with(&title, event, &[("app", app_name), ("hook_type", hook_type)])
+}
+
+/// Renders the `body` template with event data.
+///
+/// Without a template, Notification events show Claude Code's `message` and
+/// other events show the same default text as the other handlers.
+fn render_body(template: Option<&Value>, event: &Event) -> String {
+    let template_str = match template {
+        Some(Value::String(s)) => s,
+        _ if event.event_type() == Some("Notification") => {
+            return NotificationHook::from_event(event).message().to_string();
+        }
+        _ => return default_text(event),
+    };
+
+    template::render_template(template_str, event)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::event::Event;
+    use std::sync::{Arc, Mutex};
 
     #[test]
     fn test_template_rendering() {
         let event = Event::from_json(r#"{"status": "success", "tool": "bash"}"#).unwrap();
         let template = Value::String("Tool {{tool}} completed with status {{status}}".to_string());
 
-        let result = render_template(Some(&template), &event);
+        let result = render_body(Some(&template), &event);
         assert_eq!(result, "Tool bash completed with status success");
     }
 
+    #[test]
+    fn test_default_body_uses_notification_message() {
+        let event = Event::from_json(
+            r#"{"hook_event_name": "Notification", "message": "Claude needs your permission to use Bash"}"#,
+        )
+        .unwrap();
+        assert_eq!(render_body(None, &event), "Claude needs your permission to use Bash");
+
+        let event = Event::from_json(r#"{"hook_event_name": "Notification"}"#).unwrap();
+        assert_eq!(render_body(None, &event), "Claude needs your attention");
+
+        let event = Event::from_json(r#"{"hook_event_name": "Stop", "reason": "done"}"#).unwrap();
+        assert_eq!(render_body(None, &event), "Claude Code Stop: done");
+    }
+
+    #[test]
+    fn test_title_from_hook_type() {
+        let config = HashMap::new();
+
+        for hook in ["Stop", "Notification", "PreToolUse"] {
+            let event = Event::from_json(&format!(r#"{{"hook_event_name": "{}"}}"#, hook)).unwrap();
+            assert_eq!(
+                render_title(&config, &event, DEFAULT_APP_NAME),
+                format!("Claude Code — {}", hook)
+            );
+        }
+
+        let event = Event::from_json(r#"{"message": "hi"}"#).unwrap();
+        assert_eq!(render_title(&config, &event, DEFAULT_APP_NAME), "Claude Code — Notification");
+    }
+
+    #[test]
+    fn test_title_template() {
+        let event = Event::from_json(r#"{"hook_event_name": "Stop", "cwd": "/work/app"}"#).unwrap();
+        let mut config = HashMap::new();
+        config.insert("title".to_string(), Value::String("{{hook_type}} in {{cwd}} ({{app}})".to_string()));
+
+        assert_eq!(render_title(&config, &event, "Boop"), "Stop in /work/app (Boop)");
+
+        // Single braces are literal, as in every other template
+        config.insert("title".to_string(), Value::String("{app} {{app}}".to_string()));
+        assert_eq!(render_title(&config, &event, "Boop"), "{app} Boop");
+
+        config.insert("summary".to_string(), Value::String("Fixed title".to_string()));
+        assert_eq!(render_title(&config, &event, "Boop"), "Fixed title");
+    }
+
+    #[test]
+    fn test_default_urgency_per_hook_type() {
+        let config = HashMap::new();
+        assert_eq!(resolve_urgency(&config, Some("PermissionRequest")), "critical");
+        assert_eq!(resolve_urgency(&config, Some("Stop")), "normal");
+        assert_eq!(resolve_urgency(&config, Some("Notification")), "normal");
+        assert_eq!(resolve_urgency(&config, Some("SessionStart")), "low");
+        assert_eq!(resolve_urgency(&config, Some("PreCompact")), "low");
+        assert_eq!(resolve_urgency(&config, None), "normal");
+    }
+
+    #[test]
+    fn test_urgency_config_precedence() {
+        let mut config = HashMap::new();
+        config.insert("urgency".to_string(), Value::String("low".to_string()));
+        config.insert("hook_urgency".to_string(), serde_json::json!({"Stop": "critical", "Notification": "loud"}));
+
+        assert_eq!(resolve_urgency(&config, Some("Stop")), "critical");
+        assert_eq!(resolve_urgency(&config, Some("PermissionRequest")), "low");
+        assert_eq!(resolve_urgency(&config, Some("Notification")), "normal");
+    }
+
+    #[test]
+    fn test_click_action_parsing() {
+        assert_eq!(
+            ClickAction::from_value(&serde_json::json!({"url": "https://example.com"})),
+            Some(ClickAction::Url("https://example.com".to_string()))
+        );
+        assert_eq!(
+            ClickAction::from_value(&serde_json::json!({"command": "code ."})),
+            Some(ClickAction::Command("code .".to_string()))
+        );
+        assert_eq!(ClickAction::from_value(&serde_json::json!({"label": "Open"})), None);
+        assert_eq!(ClickAction::from_value(&serde_json::json!("https://example.com")), None);
+    }
+
+    #[test]
+    fn test_resolve_action_per_hook() {
+        let mut config = HashMap::new();
+        config.insert("action".to_string(), serde_json::json!({"url": "https://example.com"}));
+        config.insert("hook_actions".to_string(), serde_json::json!({"Stop": {"command": "code ."}}));
+
+        let stop = resolve_action(&config, Some("Stop"));
+        let other = resolve_action(&config, Some("Notification"));
+
+        if actions_supported() {
+            assert_eq!(stop, Some(ClickAction::Command("code .".to_string())));
+            assert_eq!(other, Some(ClickAction::Url("https://example.com".to_string())));
+        } else {
+            assert_eq!(stop, None);
+            assert_eq!(other, None);
+        }
+        assert_eq!(resolve_action(&HashMap::new(), Some("Stop")), None);
+    }
+
+    #[test]
+    fn test_action_wait() {
+        assert_eq!(action_wait(2000), Duration::from_secs(2));
+        // Notifications that never expire still get a bounded wait
+        assert_eq!(action_wait(0), PERSISTENT_ACTION_WAIT);
+    }
+
+    #[test]
+    fn test_actions_supported_only_on_linux() {
+        assert_eq!(actions_supported(), cfg!(target_os = "linux"));
+    }
+
+    #[test]
+    fn test_icon_parse() {
+        assert_eq!(Icon::parse("dialog-error"), Icon::Named("dialog-error".to_string()));
+        assert_eq!(Icon::parse("/usr/share/icons/check.png"), Icon::Path("/usr/share/icons/check.png".to_string()));
+        assert_eq!(Icon::parse("icons/check.png"), Icon::Path("icons/check.png".to_string()));
+
+        let home = shellexpand::tilde("~").into_owned();
+        assert_eq!(Icon::parse("~/check.png"), Icon::Path(format!("{}/check.png", home)));
+    }
+
+    #[test]
+    fn test_resolve_icon_per_hook() {
+        let mut config = HashMap::new();
+        config.insert("hook_icons".to_string(), serde_json::json!({"Stop": "/icons/check.png", "SessionError": "dialog-error"}));
+
+        let stop = resolve_icon(&config, Some("Stop"));
+        let error = resolve_icon(&config, Some("SessionError"));
+        let other = resolve_icon(&config, Some("Notification"));
+
+        config.insert("icon".to_string(), Value::String("boop".to_string()));
+        let fixed = resolve_icon(&config, Some("Notification"));
+
+        if icons_supported() {
+            assert_eq!(stop, Some(Icon::Path("/icons/check.png".to_string())));
+            assert_eq!(error, Some(Icon::Named("dialog-error".to_string())));
+            assert_eq!(other, Some(Icon::Named(DEFAULT_ICON.to_string())));
+            assert_eq!(fixed, Some(Icon::Named("boop".to_string())));
+        } else {
+            assert_eq!(stop, None);
+            assert_eq!(other, None);
+            assert_eq!(fixed, None);
+        }
+    }
+
+    #[test]
+    fn test_lookup_sound_name() {
+        let mut config = HashMap::new();
+        assert_eq!(lookup_sound_name(&config, Some("Stop")).unwrap(), None);
+
+        config.insert("sound_name".to_string(), Value::String("ping".to_string()));
+        config.insert("hook_sounds".to_string(), serde_json::json!({"Stop": "Glass", "PreCompact": "Bogus"}));
+
+        assert_eq!(lookup_sound_name(&config, Some("Stop")).unwrap(), Some("Glass"));
+        assert_eq!(lookup_sound_name(&config, Some("Notification")).unwrap(), Some("Ping"));
+        assert_eq!(lookup_sound_name(&config, None).unwrap(), Some("Ping"));
+
+        let err = lookup_sound_name(&config, Some("PreCompact")).unwrap_err();
+        assert!(err.to_string().contains("Unknown macOS sound \"Bogus\""), "{}", err);
+        assert_eq!(unknown_sound_names(&config), vec!["Bogus"]);
+    }
+
+    #[test]
+    fn test_sound_names_only_on_macos() {
+        assert_eq!(sound_names_supported(), cfg!(target_os = "macos"));
+
+        let mut config = HashMap::new();
+        config.insert("sound_name".to_string(), Value::String("Glass".to_string()));
+        let resolved = resolve_sound_name(&config, Some("Stop")).unwrap();
+
+        if sound_names_supported() {
+            assert_eq!(resolved.as_deref(), Some("Glass"));
+        } else {
+            assert_eq!(resolved, None);
+
+            // Ignored entirely, so bad names don't fail the handler either
+            config.insert("sound_name".to_string(), Value::String("Bogus".to_string()));
+            assert_eq!(resolve_sound_name(&config, Some("Stop")).unwrap(), None);
+        }
+    }
+
+    /// Title, body and options of each notification sent.
+    type Calls = Arc<Mutex<Vec<(String, String, NotifyOptions)>>>;
+
+    /// Notifier that records every call and optionally fails like a missing backend.
+    #[derive(Default)]
+    struct RecordingNotifier {
+        calls: Calls,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl Notifier for RecordingNotifier {
+        async fn notify(&self, title: &str, body: &str, opts: &NotifyOptions) -> HandlerResult<()> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push((title.to_string(), body.to_string(), opts.clone()));
+            if self.fail {
+                return Err(backend_unavailable("org.freedesktop.DBus.Error.ServiceUnknown"));
+            }
+            Ok(())
+        }
+    }
+
+    fn recording_handler(fail: bool) -> (DesktopHandler, Calls) {
+        let notifier = RecordingNotifier { fail, ..Default::default() };
+        let calls = notifier.calls.clone();
+        (DesktopHandler::with_notifier(Box::new(notifier)), calls)
+    }
+
+    #[tokio::test]
+    async fn test_handler_builds_title_and_body() {
+        let (handler, calls) = recording_handler(false);
+        let event = Event::from_json(r#"{"hook_event_name": "PermissionRequest", "tool_name": "Bash"}"#).unwrap();
+        let mut config = HashMap::new();
+        config.insert("body".to_string(), Value::String("Allow {{tool_name}}?".to_string()));
+        config.insert("timeout".to_string(), serde_json::json!(2000));
+
+        handler.handle(&event, &config).await.unwrap();
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls.len(), 1);
+        let (title, body, opts) = &calls[0];
+        assert_eq!(title, "Claude Code — PermissionRequest");
+        assert_eq!(body, "Allow Bash?");
+        assert_eq!(opts.app_name, DEFAULT_APP_NAME);
+        assert_eq!(opts.timeout_ms, 2000);
+        assert_eq!(opts.urgency, "critical");
+        assert_eq!(opts.action, None);
+        assert_eq!(opts.sound_name, None);
+        assert_eq!(opts.group, None);
+    }
+
+    #[test]
+    fn test_grouping_key_from_session_id_spellings() {
+        for json in [
+            r#"{"hook_event_name": "Stop", "session_id": "abc123"}"#,
+            r#"{"type": "session.idle", "sessionID": "abc123"}"#,
+            r#"{"type": "session.idle", "session": {"id": "abc123"}}"#,
+        ] {
+            let event = Event::from_json(json).unwrap();
+            assert_eq!(grouping_key(&event).as_deref(), Some("boopifier-abc123"), "{}", json);
+        }
+
+        for json in [r#"{"hook_event_name": "Stop"}"#, r#"{"session_id": ""}"#, r#"{"session_id": 7}"#] {
+            assert_eq!(grouping_key(&Event::from_json(json).unwrap()), None, "{}", json);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_group_by_session() {
+        let (handler, calls) = recording_handler(false);
+        let event = Event::from_json(r#"{"hook_event_name": "Stop", "session_id": "abc123"}"#).unwrap();
+        let mut config = HashMap::new();
+        handler.handle(&event, &config).await.unwrap();
+
+        config.insert("group_by_session".to_string(), Value::Bool(true));
+        handler.handle(&event, &config).await.unwrap();
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls[0].2.group, None);
+        let expected = grouping_supported().then(|| "boopifier-abc123".to_string());
+        assert_eq!(calls[1].2.group, expected);
+    }
+
+    #[tokio::test]
+    async fn test_backend_failure_is_reported() {
+        let (handler, calls) = recording_handler(true);
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+
+        let err = handler.handle(&event, &HashMap::new()).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Failed to send notification: notification backend unavailable: org.freedesktop.DBus.Error.ServiceUnknown"
+        );
+        assert_eq!(calls.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_backend_failure_falls_back_to_stderr() {
+        let (handler, _calls) = recording_handler(true);
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        let mut config = HashMap::new();
+        config.insert("stderr_fallback".to_string(), Value::Bool(true));
+
+        assert!(handler.handle(&event, &config).await.is_ok());
+    }
+
+    #[test]
+    fn test_detect_environment() {
+        let vars = |set: &'static [(&'static str, &'static str)]| {
+            move |name: &str| set.iter().find(|(k, _)| *k == name).map(|(_, v)| OsString::from(v))
+        };
+
+        let env = Environment::from_vars(vars(&[("DISPLAY", ":0"), ("DBUS_SESSION_BUS_ADDRESS", "unix:path=/run/bus")]));
+        assert_eq!(env, Environment::DESKTOP);
+        assert!(!env.is_headless());
+
+        let env = Environment::from_vars(vars(&[("WAYLAND_DISPLAY", "wayland-0"), ("SSH_CONNECTION", "10.0.0.2 5 10.0.0.1 22")]));
+        assert!(env.ssh && env.display);
+        assert!(env.is_headless());
+
+        // Empty values count as unset
+        let env = Environment::from_vars(vars(&[("DISPLAY", ""), ("SSH_TTY", "")]));
+        assert_eq!(env, Environment { ssh: false, display: false, dbus: false });
+        assert_eq!(env.is_headless(), cfg!(target_os = "linux"));
+    }
+
+    #[tokio::test]
+    async fn test_headless_session_prints_instead() {
+        let (handler, calls) = recording_handler(false);
+        let handler = handler.with_environment(Environment { ssh: true, ..Environment::DESKTOP });
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+
+        handler.handle(&event, &HashMap::new()).await.unwrap();
+        assert!(calls.lock().unwrap().is_empty());
+
+        // Explicitly asking for desktop notifications overrides detection
+        let mut config = HashMap::new();
+        config.insert("headless_stderr".to_string(), Value::Bool(false));
+        handler.handle(&event, &config).await.unwrap();
+        assert_eq!(calls.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_desktop_session_notifies() {
+        let (handler, calls) = recording_handler(false);
+        let handler = handler.with_environment(Environment::DESKTOP);
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+
+        handler.handle(&event, &HashMap::new()).await.unwrap();
+        assert_eq!(calls.lock().unwrap().len(), 1);
+    }
+
     #[test]
     fn test_get_string() {
         let mut config = HashMap::new();
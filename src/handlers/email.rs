@@ -4,7 +4,7 @@
 
 use crate::error::NotificationError;
 use crate::event::Event;
-use crate::handlers::{Handler, HandlerResult};
+use crate::handlers::{template, Handler, HandlerResult};
 use async_trait::async_trait;
 use lettre::message::header::ContentType;
 use lettre::transport::smtp::authentication::Credentials;
@@ -70,6 +70,7 @@ impl Handler for EmailHandler {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn send_email(
     from: &str,
     to: &str,
@@ -122,21 +123,7 @@ fn render_template(template: Option<&Value>, event: &Event, default: &str) -> St
         _ => return default.to_string(),
     };
 
-    let mut result = template_str.clone();
-
-    // Simple template substitution: {{field_name}}
-    for (key, value) in &event.data {
-        let placeholder = format!("{{{{{}}}}}", key);
-        if result.contains(&placeholder) {
-            let value_str = match value {
-                Value::String(s) => s.clone(),
-                other => other.to_string(),
-            };
-            result = result.replace(&placeholder, &value_str);
-        }
-    }
-
-    result
+    template::render_template(template_str, event)
 }
 
 #[cfg(test)]
@@ -5,25 +5,47 @@
 
 use crate::error::Result;
 use crate::event::Event;
+use crate::hooks::HandlerOutcome;
 use async_trait::async_trait;
 use serde_json::Value;
 use std::collections::HashMap;
 
+pub mod audit;
+pub mod bash_guard;
+pub mod check;
+pub mod command;
 pub mod desktop;
 pub mod email;
+pub mod permission;
 pub mod signal;
 pub mod sound;
+pub mod template;
+pub mod tts;
 pub mod webhook;
 
 /// Error type for handler operations.
 pub type HandlerResult<T> = Result<T>;
 
+/// Config key under which `process_event` passes each handler its
+/// `timeout_ms` budget, so handlers that retry or block can stop in time.
+pub const TIMEOUT_BUDGET_KEY: &str = "handler_timeout_ms";
+
 /// Trait for notification handlers.
 #[async_trait]
 pub trait Handler: Send + Sync {
     /// Returns the handler type name.
     fn handler_type(&self) -> &str;
 
+    /// Whether this handler answers permission prompts (`PreToolUse` and
+    /// `PermissionRequest`) with `Interactive` decisions.
+    ///
+    /// Decision handlers always run: quiet hours, rate limits, debounce and
+    /// digests don't apply to them. If one fails or times out, the hook asks
+    /// the user rather than falling back to the passive allow.
+    fn decides_permissions(&self) -> bool {
+        false
+    }
+
     /// Handles an event notification.
     ///
     /// # Arguments
@@ -31,6 +53,77 @@ pub trait Handler: Send + Sync {
     /// * `event` - The event that triggered this handler
     /// * `config` - Handler-specific configuration from claudifier.json
     async fn handle(&self, event: &Event, config: &HashMap<String, Value>) -> HandlerResult<()>;
+
+    /// Handles an event and reports an outcome that can shape the hook response.
+    ///
+    /// The default implementation calls `handle` and reports `HandlerOutcome::Success`.
+    /// Override this to return `Interactive`, `Context`, `Block`, or `Halt` outcomes.
+    async fn handle_outcome(
+        &self,
+        event: &Event,
+        config: &HashMap<String, Value>,
+    ) -> HandlerResult<HandlerOutcome> {
+        self.handle(event, config).await?;
+        Ok(HandlerOutcome::Success)
+    }
+}
+
+/// Builds a command for a handler to run and wait on.
+///
+/// A handler timeout drops the handler's future, so the child is killed on
+/// drop rather than left running after the handler gave up on it.
+pub(crate) fn handler_command(program: impl AsRef<std::ffi::OsStr>) -> tokio::process::Command {
+    let mut command = tokio::process::Command::new(program);
+    command.kill_on_drop(true);
+    command
+}
+
+/// Starts `command` without waiting for it, reaping it on a background thread.
+///
+/// The child is waited on once it exits, so a long-running process such as
+/// the daemon doesn't collect zombies. Only a failure to start is an error;
+/// otherwise returns the child's process id.
+pub(crate) fn spawn_detached(command: &mut std::process::Command) -> std::io::Result<u32> {
+    let mut child = command.spawn()?;
+    let pid = child.id();
+    std::thread::spawn(move || child.wait());
+    Ok(pid)
+}
+
+/// Builds a human-readable message for an event when no template is configured.
+///
+/// Uses the hook type plus the event's `message` or `reason` field, if present,
+/// and the session duration for `SessionEnd` events whose start was recorded.
+/// `PreCompact` events describe the trigger and context size instead, and
+/// `Stop`/`SubagentStop` events without either field say who finished.
+/// `Notification` events without a `message` get a generic prompt.
+pub(crate) fn default_text(event: &Event) -> String {
+    use crate::hooks::{compact::PreCompactHook, notification::NotificationHook, stop::StopHook};
+
+    let hook_type = event.event_type().unwrap_or("Event");
+    let message = event.message().or_else(|| event.get_str("reason"));
+    let detail = match hook_type {
+        "PreCompact" => Some(PreCompactHook::from_event(event).message()),
+        "Notification" => Some(NotificationHook::from_event(event).message().to_string()),
+        "Stop" | "SubagentStop" if message.is_none() => {
+            Some(StopHook::from_event(event).message().to_string())
+        }
+        _ => message.map(str::to_string),
+    };
+    let text = match detail {
+        Some(message) => format!("Claude Code {}: {}", hook_type, message),
+        None => format!("Claude Code {}", hook_type),
+    };
+
+    let text = match event.get_str(crate::sessions::DURATION_FIELD) {
+        Some(duration) => format!("{} (session lasted {})", text, duration),
+        None => text,
+    };
+
+    match event.get_str(crate::digest::DIGEST_FIELD) {
+        Some(digest) => format!("{} - {}", text, digest),
+        None => text,
+    }
 }
 
 /// Registry for managing notification handlers.
@@ -46,11 +139,17 @@ impl HandlerRegistry {
         };
 
         // Register all built-in handlers
-        registry.register(Box::new(desktop::DesktopHandler));
+        registry.register(Box::new(desktop::DesktopHandler::new()));
         registry.register(Box::new(sound::SoundHandler));
         registry.register(Box::new(signal::SignalHandler));
         registry.register(Box::new(webhook::WebhookHandler));
         registry.register(Box::new(email::EmailHandler));
+        registry.register(Box::new(tts::TtsHandler));
+        registry.register(Box::new(command::CommandHandler));
+        registry.register(Box::new(audit::AuditHandler));
+        registry.register(Box::new(permission::PermissionHandler));
+        registry.register(Box::new(bash_guard::BashGuardHandler));
+        registry.register(Box::new(check::CheckHandler));
 
         registry
     }
@@ -81,6 +180,77 @@ impl Default for HandlerRegistry {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::Config;
+    use std::sync::{Arc, Mutex};
+
+    /// Test handler that records every event it receives.
+    struct RecordingHandler {
+        seen: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Handler for RecordingHandler {
+        fn handler_type(&self) -> &str {
+            "recording"
+        }
+
+        async fn handle(&self, event: &Event, _config: &HashMap<String, Value>) -> HandlerResult<()> {
+            let name = event.event_type().unwrap_or("unknown").to_string();
+            self.seen.lock().unwrap().push(name);
+            Ok(())
+        }
+    }
+
+    /// Test handler that sleeps for `config.sleep_ms`, then succeeds or, if
+    /// `config.context` is set, returns it as a `Context` outcome.
+    struct SleepingHandler;
+
+    #[async_trait]
+    impl Handler for SleepingHandler {
+        fn handler_type(&self) -> &str {
+            "sleeping"
+        }
+
+        async fn handle(&self, _event: &Event, config: &HashMap<String, Value>) -> HandlerResult<()> {
+            let ms = config.get("sleep_ms").and_then(|v| v.as_u64()).unwrap_or(0);
+            tokio::time::sleep(std::time::Duration::from_millis(ms)).await;
+            Ok(())
+        }
+
+        async fn handle_outcome(
+            &self,
+            event: &Event,
+            config: &HashMap<String, Value>,
+        ) -> HandlerResult<HandlerOutcome> {
+            self.handle(event, config).await?;
+            Ok(match config.get("context").and_then(|v| v.as_str()) {
+                Some(context) => HandlerOutcome::Context(context.to_string()),
+                None => HandlerOutcome::Success,
+            })
+        }
+    }
+
+    /// Test handler that overrides `handle_outcome` to block.
+    struct BlockingHandler;
+
+    #[async_trait]
+    impl Handler for BlockingHandler {
+        fn handler_type(&self) -> &str {
+            "blocking"
+        }
+
+        async fn handle(&self, _event: &Event, _config: &HashMap<String, Value>) -> HandlerResult<()> {
+            Ok(())
+        }
+
+        async fn handle_outcome(
+            &self,
+            _event: &Event,
+            _config: &HashMap<String, Value>,
+        ) -> HandlerResult<HandlerOutcome> {
+            Ok(HandlerOutcome::Block("nope".to_string()))
+        }
+    }
 
     #[test]
     fn test_registry_contains_handlers() {
@@ -90,6 +260,59 @@ mod tests {
         assert!(registry.get("signal").is_some());
         assert!(registry.get("webhook").is_some());
         assert!(registry.get("email").is_some());
+        assert!(registry.get("tts").is_some());
+        assert!(registry.get("command").is_some());
+        assert!(registry.get("audit").is_some());
+        assert!(registry.get("permission").is_some());
+        assert!(registry.get("check").is_some());
+    }
+
+    #[test]
+    fn test_default_text_includes_session_duration() {
+        let event = Event::from_json(
+            r#"{"hook_event_name": "SessionEnd", "reason": "logout", "session_duration": "3m 20s"}"#,
+        )
+        .unwrap();
+        assert_eq!(default_text(&event), "Claude Code SessionEnd: logout (session lasted 3m 20s)");
+
+        let event = Event::from_json(r#"{"hook_event_name": "SessionEnd", "reason": "logout"}"#).unwrap();
+        assert_eq!(default_text(&event), "Claude Code SessionEnd: logout");
+    }
+
+    #[test]
+    fn test_default_text_includes_digest() {
+        let event = Event::from_json(
+            r#"{"hook_event_name": "Stop", "digest": "3 events: 2 PostToolUse, 1 Notification"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            default_text(&event),
+            "Claude Code Stop: Claude finished - 3 events: 2 PostToolUse, 1 Notification"
+        );
+    }
+
+    #[test]
+    fn test_default_text_describes_compaction() {
+        let event = Event::from_json(
+            r#"{"hook_event_name": "PreCompact", "trigger": "auto", "token_count": 150000}"#,
+        )
+        .unwrap();
+        assert_eq!(default_text(&event), "Claude Code PreCompact: auto compaction (150000 tokens)");
+
+        let event = Event::from_json(r#"{"hook_event_name": "PreCompact", "trigger": "manual"}"#).unwrap();
+        assert_eq!(default_text(&event), "Claude Code PreCompact: manual compaction");
+    }
+
+    #[test]
+    fn test_default_text_distinguishes_subagent_stop() {
+        let event = Event::from_json(r#"{"hook_event_name": "SubagentStop"}"#).unwrap();
+        assert_eq!(default_text(&event), "Claude Code SubagentStop: Subagent finished");
+
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        assert_eq!(default_text(&event), "Claude Code Stop: Claude finished");
+
+        let event = Event::from_json(r#"{"hook_event_name": "Stop", "reason": "done"}"#).unwrap();
+        assert_eq!(default_text(&event), "Claude Code Stop: done");
     }
 
     #[test]
@@ -99,4 +322,230 @@ mod tests {
         assert!(types.contains(&"desktop"));
         assert!(types.contains(&"sound"));
     }
+
+    #[tokio::test]
+    async fn test_custom_handler_records_invocations() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = HandlerRegistry::new();
+        registry.register(Box::new(RecordingHandler { seen: seen.clone() }));
+
+        let config = Config::from_json(
+            r#"{"handlers": [
+                {"name": "rec-1", "type": "recording", "config": {}},
+                {"name": "rec-2", "type": "recording", "config": {}}
+            ]}"#,
+        )
+        .unwrap();
+
+        let outcomes = crate::process_event(r#"{"hook_event_name": "Stop"}"#, &config, &registry)
+            .await
+            .unwrap();
+
+        assert_eq!(outcomes.len(), 2);
+        assert!(outcomes.iter().all(|o| matches!(o, HandlerOutcome::Success)));
+        assert_eq!(*seen.lock().unwrap(), vec!["Stop", "Stop"]);
+    }
+
+    #[tokio::test]
+    async fn test_handle_outcome_override_flows_to_response() {
+        use crate::hooks::hook_from_event;
+
+        let mut registry = HandlerRegistry::new();
+        registry.register(Box::new(BlockingHandler));
+
+        let config = Config::from_json(
+            r#"{"handlers": [{"name": "block", "type": "blocking", "config": {}}]}"#,
+        )
+        .unwrap();
+
+        let event_json = r#"{"hook_event_name": "UserPromptSubmit"}"#;
+        let outcomes = crate::process_event(event_json, &config, &registry).await.unwrap();
+        let hook = hook_from_event(&Event::from_json(event_json).unwrap()).unwrap();
+
+        assert_eq!(hook.generate_response(&outcomes)["decision"], "block");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_spawn_detached_reaps_child() {
+        let pid = spawn_detached(&mut std::process::Command::new("true")).unwrap();
+
+        // A zombie keeps its /proc entry until it's waited on
+        let proc_entry = std::path::PathBuf::from(format!("/proc/{}", pid));
+        for _ in 0..100 {
+            if !proc_entry.exists() {
+                return;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+        panic!("child {} was never reaped", pid);
+    }
+
+    #[tokio::test]
+    async fn test_debounced_handler_runs_once() {
+        use crate::debounce::Debouncer;
+        use crate::state::Stores;
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = HandlerRegistry::new();
+        registry.register(Box::new(RecordingHandler { seen: seen.clone() }));
+
+        let config = Config::from_json(
+            r#"{"handlers": [{"name": "rec", "type": "recording", "debounce": 60000, "config": {}}]}"#,
+        )
+        .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let stores = Stores {
+            debouncer: Debouncer::new(dir.path().join("debounce.json")),
+            ..Stores::default_files()
+        };
+        let event_json = r#"{"hook_event_name": "Stop", "session_id": "debounce-test"}"#;
+
+        let outcomes = crate::process_event_with(event_json, &config, &registry, &stores).await.unwrap();
+        assert!(matches!(outcomes[..], [HandlerOutcome::Success]));
+        for _ in 0..2 {
+            let outcomes = crate::process_event_with(event_json, &config, &registry, &stores).await.unwrap();
+            assert!(matches!(&outcomes[..], [HandlerOutcome::Skipped(reason)] if reason == "rec: debounced"));
+        }
+
+        assert_eq!(seen.lock().unwrap().len(), 1);
+    }
+
+    /// Test handler that records the `tool_input` of each event it handles.
+    struct ToolInputHandler {
+        seen: Arc<Mutex<Vec<Value>>>,
+    }
+
+    #[async_trait]
+    impl Handler for ToolInputHandler {
+        fn handler_type(&self) -> &str {
+            "tool-input"
+        }
+
+        async fn handle(&self, event: &Event, _config: &HashMap<String, Value>) -> HandlerResult<()> {
+            self.seen
+                .lock()
+                .unwrap()
+                .push(event.tool_input().cloned().unwrap_or_default());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_redacted_fields_hidden_from_handler() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = HandlerRegistry::new();
+        registry.register(Box::new(ToolInputHandler { seen: seen.clone() }));
+
+        let config = Config::from_json(
+            r#"{"handlers": [
+                {"name": "remote", "type": "tool-input", "redact": {"mask": ["tool_input.token"]}, "config": {}},
+                {"name": "local", "type": "tool-input", "config": {}}
+            ]}"#,
+        )
+        .unwrap();
+
+        let event_json = r#"{"hook_event_name": "PreToolUse", "tool_name": "Bash", "tool_input": {"command": "curl", "token": "abc"}}"#;
+        crate::process_event(event_json, &config, &registry).await.unwrap();
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                serde_json::json!({"command": "curl", "token": "[redacted]"}),
+                serde_json::json!({"command": "curl", "token": "abc"}),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_disabled_hook_skips_handlers() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut registry = HandlerRegistry::new();
+        registry.register(Box::new(RecordingHandler { seen: seen.clone() }));
+
+        let config = Config::from_json(
+            r#"{
+                "disabled_hooks": ["Notification"],
+                "handlers": [{"name": "rec", "type": "recording", "config": {}}]
+            }"#,
+        )
+        .unwrap();
+
+        let outcomes = crate::process_event(r#"{"hook_event_name": "Notification"}"#, &config, &registry)
+            .await
+            .unwrap();
+        assert!(outcomes.is_empty());
+
+        let outcomes = crate::process_event(r#"{"hook_event_name": "Stop"}"#, &config, &registry)
+            .await
+            .unwrap();
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(*seen.lock().unwrap(), vec!["Stop"]);
+    }
+
+    #[tokio::test]
+    async fn test_slow_handler_times_out() {
+        use crate::hooks::hook_from_event;
+
+        let mut registry = HandlerRegistry::new();
+        registry.register(Box::new(SleepingHandler));
+
+        let config = Config::from_json(
+            r#"{"handlers": [
+                {"name": "slow", "type": "sleeping", "timeout_ms": 50, "config": {"sleep_ms": 5000}},
+                {"name": "fast", "type": "sleeping", "timeout_ms": 1000, "config": {"sleep_ms": 0}}
+            ]}"#,
+        )
+        .unwrap();
+
+        let event_json = r#"{"hook_event_name": "Notification"}"#;
+        let started = std::time::Instant::now();
+        let outcomes = crate::process_event(event_json, &config, &registry).await.unwrap();
+        assert!(started.elapsed() < std::time::Duration::from_secs(2));
+
+        assert!(matches!(&outcomes[0], HandlerOutcome::Error(msg) if msg == "slow: timeout"));
+        assert!(matches!(outcomes[1], HandlerOutcome::Success));
+
+        // The timeout still produces a normal response
+        let hook = hook_from_event(&Event::from_json(event_json).unwrap()).unwrap();
+        assert_eq!(
+            hook.generate_response(&outcomes),
+            serde_json::json!({"systemMessage": "Boopifier warning: slow: timeout"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handlers_run_concurrently_in_config_order() {
+        let mut registry = HandlerRegistry::new();
+        registry.register(Box::new(SleepingHandler));
+
+        // The first handler finishes last, but its outcome still comes first
+        let config = Config::from_json(
+            r#"{"handlers": [
+                {"name": "a", "type": "sleeping", "config": {"sleep_ms": 400, "context": "a"}},
+                {"name": "b", "type": "sleeping", "config": {"sleep_ms": 300, "context": "b"}}
+            ]}"#,
+        )
+        .unwrap();
+
+        let started = std::time::Instant::now();
+        let outcomes = crate::process_event(r#"{"hook_event_name": "SessionStart"}"#, &config, &registry)
+            .await
+            .unwrap();
+        let elapsed = started.elapsed();
+
+        // Roughly the slowest handler (400ms), not the sum (700ms)
+        assert!(elapsed >= std::time::Duration::from_millis(400));
+        assert!(elapsed < std::time::Duration::from_millis(650), "took {:?}", elapsed);
+
+        let contexts: Vec<&str> = outcomes
+            .iter()
+            .map(|o| match o {
+                HandlerOutcome::Context(c) => c.as_str(),
+                other => panic!("unexpected outcome {:?}", other),
+            })
+            .collect();
+        assert_eq!(contexts, vec!["a", "b"]);
+    }
 }
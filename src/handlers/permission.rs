@@ -0,0 +1,202 @@
+//! Permission decision handler.
+//!
+//! Answers `PermissionRequest` and `PreToolUse` hooks with a fixed decision,
+//! so match rules alone can auto-approve safe tools or deny risky ones.
+
+use crate::error::NotificationError;
+use crate::event::Event;
+use crate::handlers::{template, Handler, HandlerResult};
+use crate::hooks::{HandlerOutcome, InteractiveResponse, PermissionDecision};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Handler that returns a permission decision.
+///
+/// Config:
+/// - `decision`: "allow", "deny" or "ask" (required)
+/// - `reason`: template shown to Claude or the user with the decision (optional)
+///
+/// Use the handler's `match_rules` to choose which tools and inputs it
+/// decides for.
+pub struct PermissionHandler;
+
+#[async_trait]
+impl Handler for PermissionHandler {
+    fn handler_type(&self) -> &str {
+        "permission"
+    }
+
+    fn decides_permissions(&self) -> bool {
+        true
+    }
+
+    async fn handle(&self, event: &Event, config: &HashMap<String, Value>) -> HandlerResult<()> {
+        decide(event, config).map(|_| ())
+    }
+
+    async fn handle_outcome(
+        &self,
+        event: &Event,
+        config: &HashMap<String, Value>,
+    ) -> HandlerResult<HandlerOutcome> {
+        decide(event, config).map(HandlerOutcome::Interactive)
+    }
+}
+
+fn decide(event: &Event, config: &HashMap<String, Value>) -> HandlerResult<InteractiveResponse> {
+    let decision = config
+        .get("decision")
+        .and_then(|v| v.as_str())
+        .and_then(PermissionDecision::parse)
+        .ok_or_else(|| {
+            NotificationError::InvalidConfig(
+                "Permission handler requires 'decision' of \"allow\", \"deny\" or \"ask\"".to_string(),
+            )
+        })?;
+    let reason = config
+        .get("reason")
+        .and_then(|v| v.as_str())
+        .map(|reason| template::render_template(reason, event));
+
+    Ok(InteractiveResponse { decision, reason })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::handlers::HandlerRegistry;
+    use crate::hooks::hook_from_event;
+    use serde_json::json;
+
+    /// Auto-allows read-only tools and denies writes under /etc.
+    const RULES: &str = r#"{"handlers": [
+        {
+            "name": "allow-reads",
+            "type": "permission",
+            "match_rules": {"any": [{"tool_name": "Read"}, {"tool_name": "Grep"}, {"tool_name": "Glob"}]},
+            "config": {"decision": "allow"}
+        },
+        {
+            "name": "deny-etc",
+            "type": "permission",
+            "match_type": "glob",
+            "match_rules": {"tool_input.file_path": "/etc/*"},
+            "config": {"decision": "deny", "reason": "{{tool_name}} under /etc is not allowed"}
+        }
+    ]}"#;
+
+    async fn respond(event: Value) -> Value {
+        let config = Config::from_json(RULES).unwrap();
+        let event_json = event.to_string();
+        let outcomes = crate::process_event(&event_json, &config, &HandlerRegistry::new())
+            .await
+            .unwrap();
+        let hook = hook_from_event(&Event::from_json(&event_json).unwrap()).unwrap();
+        hook.generate_response(&outcomes)
+    }
+
+    fn permission_request(tool_name: &str, file_path: &str) -> Value {
+        json!({
+            "hook_event_name": "PermissionRequest",
+            "tool_name": tool_name,
+            "tool_input": {"file_path": file_path}
+        })
+    }
+
+    #[tokio::test]
+    async fn test_rules_allow_read_only_tools() {
+        let response = respond(permission_request("Read", "/home/me/notes.md")).await;
+        assert_eq!(response["hookSpecificOutput"]["decision"], json!({"behavior": "allow"}));
+    }
+
+    #[tokio::test]
+    async fn test_rules_deny_writes_under_path() {
+        let response = respond(permission_request("Write", "/etc/hosts")).await;
+        assert_eq!(
+            response["hookSpecificOutput"]["decision"],
+            json!({"behavior": "deny", "message": "Write under /etc is not allowed"})
+        );
+
+        // Deny wins even when a read rule also matches
+        let response = respond(permission_request("Read", "/etc/shadow")).await;
+        assert_eq!(response["hookSpecificOutput"]["decision"]["behavior"], "deny");
+    }
+
+    #[tokio::test]
+    async fn test_no_matching_rule_asks() {
+        let response = respond(permission_request("Write", "/home/me/notes.md")).await;
+        assert_eq!(response, json!({}));
+    }
+
+    #[tokio::test]
+    async fn test_path_scoped_rules() {
+        let config = Config::from_json(
+            r#"{"handlers": [
+                {"name": "deny-env", "type": "permission", "match_rules": {"path_prefix": ".env"},
+                 "config": {"decision": "deny", "reason": "secrets"}},
+                {"name": "allow-src", "type": "permission", "match_rules": {"path_prefix": "src/"},
+                 "config": {"decision": "allow"}}
+            ]}"#,
+        )
+        .unwrap();
+        let respond = |file_path: &str| {
+            let event = json!({
+                "hook_event_name": "PreToolUse",
+                "cwd": "/home/me/app",
+                "tool_name": "Write",
+                "tool_input": {"file_path": file_path}
+            })
+            .to_string();
+            let config = config.clone();
+            async move {
+                let outcomes = crate::process_event(&event, &config, &HandlerRegistry::new()).await.unwrap();
+                hook_from_event(&Event::from_json(&event).unwrap()).unwrap().generate_response(&outcomes)
+            }
+        };
+
+        let response = respond("/home/me/app/.env").await;
+        assert_eq!(response["hookSpecificOutput"]["permissionDecision"], "deny");
+        assert_eq!(response["hookSpecificOutput"]["permissionDecisionReason"], "secrets");
+
+        let response = respond("/home/me/app/src/main.rs").await;
+        assert_eq!(response["hookSpecificOutput"]["permissionDecision"], "allow");
+    }
+
+    #[tokio::test]
+    async fn test_decisions_ignore_quiet_hours_and_rate_limit() {
+        let config = Config::from_json(
+            r#"{
+                "quiet_hours": [{"start": "00:00", "end": "23:59"}, {"start": "23:59", "end": "00:00"}],
+                "rate_limit": {"max": 1},
+                "handlers": [{"name": "deny-env", "type": "permission", "match_rules": {"path_prefix": ".env"},
+                              "config": {"decision": "deny"}}]
+            }"#,
+        )
+        .unwrap();
+        let event = json!({
+            "hook_event_name": "PreToolUse",
+            "cwd": "/home/me/app",
+            "tool_name": "Read",
+            "tool_input": {"file_path": "/home/me/app/.env"}
+        })
+        .to_string();
+
+        for _ in 0..3 {
+            let outcomes = crate::process_event(&event, &config, &HandlerRegistry::new()).await.unwrap();
+            let response = hook_from_event(&Event::from_json(&event).unwrap()).unwrap().generate_response(&outcomes);
+            assert_eq!(response["hookSpecificOutput"]["permissionDecision"], "deny");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_decision() {
+        let event = Event::from_value(permission_request("Read", "a")).unwrap();
+        let mut config = HashMap::new();
+        config.insert("decision".to_string(), json!("maybe"));
+
+        let err = PermissionHandler.handle_outcome(&event, &config).await.unwrap_err();
+        assert!(err.to_string().contains("requires 'decision'"));
+    }
+}
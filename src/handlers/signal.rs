@@ -4,11 +4,10 @@
 
 use crate::error::NotificationError;
 use crate::event::Event;
-use crate::handlers::{Handler, HandlerResult};
+use crate::handlers::{handler_command, template, Handler, HandlerResult};
 use async_trait::async_trait;
 use serde_json::Value;
 use std::collections::HashMap;
-use tokio::process::Command;
 
 /// Handler for Signal notifications.
 pub struct SignalHandler;
@@ -53,7 +52,7 @@ async fn send_signal_message(
     recipient: &str,
     message: &str,
 ) -> HandlerResult<()> {
-    let mut cmd = Command::new(signal_cli_path);
+    let mut cmd = handler_command(signal_cli_path);
 
     // Add account if specified
     if let Some(acc) = account {
@@ -83,21 +82,7 @@ fn render_message(template: Option<&Value>, event: &Event) -> String {
         _ => return format!("Claude Code Event: {:?}", event.data),
     };
 
-    let mut result = template_str.clone();
-
-    // Simple template substitution: {{field_name}}
-    for (key, value) in &event.data {
-        let placeholder = format!("{{{{{}}}}}", key);
-        if result.contains(&placeholder) {
-            let value_str = match value {
-                Value::String(s) => s.clone(),
-                other => other.to_string(),
-            };
-            result = result.replace(&placeholder, &value_str);
-        }
-    }
-
-    result
+    template::render_template(template_str, event)
 }
 
 #[cfg(test)]
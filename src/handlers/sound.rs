@@ -1,18 +1,23 @@
 //! Sound notification handler.
 //!
-//! Plays audio files using rodio.
+//! Plays audio files using rodio, or by running an external player such as
+//! `paplay` where rodio's output is unreliable.
 
 use crate::error::NotificationError;
 use crate::event::Event;
-use crate::handlers::{Handler, HandlerResult};
+use crate::handlers::{spawn_detached, Handler, HandlerResult, TIMEOUT_BUDGET_KEY};
 use async_trait::async_trait;
 use rand::seq::SliceRandom;
 use rodio::{Decoder, Sink};
 use rodio::stream::OutputStreamBuilder;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::ffi::OsStr;
 use std::fs::File;
 use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
 
 // Suppress ALSA warnings on Linux (unless debug mode is enabled)
 #[cfg(target_os = "linux")]
@@ -43,7 +48,13 @@ fn suppress_alsa_errors_if_not_debug() {
     // No-op on non-Linux platforms
 }
 
+/// How often playback checks whether the handler's time is up.
+const PLAYBACK_POLL: Duration = Duration::from_millis(20);
+
 /// Handler for sound notifications.
+///
+/// In-process playback stops when the handler's `timeout_ms` runs out, so a
+/// long sound can't keep boopifier running after the handler timed out.
 pub struct SoundHandler;
 
 #[async_trait]
@@ -52,9 +63,9 @@ impl Handler for SoundHandler {
         "sound"
     }
 
-    async fn handle(&self, _event: &Event, config: &HashMap<String, Value>) -> HandlerResult<()> {
+    async fn handle(&self, event: &Event, config: &HashMap<String, Value>) -> HandlerResult<()> {
         // Determine which file to play
-        let file_path = get_sound_file(config)?;
+        let file_path = resolve_sound_file(config, event.event_type())?;
 
         // Expand tilde in path
         let expanded_path = shellexpand::tilde(&file_path).to_string();
@@ -65,11 +76,24 @@ impl Handler for SoundHandler {
             .and_then(|v| v.as_f64())
             .unwrap_or(1.0) as f32;
 
+        // An external player runs detached, so the hook response isn't held up
+        if let Some(player) = config.get("player").and_then(|v| v.as_str()) {
+            let path_var = std::env::var_os("PATH").unwrap_or_default();
+            let program = select_player(player, &path_var)?;
+            return spawn_player(&program, &expanded_path, volume);
+        }
+
+        // The blocking task can't be cancelled, so it stops itself at the handler timeout
+        let deadline = config
+            .get(TIMEOUT_BUDGET_KEY)
+            .and_then(|v| v.as_u64())
+            .map(|ms| Instant::now() + Duration::from_millis(ms));
+
         // Play the sound in a blocking task to avoid blocking the async runtime
         tokio::task::spawn_blocking(move || {
             // Suppress ALSA warnings in this thread before initializing audio
             suppress_alsa_errors_if_not_debug();
-            play_sound(&expanded_path, volume)
+            play_sound(&expanded_path, volume, deadline)
         })
             .await
             .map_err(|e| NotificationError::Audio(format!("Sound playback task failed: {}", e)))?
@@ -79,6 +103,24 @@ impl Handler for SoundHandler {
     }
 }
 
+/// Resolves the sound file for a hook type.
+///
+/// Uses the `hook_files` mapping (e.g. `{"Stop": "boop.wav"}`) when it has an
+/// entry for `hook_type`, otherwise falls back to `get_sound_file`.
+fn resolve_sound_file(config: &HashMap<String, Value>, hook_type: Option<&str>) -> HandlerResult<String> {
+    let mapped = hook_type.and_then(|hook_type| {
+        config
+            .get("hook_files")
+            .and_then(|v| v.get(hook_type))
+            .and_then(|v| v.as_str())
+    });
+
+    match mapped {
+        Some(file) => Ok(file.to_string()),
+        None => get_sound_file(config),
+    }
+}
+
 /// Gets the sound file to play from config.
 ///
 /// Supports:
@@ -133,13 +175,95 @@ fn get_sound_file(config: &HashMap<String, Value>) -> HandlerResult<String> {
     }
 }
 
-fn play_sound(file_path: &str, volume: f32) -> HandlerResult<()> {
+/// External players tried, in order, for `"player": "auto"`.
+const PLAYERS: &[&str] = &["paplay", "aplay", "ffplay"];
+
+/// Chooses the external player program.
+///
+/// `"auto"` picks the first of `PLAYERS` found on `path_var` (a `$PATH`-style
+/// list); any other value names the player to use, looked up the same way
+/// unless it contains a `/`.
+fn select_player(player: &str, path_var: &OsStr) -> HandlerResult<PathBuf> {
+    if player == "auto" {
+        return PLAYERS
+            .iter()
+            .find_map(|name| find_program(name, path_var))
+            .ok_or_else(|| {
+                NotificationError::Audio(format!("No sound player found (tried {})", PLAYERS.join(", ")))
+            });
+    }
+
+    find_program(&shellexpand::tilde(player), path_var)
+        .ok_or_else(|| NotificationError::Audio(format!("Sound player '{}' not found", player)))
+}
+
+/// Resolves `name` to an executable: as a path if it contains `/`, otherwise
+/// in the directories of `path_var`.
+fn find_program(name: &str, path_var: &OsStr) -> Option<PathBuf> {
+    if name.contains('/') {
+        return Some(PathBuf::from(name)).filter(|path| is_executable(path));
+    }
+
+    std::env::split_paths(path_var)
+        .map(|dir| dir.join(name))
+        .find(|path| is_executable(path))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata().is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// Arguments for `program` to play `file_path` quietly at `volume` (0.0 to 1.0).
+///
+/// Known players get their volume and no-window flags; others just get the file.
+fn player_args(program: &Path, file_path: &str, volume: f32) -> Vec<String> {
+    let volume = volume.clamp(0.0, 1.0);
+    let name = program.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let file = file_path.to_string();
+
+    match name {
+        "paplay" => vec![format!("--volume={}", (volume * 65536.0) as u32), file],
+        "aplay" => vec!["-q".to_string(), file],
+        "ffplay" => vec![
+            "-nodisp".to_string(),
+            "-autoexit".to_string(),
+            "-loglevel".to_string(),
+            "quiet".to_string(),
+            "-volume".to_string(),
+            ((volume * 100.0) as u32).to_string(),
+            file,
+        ],
+        _ => vec![file],
+    }
+}
+
+/// Starts the player without waiting for it; only a failure to start is an error.
+fn spawn_player(program: &Path, file_path: &str, volume: f32) -> HandlerResult<()> {
+    spawn_detached(
+        Command::new(program)
+            .args(player_args(program, file_path, volume))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null()),
+    )
+    .map(|_| ())
+    .map_err(|e| NotificationError::Audio(format!("Failed to start {}: {}", program.display(), e)))
+}
+
+fn play_sound(file_path: &str, volume: f32, deadline: Option<Instant>) -> HandlerResult<()> {
     // Get output stream handle (rodio 0.21 API)
     let stream_handle = OutputStreamBuilder::open_default_stream()
         .map_err(|e| NotificationError::Audio(format!("Failed to get audio output stream: {}", e)))?;
 
     // Create a sink for audio playback (rodio 0.21 API)
-    let sink = Sink::connect_new(&stream_handle.mixer());
+    let sink = Sink::connect_new(stream_handle.mixer());
 
     // Open the audio file
     let file = File::open(file_path)
@@ -153,10 +277,10 @@ fn play_sound(file_path: &str, volume: f32) -> HandlerResult<()> {
     sink.set_volume(volume.clamp(0.0, 1.0));
     sink.append(source);
 
-    // Block until all audio finishes playing
+    // Block until all audio finishes playing (or the deadline passes)
     // IMPORTANT: This keeps both the sink AND stream_handle alive during playback
     // Without this, the stream would be dropped and audio would stop immediately
-    sink.sleep_until_end();
+    wait_for_playback(&sink, deadline);
 
     // Keep stream_handle alive until playback completes
     drop(stream_handle);
@@ -164,6 +288,19 @@ fn play_sound(file_path: &str, volume: f32) -> HandlerResult<()> {
     Ok(())
 }
 
+/// Blocks until `sink` has played everything or `deadline` passes, then stops it.
+fn wait_for_playback(sink: &Sink, deadline: Option<Instant>) {
+    let Some(deadline) = deadline else {
+        sink.sleep_until_end();
+        return;
+    };
+
+    while !sink.empty() && Instant::now() < deadline {
+        std::thread::sleep(PLAYBACK_POLL);
+    }
+    sink.stop();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -247,4 +384,131 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("empty"));
     }
+
+    #[test]
+    fn test_resolve_sound_file_per_hook() {
+        let mut config = HashMap::new();
+        config.insert("file".to_string(), Value::String("default.wav".to_string()));
+        config.insert(
+            "hook_files".to_string(),
+            serde_json::json!({"Stop": "boop.wav", "Notification": "alert.wav"}),
+        );
+
+        assert_eq!(resolve_sound_file(&config, Some("Stop")).unwrap(), "boop.wav");
+        assert_eq!(resolve_sound_file(&config, Some("Notification")).unwrap(), "alert.wav");
+    }
+
+    #[test]
+    fn test_resolve_sound_file_falls_back_to_default() {
+        let mut config = HashMap::new();
+        config.insert("file".to_string(), Value::String("default.wav".to_string()));
+        config.insert("hook_files".to_string(), serde_json::json!({"Stop": "boop.wav"}));
+
+        assert_eq!(resolve_sound_file(&config, Some("PreCompact")).unwrap(), "default.wav");
+        assert_eq!(resolve_sound_file(&config, None).unwrap(), "default.wav");
+    }
+
+    #[test]
+    fn test_resolve_sound_file_mapping_only() {
+        let mut config = HashMap::new();
+        config.insert("hook_files".to_string(), serde_json::json!({"Stop": "boop.wav"}));
+
+        assert_eq!(resolve_sound_file(&config, Some("Stop")).unwrap(), "boop.wav");
+        assert!(resolve_sound_file(&config, Some("Notification")).is_err());
+    }
+
+    /// Creates executable stubs named `names` in a new directory.
+    #[cfg(unix)]
+    fn stub_bin(names: &[&str]) -> tempfile::TempDir {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        for name in names {
+            let path = dir.path().join(name);
+            std::fs::write(&path, "#!/bin/sh\n").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        dir
+    }
+
+    #[cfg(unix)]
+    fn path_of(dirs: &[&tempfile::TempDir]) -> std::ffi::OsString {
+        std::env::join_paths(dirs.iter().map(|d| d.path())).unwrap()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_auto_player_prefers_first_available() {
+        let aplay_ffplay = stub_bin(&["aplay", "ffplay"]);
+        let paplay = stub_bin(&["paplay"]);
+
+        let player = select_player("auto", &path_of(&[&aplay_ffplay])).unwrap();
+        assert_eq!(player, aplay_ffplay.path().join("aplay"));
+
+        // Preference order wins over PATH order
+        let player = select_player("auto", &path_of(&[&aplay_ffplay, &paplay])).unwrap();
+        assert_eq!(player, paplay.path().join("paplay"));
+
+        let empty = stub_bin(&[]);
+        let err = select_player("auto", &path_of(&[&empty])).unwrap_err();
+        assert!(err.to_string().contains("No sound player found (tried paplay, aplay, ffplay)"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_configured_player() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let bin = stub_bin(&["paplay", "ffplay", "mpv"]);
+        let path = path_of(&[&bin]);
+
+        assert_eq!(select_player("ffplay", &path).unwrap(), bin.path().join("ffplay"));
+        assert_eq!(select_player("mpv", &path).unwrap(), bin.path().join("mpv"));
+
+        let absolute = bin.path().join("mpv");
+        assert_eq!(select_player(absolute.to_str().unwrap(), &path_of(&[])).unwrap(), absolute);
+
+        assert!(select_player("aplay", &path).is_err());
+
+        // Files that aren't executable don't count
+        std::fs::set_permissions(&absolute, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(select_player("mpv", &path).is_err());
+    }
+
+    #[test]
+    fn test_player_args() {
+        assert_eq!(
+            player_args(Path::new("/usr/bin/paplay"), "boop.wav", 0.5),
+            vec!["--volume=32768", "boop.wav"]
+        );
+        assert_eq!(player_args(Path::new("aplay"), "boop.wav", 1.0), vec!["-q", "boop.wav"]);
+        assert_eq!(
+            player_args(Path::new("ffplay"), "boop.wav", 2.0),
+            vec!["-nodisp", "-autoexit", "-loglevel", "quiet", "-volume", "100", "boop.wav"]
+        );
+        assert_eq!(player_args(Path::new("mpv"), "boop.wav", 1.0), vec!["boop.wav"]);
+    }
+
+    #[tokio::test]
+    async fn test_player_failure_is_error() {
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        let mut config = HashMap::new();
+        config.insert("file".to_string(), Value::String("boop.wav".to_string()));
+        config.insert("player".to_string(), Value::String("/no/such/player".to_string()));
+
+        let err = SoundHandler.handle(&event, &config).await.unwrap_err();
+        assert!(err.to_string().contains("Sound player '/no/such/player' not found"));
+    }
+
+    #[test]
+    fn test_playback_stops_at_deadline() {
+        // Nothing consumes this sink's output, so the endless tone never finishes
+        let (sink, _output) = Sink::new();
+        sink.append(rodio::source::SineWave::new(440.0));
+
+        let started = Instant::now();
+        wait_for_playback(&sink, Some(started + Duration::from_millis(100)));
+        assert!(started.elapsed() >= Duration::from_millis(100));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
 }
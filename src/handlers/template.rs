@@ -0,0 +1,122 @@
+//! Message templating shared by all handlers.
+//!
+//! Replaces `{{field}}` and `{{nested.path}}` placeholders with values from the event.
+
+use crate::event::Event;
+use serde_json::Value;
+
+/// Renders a template string with event data.
+///
+/// - `{{field}}` and `{{nested.path}}` are looked up with `Event::get_nested_value`
+/// - String values are inserted as-is, other JSON values as their JSON text
+/// - Placeholders for missing fields are left untouched (e.g. `{{missing}}`)
+/// - Single braces and unterminated `{{` are literal text
+/// - `\{{` renders as a literal `{{`, so `\{{field}}` prints `{{field}}`
+/// - Substituted values are not re-scanned for placeholders
+pub fn render_template(template: &str, event: &Event) -> String {
+    render_template_with(template, event, &[])
+}
+
+/// Renders a template like `render_template`, filling placeholders named in
+/// `vars` from there before looking at the event.
+pub fn render_template_with(template: &str, event: &Event, vars: &[(&str, &str)]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        if rest[..start].ends_with('\\') {
+            result.push_str(&rest[..start - 1]);
+            result.push_str("{{");
+            rest = &rest[start + 2..];
+            continue;
+        }
+
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+
+        result.push_str(&rest[..start]);
+        let placeholder = &rest[start..start + 2 + len + 2];
+        let key = rest[start + 2..start + 2 + len].trim();
+
+        if let Some((_, value)) = vars.iter().find(|(name, _)| *name == key) {
+            result.push_str(value);
+        } else {
+            match event.get_nested_value(key) {
+                Some(Value::String(s)) => result.push_str(s),
+                Some(other) => result.push_str(&other.to_string()),
+                None => result.push_str(placeholder),
+            }
+        }
+
+        rest = &rest[start + placeholder.len()..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_level_fields() {
+        let event = Event::from_json(r#"{"session_id": "abc", "count": 3}"#).unwrap();
+        assert_eq!(
+            render_template("Claude finished in session {{session_id}} ({{count}})", &event),
+            "Claude finished in session abc (3)"
+        );
+    }
+
+    #[test]
+    fn test_nested_paths() {
+        let event = Event::from_json(r#"{"tool_input": {"command": "ls"}, "calls": [{"id": 1}]}"#).unwrap();
+        assert_eq!(
+            render_template("{{tool_input.command}} / {{ calls.0.id }}", &event),
+            "ls / 1"
+        );
+    }
+
+    #[test]
+    fn test_session_id_from_opencode_event() {
+        let event = Event::from_json(r#"{"type": "session.idle", "sessionID": "ses_1"}"#).unwrap();
+        assert_eq!(render_template("session {{session_id}}", &event), "session ses_1");
+    }
+
+    #[test]
+    fn test_missing_fields_left_literal() {
+        let event = Event::from_json(r#"{"status": "ok"}"#).unwrap();
+        assert_eq!(render_template("{{status}} {{missing}}", &event), "ok {{missing}}");
+    }
+
+    #[test]
+    fn test_literal_braces() {
+        let event = Event::from_json(r#"{"status": "ok"}"#).unwrap();
+        assert_eq!(render_template("{status} {{status}} {{unterminated", &event), "{status} ok {{unterminated");
+    }
+
+    #[test]
+    fn test_escaped_braces_are_literal() {
+        let event = Event::from_json(r#"{"status": "ok"}"#).unwrap();
+        assert_eq!(
+            render_template(r"\{{status}} is {{status}}, \{{ alone", &event),
+            "{{status}} is ok, {{ alone"
+        );
+    }
+
+    #[test]
+    fn test_values_not_rescanned() {
+        let event = Event::from_json(r#"{"a": "{{b}}", "b": "nope"}"#).unwrap();
+        assert_eq!(render_template("{{a}}", &event), "{{b}}");
+    }
+
+    #[test]
+    fn test_vars_take_precedence() {
+        let event = Event::from_json(r#"{"message": "raw", "cwd": "/tmp"}"#).unwrap();
+        assert_eq!(
+            render_template_with("{{message}} in {{cwd}}", &event, &[("message", "{{cwd}}")]),
+            "{{cwd}} in /tmp"
+        );
+    }
+}
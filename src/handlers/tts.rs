@@ -0,0 +1,143 @@
+//! Text-to-speech notification handler.
+//!
+//! Speaks notification messages using the platform's TTS command
+//! (`say` on macOS, `espeak` elsewhere).
+
+use crate::error::NotificationError;
+use crate::event::Event;
+use crate::handlers::{default_text, handler_command, template, Handler, HandlerResult};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Default TTS command for this platform.
+#[cfg(target_os = "macos")]
+const DEFAULT_TTS_COMMAND: &str = "say";
+
+/// Default TTS command for this platform.
+#[cfg(not(target_os = "macos"))]
+const DEFAULT_TTS_COMMAND: &str = "espeak";
+
+/// Handler for text-to-speech notifications.
+pub struct TtsHandler;
+
+#[async_trait]
+impl Handler for TtsHandler {
+    fn handler_type(&self) -> &str {
+        "tts"
+    }
+
+    async fn handle(&self, event: &Event, config: &HashMap<String, Value>) -> HandlerResult<()> {
+        // Get message template or use default
+        let text = render_speech(config.get("message"), event);
+
+        // Get optional TTS command (e.g. "spd-say"), defaults to the platform command
+        let command = config
+            .get("command")
+            .and_then(|v| v.as_str())
+            .unwrap_or(DEFAULT_TTS_COMMAND);
+
+        speak(command, &text).await
+    }
+}
+
+async fn speak(command: &str, text: &str) -> HandlerResult<()> {
+    let output = handler_command(command)
+        .arg(text)
+        .output()
+        .await
+        .map_err(|e| NotificationError::Handler(format!("Failed to execute {}: {}", command, e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(NotificationError::Handler(format!("{} failed: {}", command, stderr.trim())));
+    }
+
+    Ok(())
+}
+
+/// Renders the text to speak.
+///
+/// Supports `{{hook_type}}`, `{{message}}`, and any top-level event field.
+/// `{{message}}` falls back to a default describing the hook when the event has no message.
+fn render_speech(template: Option<&Value>, event: &Event) -> String {
+    let message = event
+        .get_str("message")
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| default_text(event));
+
+    let template_str = match template {
+        Some(Value::String(s)) => s,
+        _ => return message,
+    };
+
+    let hook_type = event.event_type().unwrap_or("Event");
+    template::render_template_with(template_str, event, &[("hook_type", hook_type), ("message", &message)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handler_type() {
+        let handler = TtsHandler;
+        assert_eq!(handler.handler_type(), "tts");
+    }
+
+    #[test]
+    fn test_render_speech_placeholders() {
+        let event = Event::from_json(
+            r#"{"hook_event_name": "Notification", "message": "needs input", "cwd": "/tmp"}"#,
+        )
+        .unwrap();
+        let template = Value::String("{{hook_type}}: {{message}} in {{cwd}}".to_string());
+
+        assert_eq!(render_speech(Some(&template), &event), "Notification: needs input in /tmp");
+    }
+
+    #[test]
+    fn test_render_speech_default_message() {
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        let template = Value::String("{{message}}".to_string());
+
+        assert_eq!(render_speech(Some(&template), &event), "Claude Code Stop: Claude finished");
+        assert_eq!(render_speech(None, &event), "Claude Code Stop: Claude finished");
+    }
+
+    #[test]
+    fn test_render_speech_message_not_rescanned() {
+        let event = Event::from_json(
+            r#"{"hook_event_name": "Notification", "message": "literal {{cwd}}", "cwd": "/tmp"}"#,
+        )
+        .unwrap();
+        let template = Value::String("{{message}}".to_string());
+
+        assert_eq!(render_speech(Some(&template), &event), "literal {{cwd}}");
+    }
+
+    #[tokio::test]
+    async fn test_command_failure() {
+        let handler = TtsHandler;
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        let mut config = HashMap::new();
+        config.insert("command".to_string(), Value::String("false".to_string()));
+
+        let result = handler.handle(&event, &config).await;
+        assert!(result.unwrap_err().to_string().contains("false failed"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_command() {
+        let handler = TtsHandler;
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        let mut config = HashMap::new();
+        config.insert(
+            "command".to_string(),
+            Value::String("boopifier-no-such-tts".to_string()),
+        );
+
+        let result = handler.handle(&event, &config).await;
+        assert!(result.unwrap_err().to_string().contains("Failed to execute"));
+    }
+}
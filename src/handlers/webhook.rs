@@ -4,13 +4,13 @@
 
 use crate::error::NotificationError;
 use crate::event::Event;
-use crate::handlers::{Handler, HandlerResult};
+use crate::handlers::{default_text, template, Handler, HandlerResult, TIMEOUT_BUDGET_KEY};
 use async_trait::async_trait;
 use once_cell::sync::OnceCell;
 use reqwest::Client;
 use serde_json::{json, Value};
 use std::collections::HashMap;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Shared HTTP client with connection pooling.
 static HTTP_CLIENT: OnceCell<Client> = OnceCell::new();
@@ -31,7 +31,27 @@ fn get_http_client() -> HandlerResult<&'static Client> {
     })
 }
 
+/// Default delay before the first retry, when `retries` is set.
+const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// `Retry-After` delays longer than this aren't waited for; the request fails instead.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// Time kept back from the handler timeout so the last error can be reported
+/// before the handler is cancelled.
+const BUDGET_MARGIN: Duration = Duration::from_millis(250);
+
 /// Handler for webhook notifications.
+///
+/// With `retries` set, requests that fail to connect, time out, or get a 5xx
+/// or 429 response are retried up to that many times. Delays start at
+/// `retry_backoff_ms` (default 500) and double each attempt, unless the
+/// server sends a `Retry-After` in seconds.
+///
+/// All attempts and delays share the handler's `timeout_ms`: a retry that
+/// couldn't finish in the time left isn't made, and each request is cut
+/// short at the deadline, so the handler fails with the last HTTP error
+/// rather than a timeout.
 pub struct WebhookHandler;
 
 #[async_trait]
@@ -55,11 +75,17 @@ impl Handler for WebhookHandler {
             .and_then(|v| v.as_str())
             .unwrap_or("json");
 
+        // Get optional request timeout in milliseconds (defaults to the client's 30s)
+        let timeout = config
+            .get("timeout")
+            .and_then(|v| v.as_u64())
+            .map(Duration::from_millis);
+
         // Build the payload
         let payload = build_payload(payload_type, event, config)?;
 
         // Send the webhook
-        send_webhook(url, &payload).await?;
+        send_webhook(url, &payload, timeout, &Retry::from_config(config)).await?;
 
         Ok(())
     }
@@ -113,6 +139,18 @@ fn build_discord_payload(event: &Event, config: &HashMap<String, Value>) -> Hand
         payload["username"] = Value::String(username.to_string());
     }
 
+    // Optional: add an embed titled with the hook type
+    if config.get("embed").and_then(|v| v.as_bool()).unwrap_or(false) {
+        let mut embed = json!({
+            "title": format!("Claude Code {}", event.event_type().unwrap_or("Event")),
+            "description": content,
+        });
+        if let Some(session_id) = event.get_str("session_id") {
+            embed["footer"] = json!({"text": format!("Session {}", session_id)});
+        }
+        payload["embeds"] = json!([embed]);
+    }
+
     Ok(payload)
 }
 
@@ -129,20 +167,7 @@ fn build_json_payload(event: &Event, config: &HashMap<String, Value>) -> Handler
 /// Recursively render template variables in a JSON value
 fn render_payload_template(value: &Value, event: &Event) -> Value {
     match value {
-        Value::String(s) => {
-            let mut result = s.clone();
-            for (key, val) in &event.data {
-                let placeholder = format!("{{{{{}}}}}", key);
-                if result.contains(&placeholder) {
-                    let value_str = match val {
-                        Value::String(s) => s.clone(),
-                        other => other.to_string(),
-                    };
-                    result = result.replace(&placeholder, &value_str);
-                }
-            }
-            Value::String(result)
-        }
+        Value::String(s) => Value::String(template::render_template(s, event)),
         Value::Object(map) => {
             let mut new_map = serde_json::Map::new();
             for (k, v) in map {
@@ -160,41 +185,137 @@ fn render_payload_template(value: &Value, event: &Event) -> Value {
 fn render_template(template: Option<&Value>, event: &Event) -> String {
     let template_str = match template {
         Some(Value::String(s)) => s,
-        _ => return format!("Claude Code Event: {:?}", event.data),
+        _ => return default_text(event),
     };
 
-    let mut result = template_str.clone();
-
-    // Simple template substitution: {{field_name}}
-    for (key, value) in &event.data {
-        let placeholder = format!("{{{{{}}}}}", key);
-        if result.contains(&placeholder) {
-            let value_str = match value {
-                Value::String(s) => s.clone(),
-                other => other.to_string(),
-            };
-            result = result.replace(&placeholder, &value_str);
+    template::render_template(template_str, event)
+}
+
+/// How often and how long to wait before retrying a failed request.
+struct Retry {
+    retries: u32,
+    backoff: Duration,
+    /// Total time for every attempt and delay (the handler's timeout).
+    budget: Option<Duration>,
+}
+
+impl Retry {
+    /// Reads `retries` (default 0), `retry_backoff_ms` and the handler
+    /// timeout budget from handler config.
+    fn from_config(config: &HashMap<String, Value>) -> Self {
+        Self {
+            retries: config.get("retries").and_then(|v| v.as_u64()).unwrap_or(0) as u32,
+            backoff: config
+                .get("retry_backoff_ms")
+                .and_then(|v| v.as_u64())
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_RETRY_BACKOFF),
+            budget: config
+                .get(TIMEOUT_BUDGET_KEY)
+                .and_then(|v| v.as_u64())
+                .map(Duration::from_millis),
         }
     }
 
-    result
+    /// Delay before retry number `retry` (0-based): the backoff, doubled each time.
+    fn delay(&self, retry: u32) -> Duration {
+        self.backoff.saturating_mul(1 << retry.min(16))
+    }
 }
 
-async fn send_webhook(url: &str, payload: &Value) -> HandlerResult<()> {
-    let client = get_http_client()?;
+/// A failed request, and whether it's worth retrying.
+struct Failure {
+    error: NotificationError,
+    retryable: bool,
+    retry_after: Option<Duration>,
+}
 
-    let response = client
-        .post(url)
-        .json(payload)
-        .send()
-        .await
-        .map_err(|e| NotificationError::Network(format!("Failed to send webhook: {}", e)))?;
+impl Failure {
+    fn fatal(error: NotificationError) -> Self {
+        Self {
+            error,
+            retryable: false,
+            retry_after: None,
+        }
+    }
+}
 
-    if !response.status().is_success() {
-        return Err(NotificationError::Network(format!(
-            "Webhook request failed with status: {}",
-            response.status()
-        )));
+async fn send_webhook(url: &str, payload: &Value, timeout: Option<Duration>, retry: &Retry) -> HandlerResult<()> {
+    let deadline = retry
+        .budget
+        .map(|budget| Instant::now() + budget.saturating_sub(BUDGET_MARGIN));
+    let mut attempt = 0;
+    loop {
+        // Don't let a request run past the deadline
+        let timeout = match deadline {
+            Some(deadline) => {
+                let left = deadline.saturating_duration_since(Instant::now());
+                Some(timeout.map_or(left, |timeout| timeout.min(left)))
+            }
+            None => timeout,
+        };
+        let failure = match send_once(url, payload, timeout).await {
+            Ok(()) => return Ok(()),
+            Err(failure) => failure,
+        };
+
+        let delay = failure.retry_after.unwrap_or_else(|| retry.delay(attempt));
+        let out_of_time = deadline.is_some_and(|deadline| Instant::now() + delay >= deadline);
+        if !failure.retryable || attempt >= retry.retries || delay > MAX_RETRY_AFTER || out_of_time {
+            if out_of_time && attempt < retry.retries {
+                log::warn!("Webhook retry in {:?} would exceed the handler timeout", delay);
+            }
+            if attempt > 0 {
+                log::warn!("Webhook failed after {} attempts", attempt + 1);
+            }
+            return Err(failure.error);
+        }
+
+        attempt += 1;
+        log::warn!("{}; retry {} of {} in {:?}", failure.error, attempt, retry.retries, delay);
+        tokio::time::sleep(delay).await;
+    }
+}
+
+async fn send_once(url: &str, payload: &Value, timeout: Option<Duration>) -> Result<(), Failure> {
+    let client = get_http_client().map_err(Failure::fatal)?;
+
+    let mut request = client.post(url).json(payload);
+    if let Some(timeout) = timeout {
+        request = request.timeout(timeout);
+    }
+
+    let response = request.send().await.map_err(|e| Failure {
+        retryable: e.is_connect() || e.is_timeout(),
+        error: NotificationError::Network(format!("Failed to send webhook: {}", e)),
+        retry_after: None,
+    })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        // Rate limited (e.g. Discord 429) - report when we're allowed to retry
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok());
+
+        // Retry-After is either seconds or an HTTP date; only seconds get a unit
+        let seconds: Option<u64> = retry_after.and_then(|v| v.trim().parse().ok());
+        return Err(Failure {
+            retryable: status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS,
+            retry_after: seconds.map(Duration::from_secs),
+            error: NotificationError::Network(match (seconds, retry_after) {
+                (Some(seconds), _) => format!(
+                    "Webhook request failed with status: {} (retry after {}s)",
+                    status, seconds
+                ),
+                (None, Some(retry_after)) => format!(
+                    "Webhook request failed with status: {} (retry after {})",
+                    status, retry_after
+                ),
+                (None, None) => format!("Webhook request failed with status: {}", status),
+            }),
+        });
     }
 
     Ok(())
@@ -203,6 +324,71 @@ async fn send_webhook(url: &str, payload: &Value) -> HandlerResult<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// A captured HTTP request: (lowercased headers, body).
+    type CapturedRequest = (String, String);
+
+    /// Starts a one-shot HTTP server that replies with `status_line` and
+    /// sends the received request back over the returned channel.
+    fn spawn_mock_server(status_line: &'static str) -> (String, mpsc::Receiver<CapturedRequest>) {
+        spawn_mock_server_with_headers(status_line, "")
+    }
+
+    /// Like `spawn_mock_server`, but adds `extra_headers` (CRLF-terminated) to the reply.
+    fn spawn_mock_server_with_headers(
+        status_line: &'static str,
+        extra_headers: &'static str,
+    ) -> (String, mpsc::Receiver<CapturedRequest>) {
+        spawn_mock_server_sequence(vec![(status_line, extra_headers)])
+    }
+
+    /// Starts an HTTP server that answers one request per `(status_line, extra_headers)`
+    /// reply, in order, sending each received request back over the returned channel.
+    fn spawn_mock_server_sequence(
+        replies: Vec<(&'static str, &'static str)>,
+    ) -> (String, mpsc::Receiver<CapturedRequest>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            for (status_line, extra_headers) in replies {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+
+                let mut headers = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                    let lower = line.to_ascii_lowercase();
+                    if let Some(len) = lower.strip_prefix("content-length:") {
+                        content_length = len.trim().parse().unwrap();
+                    }
+                    headers.push_str(&lower);
+                }
+
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+
+                let response = format!(
+                    "HTTP/1.1 {}\r\n{}content-length: 0\r\nconnection: close\r\n\r\n",
+                    status_line, extra_headers
+                );
+                reader.get_mut().write_all(response.as_bytes()).unwrap();
+
+                let _ = tx.send((headers, String::from_utf8(body).unwrap()));
+            }
+        });
+
+        (url, rx)
+    }
 
     #[test]
     fn test_handler_type() {
@@ -246,4 +432,265 @@ mod tests {
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("requires 'url'"));
     }
+
+    #[tokio::test]
+    async fn test_posts_event_json() {
+        let (url, rx) = spawn_mock_server("200 OK");
+        let handler = WebhookHandler;
+        let event = Event::from_json(r#"{"hook_event_name": "Stop", "session_id": "abc"}"#).unwrap();
+        let mut config = HashMap::new();
+        config.insert("url".to_string(), Value::String(url));
+
+        handler.handle(&event, &config).await.unwrap();
+
+        let (headers, body) = rx.recv().unwrap();
+        assert!(headers.contains("content-type: application/json"));
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body, event.as_value());
+    }
+
+    #[tokio::test]
+    async fn test_server_error_status() {
+        let (url, _rx) = spawn_mock_server("500 Internal Server Error");
+        let handler = WebhookHandler;
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        let mut config = HashMap::new();
+        config.insert("url".to_string(), Value::String(url));
+
+        let result = handler.handle(&event, &config).await;
+        assert!(result.unwrap_err().to_string().contains("500"));
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        // Accept the connection but never respond
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+
+        let handler = WebhookHandler;
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        let mut config = HashMap::new();
+        config.insert("url".to_string(), Value::String(url));
+        config.insert("timeout".to_string(), json!(100));
+
+        let result = handler.handle(&event, &config).await;
+        assert!(result.is_err());
+        drop(listener);
+    }
+
+    #[test]
+    fn test_slack_default_text_includes_hook_and_message() {
+        let event = Event::from_json(
+            r#"{"hook_event_name": "Notification", "message": "Claude needs your permission"}"#,
+        )
+        .unwrap();
+
+        let payload = build_slack_payload(&event, &HashMap::new()).unwrap();
+        assert_eq!(payload, json!({"text": "Claude Code Notification: Claude needs your permission"}));
+    }
+
+    #[test]
+    fn test_slack_default_text_uses_reason() {
+        let event = Event::from_json(r#"{"hook_event_name": "Stop", "reason": "done"}"#).unwrap();
+
+        let payload = build_slack_payload(&event, &HashMap::new()).unwrap();
+        assert_eq!(payload["text"], "Claude Code Stop: done");
+    }
+
+    #[test]
+    fn test_slack_default_text_without_message() {
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+
+        let payload = build_slack_payload(&event, &HashMap::new()).unwrap();
+        assert_eq!(payload["text"], "Claude Code Stop: Claude finished");
+    }
+
+    #[tokio::test]
+    async fn test_slack_posts_text_payload() {
+        let (url, rx) = spawn_mock_server("200 OK");
+        let handler = WebhookHandler;
+        let event = Event::from_json(r#"{"hook_event_name": "Stop", "message": "All done"}"#).unwrap();
+        let mut config = HashMap::new();
+        config.insert("url".to_string(), Value::String(url));
+        config.insert("type".to_string(), json!("slack"));
+        config.insert("channel".to_string(), json!("#builds"));
+
+        handler.handle(&event, &config).await.unwrap();
+
+        let (_, body) = rx.recv().unwrap();
+        let body: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body, json!({"text": "Claude Code Stop: All done", "channel": "#builds"}));
+    }
+
+    #[tokio::test]
+    async fn test_slack_error_status() {
+        let (url, _rx) = spawn_mock_server("403 Forbidden");
+        let handler = WebhookHandler;
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        let mut config = HashMap::new();
+        config.insert("url".to_string(), Value::String(url));
+        config.insert("type".to_string(), json!("slack"));
+
+        let result = handler.handle(&event, &config).await;
+        assert!(result.unwrap_err().to_string().contains("403"));
+    }
+
+    #[test]
+    fn test_discord_embed_payload() {
+        let event = Event::from_json(r#"{"hook_event_name": "Stop", "session_id": "abc123"}"#).unwrap();
+        let mut config = HashMap::new();
+        config.insert("embed".to_string(), json!(true));
+
+        let payload = build_discord_payload(&event, &config).unwrap();
+        assert_eq!(
+            payload,
+            json!({
+                "content": "Claude Code Stop: Claude finished",
+                "embeds": [{
+                    "title": "Claude Code Stop",
+                    "description": "Claude Code Stop: Claude finished",
+                    "footer": {"text": "Session abc123"}
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_discord_no_embed_by_default() {
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+
+        let payload = build_discord_payload(&event, &HashMap::new()).unwrap();
+        assert!(payload.get("embeds").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_discord_rate_limited() {
+        let (url, _rx) = spawn_mock_server_with_headers("429 Too Many Requests", "retry-after: 2\r\n");
+        let handler = WebhookHandler;
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        let mut config = HashMap::new();
+        config.insert("url".to_string(), Value::String(url));
+        config.insert("type".to_string(), json!("discord"));
+
+        let message = handler.handle(&event, &config).await.unwrap_err().to_string();
+        assert!(message.contains("429"));
+        assert!(message.contains("retry after 2s"));
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_date_reported_verbatim() {
+        let (url, rx) = spawn_mock_server_with_headers(
+            "503 Service Unavailable",
+            "retry-after: Wed, 21 Oct 2015 07:28:00 GMT\r\n",
+        );
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+
+        let message = WebhookHandler.handle(&event, &retrying(url, 0)).await.unwrap_err().to_string();
+        assert!(message.contains("(retry after Wed, 21 Oct 2015 07:28:00 GMT)"));
+        assert_eq!(request_count(&rx), 1);
+    }
+
+    /// Counts the requests a mock server received, waiting for it to finish.
+    fn request_count(rx: &mpsc::Receiver<CapturedRequest>) -> usize {
+        std::iter::from_fn(|| rx.recv_timeout(Duration::from_secs(5)).ok()).count()
+    }
+
+    fn retrying(url: String, retries: u64) -> HashMap<String, Value> {
+        let mut config = HashMap::new();
+        config.insert("url".to_string(), Value::String(url));
+        config.insert("retries".to_string(), json!(retries));
+        config.insert("retry_backoff_ms".to_string(), json!(10));
+        config
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_success() {
+        let (url, rx) = spawn_mock_server_sequence(vec![
+            ("503 Service Unavailable", ""),
+            ("502 Bad Gateway", ""),
+            ("200 OK", ""),
+        ]);
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+
+        WebhookHandler.handle(&event, &retrying(url, 3)).await.unwrap();
+        assert_eq!(request_count(&rx), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retries_exhausted() {
+        let (url, rx) = spawn_mock_server_sequence(vec![("500 Internal Server Error", ""); 3]);
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+
+        let err = WebhookHandler.handle(&event, &retrying(url, 2)).await.unwrap_err();
+        assert!(err.to_string().contains("500"));
+        // The first attempt plus two retries
+        assert_eq!(request_count(&rx), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_respects_retry_after() {
+        let (url, rx) = spawn_mock_server_sequence(vec![
+            ("429 Too Many Requests", "retry-after: 1\r\n"),
+            ("200 OK", ""),
+        ]);
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+
+        let started = std::time::Instant::now();
+        WebhookHandler.handle(&event, &retrying(url, 1)).await.unwrap();
+        assert!(started.elapsed() >= Duration::from_secs(1));
+        assert_eq!(request_count(&rx), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_beyond_handler_timeout_fails_fast() {
+        let (url, rx) = spawn_mock_server_with_headers("429 Too Many Requests", "retry-after: 15\r\n");
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        let mut config = retrying(url, 3);
+        config.insert(TIMEOUT_BUDGET_KEY.to_string(), json!(10_000));
+
+        let started = std::time::Instant::now();
+        let err = WebhookHandler.handle(&event, &config).await.unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(err.to_string().contains("429"));
+        assert_eq!(request_count(&rx), 1);
+    }
+
+    #[tokio::test]
+    async fn test_backoff_stops_within_handler_timeout() {
+        let (url, rx) = spawn_mock_server_sequence(vec![("503 Service Unavailable", ""); 3]);
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        let mut config = retrying(url, 10);
+        config.insert("retry_backoff_ms".to_string(), json!(300));
+        config.insert(TIMEOUT_BUDGET_KEY.to_string(), json!(1_500));
+
+        let started = std::time::Instant::now();
+        let err = WebhookHandler.handle(&event, &config).await.unwrap_err();
+        assert!(started.elapsed() < Duration::from_millis(1_500));
+        assert!(err.to_string().contains("503"));
+        // Waits of 300ms and 600ms fit; the next 1200ms wait doesn't
+        assert_eq!(request_count(&rx), 3);
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_not_retried() {
+        let (url, rx) = spawn_mock_server("404 Not Found");
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+
+        let err = WebhookHandler.handle(&event, &retrying(url, 3)).await.unwrap_err();
+        assert!(err.to_string().contains("404"));
+        assert_eq!(request_count(&rx), 1);
+    }
+
+    #[test]
+    fn test_retry_backoff_doubles() {
+        let retry = Retry::from_config(&retrying(String::new(), 3));
+        assert_eq!(retry.retries, 3);
+        assert_eq!(retry.delay(0), Duration::from_millis(10));
+        assert_eq!(retry.delay(1), Duration::from_millis(20));
+        assert_eq!(retry.delay(2), Duration::from_millis(40));
+
+        let retry = Retry::from_config(&HashMap::new());
+        assert_eq!(retry.retries, 0);
+        assert_eq!(retry.delay(0), DEFAULT_RETRY_BACKOFF);
+    }
 }
@@ -1,38 +1,127 @@
 //! PreCompact hook implementation.
 
-use super::{HandlerOutcome, Hook};
-use serde_json::{json, Value};
+use super::{passive_response, HandlerOutcome, Hook};
+use crate::event::Event;
+use serde_json::Value;
+
+/// Event fields that may carry the size of the context about to be compacted,
+/// with the unit to report them in. The first field present for a unit wins.
+const SIZE_FIELDS: &[(&str, &str)] = &[
+    ("token_count", "tokens"),
+    ("tokens", "tokens"),
+    ("context_tokens", "tokens"),
+    ("message_count", "messages"),
+];
 
 /// Handler for PreCompact hooks.
 ///
 /// These hooks fire before Claude Code compacts conversation history.
-/// Returns an empty object {} to allow passive observation.
-/// Future: Could implement logic to prevent compaction or save history.
-pub struct PreCompactHook;
+/// The response is always passive (an empty object, or a `systemMessage`
+/// listing handler errors); `message` describes the compaction for
+/// notifications.
+pub struct PreCompactHook {
+    trigger: Option<String>,
+    sizes: Vec<(i64, &'static str)>,
+}
+
+impl PreCompactHook {
+    pub fn from_event(event: &Event) -> Self {
+        let mut sizes: Vec<(i64, &'static str)> = Vec::new();
+        for &(field, unit) in SIZE_FIELDS {
+            if sizes.iter().any(|&(_, u)| u == unit) {
+                continue;
+            }
+            if let Some(size) = event.get_i64(field) {
+                sizes.push((size, unit));
+            }
+        }
+
+        Self {
+            trigger: event.get_str("trigger").map(str::to_string),
+            sizes,
+        }
+    }
+
+    /// What triggered the compaction: "manual" (`/compact`) or "auto" (context full).
+    pub fn trigger(&self) -> Option<&str> {
+        self.trigger.as_deref()
+    }
+
+    /// Describes the compaction, e.g. "auto compaction (150000 tokens, 42 messages)".
+    pub fn message(&self) -> String {
+        let text = match &self.trigger {
+            Some(trigger) => format!("{} compaction", trigger),
+            None => "compaction".to_string(),
+        };
+
+        if self.sizes.is_empty() {
+            return text;
+        }
+
+        let sizes: Vec<String> = self
+            .sizes
+            .iter()
+            .map(|(size, unit)| format!("{} {}", size, unit))
+            .collect();
+        format!("{} ({})", text, sizes.join(", "))
+    }
+}
 
 impl Hook for PreCompactHook {
     fn hook_type(&self) -> &str {
         "PreCompact"
     }
 
-    fn generate_response(&self, _outcomes: &[HandlerOutcome]) -> Value {
-        // Return empty object - currently just observing
-        // Future: Could return {"decision": "block"} to prevent compaction
-        // or archive conversation history before it's compacted
-        json!({})
+    fn generate_response(&self, outcomes: &[HandlerOutcome]) -> Value {
+        // Just observing - compaction is never blocked or altered
+        passive_response(outcomes)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
+
+    fn hook(event: Value) -> PreCompactHook {
+        PreCompactHook::from_event(&Event::from_value(event).unwrap())
+    }
 
     #[test]
     fn test_pre_compact_hook_response() {
-        let hook = PreCompactHook;
+        let hook = hook(json!({"hook_event_name": "PreCompact", "trigger": "auto"}));
         assert_eq!(hook.hook_type(), "PreCompact");
 
         let response = hook.generate_response(&[]);
         assert_eq!(response, json!({}));
+
+        let response = hook.generate_response(&[HandlerOutcome::Block("no".to_string())]);
+        assert_eq!(response, json!({}));
+    }
+
+    #[test]
+    fn test_manual_trigger_message() {
+        let hook = hook(json!({"hook_event_name": "PreCompact", "trigger": "manual", "custom_instructions": ""}));
+        assert_eq!(hook.trigger(), Some("manual"));
+        assert_eq!(hook.message(), "manual compaction");
+    }
+
+    #[test]
+    fn test_auto_trigger_message_with_sizes() {
+        let hook = hook(json!({
+            "hook_event_name": "PreCompact",
+            "trigger": "auto",
+            "token_count": 150000,
+            "tokens": 1,
+            "message_count": 42
+        }));
+        assert_eq!(hook.message(), "auto compaction (150000 tokens, 42 messages)");
+    }
+
+    #[test]
+    fn test_message_without_trigger() {
+        let hook = hook(json!({"hook_event_name": "PreCompact", "context_tokens": 9000}));
+        assert_eq!(hook.trigger(), None);
+        assert_eq!(hook.message(), "compaction (9000 tokens)");
     }
 }
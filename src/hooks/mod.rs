@@ -12,8 +12,8 @@ pub mod session;
 pub mod compact;
 
 use crate::event::Event;
-use anyhow::{bail, Result};
-use serde_json::Value;
+use crate::error::BoopError;
+use serde_json::{json, Value};
 
 /// Outcome from executing a notification handler
 #[derive(Debug, Clone)]
@@ -22,12 +22,22 @@ pub enum HandlerOutcome {
     Success,
     /// Handler failed with an error
     Error(String),
-    /// Handler requires user interaction (future: for PreToolUse)
-    #[allow(dead_code)]
+    /// Handler made a permission decision (used by PreToolUse and PermissionRequest)
     Interactive(InteractiveResponse),
+    /// Handler produced context to inject into the conversation (used by SessionStart)
+    Context(String),
+    /// Handler asked to block the action, with a reason shown to Claude
+    Block(String),
+    /// Handler asked to halt the agent entirely (used by Stop/SubagentStop)
+    Halt(String),
+    /// Handler didn't run, with why (e.g. "slack: quiet hours" or
+    /// "slack: rate limited"); responds like `Success`
+    Skipped(String),
+    /// Handler returned JSON to merge into the hook response (see `merge_responses`)
+    Response(serde_json::Map<String, Value>),
 }
 
-/// Interactive response from a handler (for PreToolUse hooks)
+/// Interactive response from a handler (for PreToolUse and PermissionRequest hooks)
 #[derive(Debug, Clone)]
 pub struct InteractiveResponse {
     pub decision: PermissionDecision,
@@ -35,13 +45,34 @@ pub struct InteractiveResponse {
 }
 
 /// Permission decision for tool use
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum PermissionDecision {
     Allow,
     Deny,
     Ask,
 }
 
+impl PermissionDecision {
+    /// The string Claude Code expects in `permissionDecision`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PermissionDecision::Allow => "allow",
+            PermissionDecision::Deny => "deny",
+            PermissionDecision::Ask => "ask",
+        }
+    }
+
+    /// Parses the strings `as_str` produces; anything else is `None`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "allow" => Some(PermissionDecision::Allow),
+            "deny" => Some(PermissionDecision::Deny),
+            "ask" => Some(PermissionDecision::Ask),
+            _ => None,
+        }
+    }
+}
+
 /// Trait for Claude Code hook types.
 ///
 /// Each hook type knows how to generate its own JSON response format.
@@ -51,24 +82,449 @@ pub trait Hook: Send + Sync {
 
     /// Generate the appropriate JSON response based on handler outcomes
     fn generate_response(&self, outcomes: &[HandlerOutcome]) -> Value;
+
+    /// Whether Claude Code feeds stderr back to Claude when this hook exits with code 2.
+    ///
+    /// For other hooks exit code 2 only shows stderr to the user, so errors
+    /// stay in the JSON response instead.
+    fn supports_blocking_feedback(&self) -> bool {
+        false
+    }
+
+    /// Whether `generate_response` for these outcomes would stop or deny the
+    /// agent (a halt, a block, or a deny), without re-parsing the JSON.
+    ///
+    /// Keys merged in from `Response` outcomes aren't considered.
+    fn response_is_blocking(&self, _outcomes: &[HandlerOutcome]) -> bool {
+        false
+    }
+}
+
+/// Returns the reason to report with exit code 2, if any handler failed and
+/// the hook can feed it back to Claude.
+pub fn blocking_feedback(hook: &dyn Hook, outcomes: &[HandlerOutcome]) -> Option<String> {
+    if hook.supports_blocking_feedback() {
+        aggregate_errors(outcomes)
+    } else {
+        None
+    }
+}
+
+/// Joins the messages selected by `select` into one string separated by "; ".
+fn join_messages<'a>(
+    outcomes: &'a [HandlerOutcome],
+    select: impl Fn(&'a HandlerOutcome) -> Option<&'a str>,
+) -> Option<String> {
+    let messages: Vec<&str> = outcomes.iter().filter_map(select).collect();
+
+    if messages.is_empty() {
+        None
+    } else {
+        Some(messages.join("; "))
+    }
+}
+
+/// Joins all handler error messages into a single string separated by "; ".
+///
+/// Returns `None` if no handler failed.
+pub fn aggregate_errors(outcomes: &[HandlerOutcome]) -> Option<String> {
+    join_messages(outcomes, |outcome| match outcome {
+        HandlerOutcome::Error(msg) => Some(msg.as_str()),
+        _ => None,
+    })
+}
+
+/// Joins all `Block` reasons into a single string separated by "; ".
+///
+/// Returns `None` if no handler asked to block.
+pub fn block_reason(outcomes: &[HandlerOutcome]) -> Option<String> {
+    join_messages(outcomes, |outcome| match outcome {
+        HandlerOutcome::Block(reason) => Some(reason.as_str()),
+        _ => None,
+    })
+}
+
+/// Joins all `Halt` reasons into a single string separated by "; ".
+///
+/// Returns `None` if no handler asked to halt.
+pub fn halt_reason(outcomes: &[HandlerOutcome]) -> Option<String> {
+    join_messages(outcomes, |outcome| match outcome {
+        HandlerOutcome::Halt(reason) => Some(reason.as_str()),
+        _ => None,
+    })
+}
+
+/// Picks the most restrictive decision among `Interactive` outcomes: deny,
+/// then ask, then allow. Among equal decisions the first one wins.
+///
+/// Returns `None` if no handler made a decision.
+pub fn strictest_decision(outcomes: &[HandlerOutcome]) -> Option<&InteractiveResponse> {
+    outcomes
+        .iter()
+        .filter_map(|outcome| match outcome {
+            HandlerOutcome::Interactive(response) => Some(response),
+            _ => None,
+        })
+        .rev()
+        .max_by_key(|response| match response.decision {
+            PermissionDecision::Allow => 0,
+            PermissionDecision::Ask => 1,
+            PermissionDecision::Deny => 2,
+        })
+}
+
+/// Generates the hook's response, then merges in handler `Response` outcomes.
+///
+/// The merge is shallow: each top-level key a handler returned replaces the
+/// key in the generated response, so handler keys win. Handlers are applied
+/// in config order, so a later handler wins over an earlier one.
+pub fn merge_responses(hook: &dyn Hook, outcomes: &[HandlerOutcome]) -> Value {
+    let mut response = hook.generate_response(outcomes);
+
+    for outcome in outcomes {
+        if let (HandlerOutcome::Response(fields), Value::Object(base)) = (outcome, &mut response) {
+            base.extend(fields.clone());
+        }
+    }
+
+    response
+}
+
+/// Response key that `add_version` sets to boopifier's version.
+pub const VERSION_FIELD: &str = "boopifier_version";
+
+/// Adds `"boopifier_version": "<crate version>"` to a response object, so
+/// tools reading boopifier's output can tell which release produced it.
+///
+/// Off by default (see `--response-version`): Claude Code may reject unknown
+/// response keys. Non-object responses are left alone.
+pub fn add_version(response: &mut Value) {
+    if let Value::Object(map) = response {
+        map.insert(VERSION_FIELD.to_string(), json!(env!("CARGO_PKG_VERSION")));
+    }
+}
+
+/// Builds a passive response that surfaces handler errors to the user.
+///
+/// Returns `{}` when every handler succeeded, otherwise a `systemMessage`
+/// listing all failures.
+pub(crate) fn passive_response(outcomes: &[HandlerOutcome]) -> Value {
+    match aggregate_errors(outcomes) {
+        Some(errors) => json!({
+            "systemMessage": format!("Boopifier warning: {}", errors)
+        }),
+        None => json!({}),
+    }
+}
+
+/// Builds the hook for an event, given the hook type name it was resolved as.
+type HookBuilder = fn(&str, &Event) -> Result<Box<dyn Hook>, BoopError>;
+
+/// Every hook type `hook_from_event` accepts, with how to build it.
+///
+/// `HOOK_TYPES` is derived from this table, so the two can't drift apart.
+const HOOKS: &[(&str, HookBuilder)] = &[
+    ("Stop", |name, event| Ok(Box::new(stop::StopHook::for_hook(name, event)))),
+    ("SubagentStop", |name, event| Ok(Box::new(stop::StopHook::for_hook(name, event)))),
+    ("Notification", |_, event| Ok(Box::new(notification::NotificationHook::from_event(event)))),
+    ("PreToolUse", |_, event| Ok(Box::new(tool_use::PreToolUseHook::from_event(event)?))),
+    ("PostToolUse", |_, _| Ok(Box::new(tool_use::PostToolUseHook))),
+    ("PermissionRequest", |_, _| Ok(Box::new(permission::PermissionRequestHook))),
+    ("UserPromptSubmit", |_, _| Ok(Box::new(prompt::UserPromptSubmitHook))),
+    ("SessionStart", |_, _| Ok(Box::new(session::SessionStartHook))),
+    ("SessionEnd", |_, _| Ok(Box::new(session::SessionEndHook))),
+    ("PreCompact", |_, event| Ok(Box::new(compact::PreCompactHook::from_event(event)))),
+];
+
+/// All hook type names that `hook_from_event` accepts.
+pub const HOOK_TYPES: &[&str] = &hook_names();
+
+/// The names in `HOOKS`, in order.
+const fn hook_names() -> [&'static str; HOOKS.len()] {
+    let mut names = [""; HOOKS.len()];
+    let mut i = 0;
+    while i < HOOKS.len() {
+        names[i] = HOOKS[i].0;
+        i += 1;
+    }
+    names
+}
+
+/// Returns the hook-specific fields Claude Code always sends for `hook_type`.
+///
+/// Used by `Event::validate`. Unknown hook types have no required fields.
+pub fn required_fields(hook_type: &str) -> &'static [&'static str] {
+    match hook_type {
+        "PreToolUse" | "PermissionRequest" => &["tool_name", "tool_input"],
+        "PostToolUse" => &["tool_name", "tool_input", "tool_response"],
+        "Notification" => &["message"],
+        "UserPromptSubmit" => &["prompt"],
+        "Stop" | "SubagentStop" => &["stop_hook_active"],
+        "PreCompact" => &["trigger"],
+        "SessionStart" => &["source"],
+        "SessionEnd" => &["reason"],
+        _ => &[],
+    }
+}
+
+/// Returns true if `name` is a hook type that boopifier can handle.
+///
+/// Useful for validating configuration without constructing an `Event`.
+pub fn is_known_hook_type(name: &str) -> bool {
+    HOOK_TYPES.contains(&name)
+}
+
+/// Environment variable that, when set to a hook name, overrides the event's
+/// own hook type in `hook_from_event` (for debugging).
+///
+/// The boopifier binary also rewrites the event's `hook_event_name`, so
+/// matching, enabled hooks, debounce and digests see the forced type.
+pub const FORCE_HOOK_ENV: &str = "BOOPIFIER_FORCE_HOOK";
+
+/// Returns the hook type forced by `BOOPIFIER_FORCE_HOOK`, if it is set and non-empty.
+pub fn forced_hook_type() -> Option<String> {
+    std::env::var(FORCE_HOOK_ENV).ok().filter(|name| !name.is_empty())
 }
 
 /// Create a Hook instance from an event by parsing the hook_event_name
-pub fn hook_from_event(event: &Event) -> Result<Box<dyn Hook>> {
-    let hook_event_name = event
-        .get_str("hook_event_name")
-        .unwrap_or("unknown");
-
-    match hook_event_name {
-        "Stop" | "SubagentStop" => Ok(Box::new(stop::StopHook::new(hook_event_name))),
-        "Notification" => Ok(Box::new(notification::NotificationHook)),
-        "PreToolUse" => Ok(Box::new(tool_use::PreToolUseHook::from_event(event)?)),
-        "PostToolUse" => Ok(Box::new(tool_use::PostToolUseHook)),
-        "PermissionRequest" => Ok(Box::new(permission::PermissionRequestHook)),
-        "UserPromptSubmit" => Ok(Box::new(prompt::UserPromptSubmitHook)),
-        "SessionStart" => Ok(Box::new(session::SessionStartHook)),
-        "SessionEnd" => Ok(Box::new(session::SessionEndHook)),
-        "PreCompact" => Ok(Box::new(compact::PreCompactHook)),
-        _ => bail!("Unknown hook type: {}", hook_event_name),
+///
+/// If `BOOPIFIER_FORCE_HOOK` is set and non-empty, that hook type is used
+/// instead (see `hook_from_event_as`).
+pub fn hook_from_event(event: &Event) -> Result<Box<dyn Hook>, BoopError> {
+    hook_from_event_as(event, forced_hook_type().as_deref())
+}
+
+/// Create a Hook instance for `forced` if given, otherwise for the event's hook_event_name
+///
+/// Hook-specific fields (e.g. `tool_name`) are still read from the event.
+///
+/// # Errors
+///
+/// Returns `BoopError::InvalidForcedHook` if `forced` is not a known hook
+/// type, or `BoopError::UnknownHook` if the event's hook type isn't.
+pub fn hook_from_event_as(event: &Event, forced: Option<&str>) -> Result<Box<dyn Hook>, BoopError> {
+    if let Some(name) = forced {
+        if !is_known_hook_type(name) {
+            return Err(BoopError::InvalidForcedHook {
+                env: FORCE_HOOK_ENV,
+                name: name.to_string(),
+                expected: HOOK_TYPES.join(", "),
+            });
+        }
+        log::debug!("Hook type forced to {}", name);
+    }
+
+    let hook_event_name = forced.or(event.event_type()).unwrap_or("unknown");
+    log::debug!("Resolving hook type: {}", hook_event_name);
+
+    match HOOKS.iter().find(|(name, _)| *name == hook_event_name) {
+        Some((name, build)) => build(name, event),
+        None => Err(BoopError::UnknownHook(hook_event_name.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_is_known_hook_type() {
+        assert!(is_known_hook_type("Stop"));
+        assert!(is_known_hook_type("SubagentStop"));
+        assert!(is_known_hook_type("PreToolUse"));
+        assert!(is_known_hook_type("PreCompact"));
+        assert!(!is_known_hook_type("stop"));
+        assert!(!is_known_hook_type("FileEdited"));
+        assert!(!is_known_hook_type(""));
+    }
+
+    #[test]
+    fn test_hook_types_match_hook_from_event() {
+        for name in HOOK_TYPES {
+            let event = Event::from_json(&json!({"hook_event_name": name}).to_string()).unwrap();
+            let hook = hook_from_event(&event).unwrap();
+            assert_eq!(hook.hook_type(), *name);
+        }
+    }
+
+    #[test]
+    fn test_required_fields_cover_all_hook_types() {
+        for name in HOOK_TYPES {
+            assert!(!required_fields(name).is_empty(), "{} has no required fields", name);
+        }
+        assert!(required_fields("Bogus").is_empty());
+    }
+
+    #[test]
+    fn test_aggregate_errors() {
+        let outcomes = vec![
+            HandlerOutcome::Error("a".to_string()),
+            HandlerOutcome::Success,
+            HandlerOutcome::Error("b".to_string()),
+        ];
+        assert_eq!(aggregate_errors(&outcomes), Some("a; b".to_string()));
+        assert_eq!(aggregate_errors(&[HandlerOutcome::Success]), None);
+        assert_eq!(aggregate_errors(&[]), None);
+    }
+
+    #[test]
+    fn test_blocking_feedback_only_for_blocking_hooks() {
+        let outcomes = vec![HandlerOutcome::Error("webhook: timed out".to_string())];
+
+        for (name, blocking) in [
+            ("Stop", true),
+            ("SubagentStop", true),
+            ("PreToolUse", true),
+            ("PostToolUse", true),
+            ("UserPromptSubmit", true),
+            ("Notification", false),
+            ("SessionStart", false),
+            ("PreCompact", false),
+        ] {
+            let event = Event::from_json(&json!({"hook_event_name": name}).to_string()).unwrap();
+            let hook = hook_from_event(&event).unwrap();
+            let expected = blocking.then(|| "webhook: timed out".to_string());
+            assert_eq!(blocking_feedback(hook.as_ref(), &outcomes), expected, "{}", name);
+            assert_eq!(blocking_feedback(hook.as_ref(), &[HandlerOutcome::Success]), None);
+        }
+    }
+
+    #[test]
+    fn test_response_is_blocking_defaults_to_false() {
+        // Hooks that can't block ignore Block and Halt outcomes
+        let outcomes = vec![
+            HandlerOutcome::Block("nope".to_string()),
+            HandlerOutcome::Halt("stop".to_string()),
+        ];
+
+        for name in ["Notification", "SessionStart", "PreCompact", "PermissionRequest"] {
+            let event = Event::from_json(&json!({"hook_event_name": name}).to_string()).unwrap();
+            let hook = hook_from_event(&event).unwrap();
+            assert!(!hook.response_is_blocking(&outcomes), "{}", name);
+        }
+    }
+
+    fn response_outcome(value: Value) -> HandlerOutcome {
+        match value {
+            Value::Object(fields) => HandlerOutcome::Response(fields),
+            other => panic!("not an object: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_merge_responses_augments_base() {
+        let event = Event::from_json(r#"{"hook_event_name": "SessionStart"}"#).unwrap();
+        let hook = hook_from_event(&event).unwrap();
+
+        let outcomes = vec![
+            HandlerOutcome::Error("sound: failed".to_string()),
+            response_outcome(json!({
+                "hookSpecificOutput": {"hookEventName": "SessionStart", "additionalContext": "branch: main"}
+            })),
+        ];
+        assert_eq!(
+            merge_responses(hook.as_ref(), &outcomes),
+            json!({
+                "systemMessage": "Boopifier warning: sound: failed",
+                "hookSpecificOutput": {"hookEventName": "SessionStart", "additionalContext": "branch: main"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_merge_responses_handler_keys_win() {
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        let hook = hook_from_event(&event).unwrap();
+
+        let outcomes = vec![
+            HandlerOutcome::Halt("budget".to_string()),
+            response_outcome(json!({"stopReason": "first", "suppressOutput": true})),
+            response_outcome(json!({"stopReason": "second"})),
+        ];
+        // Shallow and in order: the last handler's stopReason wins, other keys stay
+        assert_eq!(
+            merge_responses(hook.as_ref(), &outcomes),
+            json!({"continue": false, "stopReason": "second", "suppressOutput": true})
+        );
+
+        assert_eq!(merge_responses(hook.as_ref(), &[]), hook.generate_response(&[]));
+    }
+
+    #[test]
+    fn test_add_version() {
+        let mut response = json!({"continue": true});
+        add_version(&mut response);
+        assert_eq!(response, json!({"continue": true, "boopifier_version": env!("CARGO_PKG_VERSION")}));
+
+        let mut response = json!("not an object");
+        add_version(&mut response);
+        assert_eq!(response, json!("not an object"));
+    }
+
+    #[test]
+    fn test_rate_limited_is_passive() {
+        let rate_limited = HandlerOutcome::Skipped("slack: rate limited".to_string());
+        assert_eq!(passive_response(&[rate_limited]), json!({}));
+    }
+
+    #[test]
+    fn test_skipped_responds_like_success() {
+        let skipped = [HandlerOutcome::Skipped("popup: quiet hours".to_string())];
+        assert_eq!(passive_response(&skipped), json!({}));
+
+        for name in HOOK_TYPES {
+            let event = Event::from_json(&json!({"hook_event_name": name}).to_string()).unwrap();
+            let hook = hook_from_event(&event).unwrap();
+            assert_eq!(
+                hook.generate_response(&skipped),
+                hook.generate_response(&[HandlerOutcome::Success]),
+                "{}",
+                name
+            );
+            assert!(!hook.response_is_blocking(&skipped), "{}", name);
+            assert_eq!(blocking_feedback(hook.as_ref(), &skipped), None);
+        }
+    }
+
+    #[test]
+    fn test_unknown_hook_type_rejected() {
+        let event = Event::from_json(r#"{"hook_event_name": "Bogus"}"#).unwrap();
+        assert!(!is_known_hook_type("Bogus"));
+        let err = hook_from_event_as(&event, None).err().unwrap();
+        assert!(matches!(&err, BoopError::UnknownHook(name) if name == "Bogus"));
+
+        // No hook_event_name at all
+        let event = Event::from_json(r#"{"type": "session.idle"}"#).unwrap();
+        let err = hook_from_event_as(&event, None).err().unwrap();
+        assert!(matches!(&err, BoopError::UnknownHook(name) if name == "unknown"));
+    }
+
+    #[test]
+    fn test_forced_hook_type() {
+        let event = Event::from_json(r#"{"hook_event_name": "Stop", "stop_hook_active": true}"#).unwrap();
+
+        let hook = hook_from_event_as(&event, Some("Notification")).unwrap();
+        assert_eq!(hook.hook_type(), "Notification");
+
+        // Hook-specific fields still come from the event
+        let hook = hook_from_event_as(&event, Some("SubagentStop")).unwrap();
+        assert_eq!(hook.hook_type(), "SubagentStop");
+        assert!(!hook.supports_blocking_feedback());
+
+        // Forcing overrides a missing or unknown hook_event_name too
+        let event = Event::from_json(r#"{"hook_event_name": "Bogus"}"#).unwrap();
+        assert_eq!(hook_from_event_as(&event, Some("PreCompact")).unwrap().hook_type(), "PreCompact");
+        let err = hook_from_event_as(&event, None).err().unwrap();
+        assert_eq!(err.to_string(), "Unknown hook type: Bogus");
+    }
+
+    #[test]
+    fn test_forced_hook_type_must_be_known() {
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        let err = hook_from_event_as(&event, Some("stop")).err().unwrap();
+        assert!(matches!(&err, BoopError::InvalidForcedHook { name, .. } if name == "stop"));
+        let err = err.to_string();
+        assert!(err.starts_with("Invalid BOOPIFIER_FORCE_HOOK \"stop\": expected one of Stop, "), "{}", err);
     }
 }
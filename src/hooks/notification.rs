@@ -1,36 +1,90 @@
 //! Notification hook implementation.
 
-use super::{HandlerOutcome, Hook};
-use serde_json::{json, Value};
+use super::{passive_response, HandlerOutcome, Hook};
+use crate::event::Event;
+use serde_json::Value;
+
+/// Text shown when a Notification event has no `message`.
+pub const DEFAULT_MESSAGE: &str = "Claude needs your attention";
 
 /// Handler for Notification hooks.
 ///
-/// These hooks fire when Claude Code wants to notify about an event.
-/// We return an empty object {} to indicate passive observation.
-pub struct NotificationHook;
+/// These hooks fire when Claude Code wants to notify about an event; the
+/// text to show is in the event's `message`.
+/// We return an empty object {} to indicate passive observation,
+/// or a `systemMessage` listing handler errors if any failed.
+#[derive(Default)]
+pub struct NotificationHook {
+    message: Option<String>,
+}
+
+impl NotificationHook {
+    pub fn from_event(event: &Event) -> Self {
+        Self {
+            message: event.message().map(str::to_string),
+        }
+    }
+
+    /// The text Claude Code wants to show, or `DEFAULT_MESSAGE` if it sent none.
+    pub fn message(&self) -> &str {
+        self.message.as_deref().unwrap_or(DEFAULT_MESSAGE)
+    }
+}
 
 impl Hook for NotificationHook {
     fn hook_type(&self) -> &str {
         "Notification"
     }
 
-    fn generate_response(&self, _outcomes: &[HandlerOutcome]) -> Value {
-        // Return empty object - just observing notifications
-        // Could add systemMessage here if needed to show warnings to user
-        json!({})
+    fn generate_response(&self, outcomes: &[HandlerOutcome]) -> Value {
+        // Just observing notifications - only speak up if handlers failed
+        passive_response(outcomes)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn test_notification_hook_response() {
-        let hook = NotificationHook;
+        let hook = NotificationHook::default();
         assert_eq!(hook.hook_type(), "Notification");
 
         let response = hook.generate_response(&[]);
         assert_eq!(response, json!({}));
     }
+
+    #[test]
+    fn test_notification_message() {
+        let event = Event::from_json(
+            r#"{"hook_event_name": "Notification", "message": "Claude needs your permission to use Bash"}"#,
+        )
+        .unwrap();
+        let hook = NotificationHook::from_event(&event);
+        assert_eq!(hook.message(), "Claude needs your permission to use Bash");
+        assert_eq!(hook.generate_response(&[]), json!({}));
+    }
+
+    #[test]
+    fn test_notification_message_missing() {
+        let event = Event::from_json(r#"{"hook_event_name": "Notification"}"#).unwrap();
+        assert_eq!(NotificationHook::from_event(&event).message(), DEFAULT_MESSAGE);
+    }
+
+    #[test]
+    fn test_notification_hook_aggregates_errors() {
+        let hook = NotificationHook::default();
+        let response = hook.generate_response(&[
+            HandlerOutcome::Error("a".to_string()),
+            HandlerOutcome::Success,
+            HandlerOutcome::Error("b".to_string()),
+        ]);
+
+        let message = response["systemMessage"].as_str().unwrap();
+        assert!(message.contains('a'));
+        assert!(message.contains('b'));
+        assert_eq!(message, "Boopifier warning: a; b");
+    }
 }
@@ -1,13 +1,19 @@
 //! PermissionRequest hook implementation.
 
-use super::{HandlerOutcome, Hook};
+use super::{passive_response, strictest_decision, HandlerOutcome, Hook, PermissionDecision};
 use serde_json::{json, Value};
 
 /// Handler for PermissionRequest hooks.
 ///
-/// These hooks fire when Claude Code asks for permission to perform an action.
-/// Currently returns an empty object {} to allow passive observation.
-/// Future: Could implement automatic approval logic based on handler outcomes.
+/// These hooks fire when Claude Code is about to show a permission dialog.
+/// Handlers answer with `Interactive` outcomes (e.g. the `permission`
+/// handler, selected by match rules). If several answer, the most
+/// restrictive decision wins: deny, then ask, then allow.
+///
+/// - allow: `{"hookSpecificOutput": {..., "decision": {"behavior": "allow"}}}`
+/// - deny: the same with `"behavior": "deny"` and the reason as `message`
+/// - ask, or no decision: a passive response, so Claude Code shows its
+///   usual dialog
 pub struct PermissionRequestHook;
 
 impl Hook for PermissionRequestHook {
@@ -15,17 +21,41 @@ impl Hook for PermissionRequestHook {
         "PermissionRequest"
     }
 
-    fn generate_response(&self, _outcomes: &[HandlerOutcome]) -> Value {
-        // Return empty object - currently just observing
-        // Future: Could return {"decision": "allow"} or {"decision": "deny"}
-        // based on handler outcomes to enable automated permission workflows
-        json!({})
+    fn generate_response(&self, outcomes: &[HandlerOutcome]) -> Value {
+        let Some(response) = strictest_decision(outcomes) else {
+            return passive_response(outcomes);
+        };
+
+        let mut decision = match response.decision {
+            PermissionDecision::Allow => json!({"behavior": "allow"}),
+            PermissionDecision::Deny => json!({"behavior": "deny"}),
+            // Leave the decision to the user
+            PermissionDecision::Ask => return passive_response(outcomes),
+        };
+        if let (PermissionDecision::Deny, Some(reason)) = (&response.decision, &response.reason) {
+            decision["message"] = json!(reason);
+        }
+
+        json!({
+            "hookSpecificOutput": {
+                "hookEventName": "PermissionRequest",
+                "decision": decision
+            }
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hooks::InteractiveResponse;
+
+    fn decided(decision: PermissionDecision, reason: Option<&str>) -> HandlerOutcome {
+        HandlerOutcome::Interactive(InteractiveResponse {
+            decision,
+            reason: reason.map(str::to_string),
+        })
+    }
 
     #[test]
     fn test_permission_request_hook_response() {
@@ -35,4 +65,57 @@ mod tests {
         let response = hook.generate_response(&[]);
         assert_eq!(response, json!({}));
     }
+
+    #[test]
+    fn test_allow() {
+        let response = PermissionRequestHook.generate_response(&[
+            HandlerOutcome::Success,
+            decided(PermissionDecision::Allow, Some("read-only")),
+        ]);
+        assert_eq!(
+            response,
+            json!({
+                "hookSpecificOutput": {
+                    "hookEventName": "PermissionRequest",
+                    "decision": {"behavior": "allow"}
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_deny_with_message() {
+        let response = PermissionRequestHook
+            .generate_response(&[decided(PermissionDecision::Deny, Some("No writes under /etc"))]);
+        assert_eq!(
+            response["hookSpecificOutput"]["decision"],
+            json!({"behavior": "deny", "message": "No writes under /etc"})
+        );
+    }
+
+    #[test]
+    fn test_ask_is_passive() {
+        let response = PermissionRequestHook.generate_response(&[decided(PermissionDecision::Ask, None)]);
+        assert_eq!(response, json!({}));
+
+        // Handler errors still surface when nothing decided
+        let response = PermissionRequestHook.generate_response(&[HandlerOutcome::Error("x".to_string())]);
+        assert_eq!(response, json!({"systemMessage": "Boopifier warning: x"}));
+    }
+
+    #[test]
+    fn test_most_restrictive_decision_wins() {
+        let response = PermissionRequestHook.generate_response(&[
+            decided(PermissionDecision::Allow, None),
+            decided(PermissionDecision::Deny, Some("protected path")),
+            decided(PermissionDecision::Ask, None),
+        ]);
+        assert_eq!(response["hookSpecificOutput"]["decision"]["behavior"], "deny");
+
+        let response = PermissionRequestHook.generate_response(&[
+            decided(PermissionDecision::Allow, None),
+            decided(PermissionDecision::Ask, None),
+        ]);
+        assert_eq!(response, json!({}));
+    }
 }
@@ -1,13 +1,13 @@
 //! UserPromptSubmit hook implementation.
 
-use super::{HandlerOutcome, Hook};
+use super::{block_reason, passive_response, HandlerOutcome, Hook};
 use serde_json::{json, Value};
 
 /// Handler for UserPromptSubmit hooks.
 ///
 /// These hooks fire when the user submits a prompt to Claude Code.
-/// Returns an empty object {} to allow passive observation.
-/// Future: Could implement prompt transformation or validation logic.
+/// Returns an empty object {} to allow passive observation, or
+/// `{"decision": "block", "reason": "..."}` if a handler returned `Block`.
 pub struct UserPromptSubmitHook;
 
 impl Hook for UserPromptSubmitHook {
@@ -15,11 +15,23 @@ impl Hook for UserPromptSubmitHook {
         "UserPromptSubmit"
     }
 
-    fn generate_response(&self, _outcomes: &[HandlerOutcome]) -> Value {
-        // Return empty object - currently just observing
-        // Future: Could modify the prompt or add system messages
-        // based on handler outcomes
-        json!({})
+    fn supports_blocking_feedback(&self) -> bool {
+        true
+    }
+
+    fn response_is_blocking(&self, outcomes: &[HandlerOutcome]) -> bool {
+        block_reason(outcomes).is_some()
+    }
+
+    fn generate_response(&self, outcomes: &[HandlerOutcome]) -> Value {
+        match block_reason(outcomes) {
+            // Blocked prompts are erased from context; the reason is shown to the user
+            Some(reason) => json!({
+                "decision": "block",
+                "reason": reason
+            }),
+            None => passive_response(outcomes),
+        }
     }
 }
 
@@ -35,4 +47,49 @@ mod tests {
         let response = hook.generate_response(&[]);
         assert_eq!(response, json!({}));
     }
+
+    #[test]
+    fn test_user_prompt_submit_block() {
+        let hook = UserPromptSubmitHook;
+
+        let response = hook.generate_response(&[
+            HandlerOutcome::Success,
+            HandlerOutcome::Block("Prompt contains a secret".to_string()),
+        ]);
+        assert_eq!(
+            response,
+            json!({
+                "decision": "block",
+                "reason": "Prompt contains a secret"
+            })
+        );
+    }
+
+    #[test]
+    fn test_user_prompt_submit_allow() {
+        let hook = UserPromptSubmitHook;
+
+        let response = hook.generate_response(&[HandlerOutcome::Success]);
+        assert_eq!(response, json!({}));
+    }
+
+    #[test]
+    fn test_user_prompt_submit_error_does_not_block() {
+        let hook = UserPromptSubmitHook;
+
+        let response = hook.generate_response(&[HandlerOutcome::Error("sound: failed".to_string())]);
+        assert!(response.get("decision").is_none());
+    }
+
+    #[test]
+    fn test_response_is_blocking() {
+        let hook = UserPromptSubmitHook;
+
+        assert!(hook.response_is_blocking(&[
+            HandlerOutcome::Success,
+            HandlerOutcome::Block("Prompt contains a secret".to_string()),
+        ]));
+        assert!(!hook.response_is_blocking(&[HandlerOutcome::Error("sound: failed".to_string())]));
+        assert!(!hook.response_is_blocking(&[]));
+    }
 }
@@ -1,13 +1,13 @@
 //! SessionStart and SessionEnd hook implementations.
 
-use super::{HandlerOutcome, Hook};
+use super::{passive_response, HandlerOutcome, Hook};
 use serde_json::{json, Value};
 
 /// Handler for SessionStart hooks.
 ///
 /// These hooks fire when a Claude Code session begins.
-/// Returns an empty object {} to allow passive observation.
-/// Future: Could implement session initialization logic.
+/// Returns an empty object {} to allow passive observation, unless handlers
+/// returned `Context` outcomes, which are joined into `additionalContext`.
 pub struct SessionStartHook;
 
 impl Hook for SessionStartHook {
@@ -15,10 +15,25 @@ impl Hook for SessionStartHook {
         "SessionStart"
     }
 
-    fn generate_response(&self, _outcomes: &[HandlerOutcome]) -> Value {
-        // Return empty object - currently just observing
-        // Future: Could set up session-specific state or notify external systems
-        json!({})
+    fn generate_response(&self, outcomes: &[HandlerOutcome]) -> Value {
+        let contexts: Vec<&str> = outcomes
+            .iter()
+            .filter_map(|outcome| match outcome {
+                HandlerOutcome::Context(context) => Some(context.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        let mut response = passive_response(outcomes);
+
+        if !contexts.is_empty() {
+            response["hookSpecificOutput"] = json!({
+                "hookEventName": "SessionStart",
+                "additionalContext": contexts.join("\n")
+            });
+        }
+
+        response
     }
 }
 
@@ -54,6 +69,36 @@ mod tests {
         assert_eq!(response, json!({}));
     }
 
+    #[test]
+    fn test_session_start_additional_context() {
+        let hook = SessionStartHook;
+
+        let response = hook.generate_response(&[
+            HandlerOutcome::Success,
+            HandlerOutcome::Context("Current branch: main".to_string()),
+        ]);
+        assert_eq!(
+            response,
+            json!({
+                "hookSpecificOutput": {
+                    "hookEventName": "SessionStart",
+                    "additionalContext": "Current branch: main"
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_session_start_joins_multiple_contexts() {
+        let hook = SessionStartHook;
+
+        let response = hook.generate_response(&[
+            HandlerOutcome::Context("first".to_string()),
+            HandlerOutcome::Context("second".to_string()),
+        ]);
+        assert_eq!(response["hookSpecificOutput"]["additionalContext"], "first\nsecond");
+    }
+
     #[test]
     fn test_session_end_hook_response() {
         let hook = SessionEndHook;
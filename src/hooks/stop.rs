@@ -1,20 +1,60 @@
 //! Stop and SubagentStop hook implementation.
 
-use super::{HandlerOutcome, Hook};
+use super::{halt_reason, passive_response, HandlerOutcome, Hook};
+use crate::event::Event;
 use serde_json::{json, Value};
 
 /// Handler for Stop and SubagentStop hooks.
 ///
 /// These hooks fire when Claude Code is about to stop execution.
-/// We return an empty object {} to indicate passive observation (no decision).
+/// We return an empty object {} to indicate passive observation (no decision),
+/// or a `systemMessage` listing handler errors if any failed. If a handler
+/// returns `Halt`, we emit `{"continue": false, "stopReason": "..."}` instead.
+///
+/// When Claude Code re-enters the hook (`stop_hook_active: true`), the
+/// response is always passive and errors never block, so a handler can't
+/// keep Claude looping.
 pub struct StopHook {
     hook_name: String,
+    stop_hook_active: bool,
 }
 
 impl StopHook {
     pub fn new(hook_name: &str) -> Self {
         Self {
             hook_name: hook_name.to_string(),
+            stop_hook_active: false,
+        }
+    }
+
+    pub fn from_event(event: &Event) -> Self {
+        Self::for_hook(event.event_type().unwrap_or("Stop"), event)
+    }
+
+    /// Like `from_event`, but named `hook_name` whatever the event's hook type.
+    pub fn for_hook(hook_name: &str, event: &Event) -> Self {
+        Self {
+            hook_name: hook_name.to_string(),
+            stop_hook_active: event.stop_hook_active(),
+        }
+    }
+
+    /// Whether Claude Code is already continuing because of a Stop hook.
+    pub fn stop_hook_active(&self) -> bool {
+        self.stop_hook_active
+    }
+
+    /// Whether a subagent (`SubagentStop`) rather than the main agent finished.
+    pub fn is_subagent(&self) -> bool {
+        self.hook_name == "SubagentStop"
+    }
+
+    /// Describes who finished, for notifications without a `message` or `reason`.
+    pub fn message(&self) -> &'static str {
+        if self.is_subagent() {
+            "Subagent finished"
+        } else {
+            "Claude finished"
         }
     }
 }
@@ -24,11 +64,29 @@ impl Hook for StopHook {
         &self.hook_name
     }
 
-    fn generate_response(&self, _outcomes: &[HandlerOutcome]) -> Value {
-        // Return empty object - we're just observing events, not controlling behavior
-        // To block stopping, we would return:
-        // {"decision": "block", "reason": "...", "continue": false}
-        json!({})
+    fn supports_blocking_feedback(&self) -> bool {
+        !self.stop_hook_active
+    }
+
+    fn response_is_blocking(&self, outcomes: &[HandlerOutcome]) -> bool {
+        !self.stop_hook_active && halt_reason(outcomes).is_some()
+    }
+
+    fn generate_response(&self, outcomes: &[HandlerOutcome]) -> Value {
+        if self.stop_hook_active {
+            return passive_response(outcomes);
+        }
+
+        // A handler asked for a hard stop - continue: false overrides everything else
+        if let Some(reason) = halt_reason(outcomes) {
+            return json!({
+                "continue": false,
+                "stopReason": reason
+            });
+        }
+
+        // Passive response - we're just observing events, not controlling behavior
+        passive_response(outcomes)
     }
 }
 
@@ -53,4 +111,113 @@ mod tests {
         let response = hook.generate_response(&[]);
         assert_eq!(response, json!({}));
     }
+
+    #[test]
+    fn test_subagent_stop_message() {
+        let hook = StopHook::new("SubagentStop");
+        assert!(hook.is_subagent());
+        assert_eq!(hook.message(), "Subagent finished");
+
+        let hook = StopHook::new("Stop");
+        assert!(!hook.is_subagent());
+        assert_eq!(hook.message(), "Claude finished");
+    }
+
+    #[test]
+    fn test_stop_hook_aggregates_errors() {
+        let hook = StopHook::new("Stop");
+        let response = hook.generate_response(&[
+            HandlerOutcome::Error("a".to_string()),
+            HandlerOutcome::Success,
+            HandlerOutcome::Error("b".to_string()),
+        ]);
+
+        let message = response["systemMessage"].as_str().unwrap();
+        assert!(message.contains("a; b"));
+    }
+
+    #[test]
+    fn test_stop_hook_halt() {
+        let hook = StopHook::new("Stop");
+        let response = hook.generate_response(&[
+            HandlerOutcome::Success,
+            HandlerOutcome::Halt("Budget exceeded".to_string()),
+        ]);
+
+        assert_eq!(
+            response,
+            json!({
+                "continue": false,
+                "stopReason": "Budget exceeded"
+            })
+        );
+    }
+
+    #[test]
+    fn test_subagent_stop_hook_halt() {
+        let hook = StopHook::new("SubagentStop");
+        let response = hook.generate_response(&[HandlerOutcome::Halt("done".to_string())]);
+
+        assert_eq!(response["continue"], false);
+        assert_eq!(response["stopReason"], "done");
+    }
+
+    #[test]
+    fn test_stop_hook_without_halt_continues() {
+        let hook = StopHook::new("Stop");
+        let response = hook.generate_response(&[HandlerOutcome::Success]);
+
+        assert!(response.get("continue").is_none());
+    }
+
+    fn stop_event(active: bool) -> Event {
+        Event::from_json(&json!({"hook_event_name": "Stop", "stop_hook_active": active}).to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_stop_hook_active_read_from_event() {
+        assert!(StopHook::from_event(&stop_event(true)).stop_hook_active());
+        assert!(!StopHook::from_event(&stop_event(false)).stop_hook_active());
+
+        let event = Event::from_json(r#"{"hook_event_name": "SubagentStop"}"#).unwrap();
+        let hook = StopHook::from_event(&event);
+        assert_eq!(hook.hook_type(), "SubagentStop");
+        assert!(!hook.stop_hook_active());
+    }
+
+    #[test]
+    fn test_stop_hook_active_ignores_halt() {
+        let hook = StopHook::from_event(&stop_event(true));
+        let response = hook.generate_response(&[
+            HandlerOutcome::Halt("Budget exceeded".to_string()),
+            HandlerOutcome::Error("sound: failed".to_string()),
+        ]);
+
+        assert_eq!(response, json!({"systemMessage": "Boopifier warning: sound: failed"}));
+        assert!(!hook.supports_blocking_feedback());
+    }
+
+    #[test]
+    fn test_stop_hook_inactive_still_halts() {
+        let hook = StopHook::from_event(&stop_event(false));
+        let response = hook.generate_response(&[HandlerOutcome::Halt("Budget exceeded".to_string())]);
+
+        assert_eq!(response["continue"], false);
+        assert!(hook.supports_blocking_feedback());
+    }
+
+    #[test]
+    fn test_response_is_blocking() {
+        let halt = [HandlerOutcome::Success, HandlerOutcome::Halt("Budget exceeded".to_string())];
+        let errors = [HandlerOutcome::Error("sound: failed".to_string())];
+
+        let hook = StopHook::from_event(&stop_event(false));
+        assert!(hook.response_is_blocking(&halt));
+        assert!(!hook.response_is_blocking(&errors));
+        assert!(!hook.response_is_blocking(&[]));
+
+        // Re-entered Stop hooks never halt
+        let hook = StopHook::from_event(&stop_event(true));
+        assert!(!hook.response_is_blocking(&halt));
+    }
 }
@@ -1,29 +1,34 @@
 //! PreToolUse and PostToolUse hook implementations.
 
-use super::{HandlerOutcome, Hook, PermissionDecision};
+use super::{
+    block_reason, passive_response, strictest_decision, HandlerOutcome, Hook, PermissionDecision,
+};
+use crate::error::BoopError;
 use crate::event::Event;
-use anyhow::Result;
 use serde_json::{json, Value};
 
 /// Handler for PreToolUse hooks.
 ///
 /// These hooks fire before a tool is executed, allowing for approval/denial.
-/// Currently auto-approves all tools (passive mode), but can be extended
-/// to support interactive handlers that ask the user for permission.
+/// If handlers return `Interactive` outcomes, the most restrictive decision
+/// wins (deny, then ask, then allow), so a `bash_guard` deny can't be
+/// overridden by another handler's allow. Otherwise all tools are
+/// auto-approved (passive mode).
 pub struct PreToolUseHook {
-    #[allow(dead_code)]
     tool_name: String,
 }
 
 impl PreToolUseHook {
-    pub fn from_event(event: &Event) -> Result<Self> {
-        let tool_name = event
-            .get_str("tool_name")
-            .unwrap_or("unknown")
-            .to_string();
+    pub fn from_event(event: &Event) -> Result<Self, BoopError> {
+        let tool_name = event.tool_name().unwrap_or("unknown").to_string();
 
         Ok(Self { tool_name })
     }
+
+    /// Name of the tool about to run, or "unknown" if the event didn't say.
+    pub fn tool_name(&self) -> &str {
+        &self.tool_name
+    }
 }
 
 impl Hook for PreToolUseHook {
@@ -31,24 +36,18 @@ impl Hook for PreToolUseHook {
         "PreToolUse"
     }
 
-    fn generate_response(&self, outcomes: &[HandlerOutcome]) -> Value {
-        // Check if any handler provided an interactive response
-        let interactive = outcomes.iter().find_map(|outcome| {
-            if let HandlerOutcome::Interactive(response) = outcome {
-                Some(response)
-            } else {
-                None
-            }
-        });
+    fn supports_blocking_feedback(&self) -> bool {
+        true
+    }
 
-        let (decision, reason) = if let Some(response) = interactive {
+    fn response_is_blocking(&self, outcomes: &[HandlerOutcome]) -> bool {
+        strictest_decision(outcomes).is_some_and(|response| response.decision == PermissionDecision::Deny)
+    }
+
+    fn generate_response(&self, outcomes: &[HandlerOutcome]) -> Value {
+        let (decision, reason) = if let Some(response) = strictest_decision(outcomes) {
             // Use the interactive handler's decision
-            let decision_str = match response.decision {
-                PermissionDecision::Allow => "allow",
-                PermissionDecision::Deny => "deny",
-                PermissionDecision::Ask => "ask",
-            };
-            (decision_str, response.reason.clone())
+            (response.decision.as_str(), response.reason.clone())
         } else {
             // Default: auto-approve (passive mode)
             ("allow", None)
@@ -73,7 +72,9 @@ impl Hook for PreToolUseHook {
 /// Handler for PostToolUse hooks.
 ///
 /// These hooks fire after a tool has been executed.
-/// We return an empty object {} as we're just observing.
+/// We return an empty object {} as we're just observing, unless a handler
+/// returned `Block`, in which case the reason is fed back to Claude via
+/// `{"decision": "block", "reason": "..."}` (e.g. lint or test failures).
 pub struct PostToolUseHook;
 
 impl Hook for PostToolUseHook {
@@ -81,9 +82,23 @@ impl Hook for PostToolUseHook {
         "PostToolUse"
     }
 
-    fn generate_response(&self, _outcomes: &[HandlerOutcome]) -> Value {
-        // Return empty object - just observing tool execution
-        json!({})
+    fn supports_blocking_feedback(&self) -> bool {
+        true
+    }
+
+    fn response_is_blocking(&self, outcomes: &[HandlerOutcome]) -> bool {
+        block_reason(outcomes).is_some()
+    }
+
+    fn generate_response(&self, outcomes: &[HandlerOutcome]) -> Value {
+        match block_reason(outcomes) {
+            // The tool already ran; "block" just prompts Claude with the reason
+            Some(reason) => json!({
+                "decision": "block",
+                "reason": reason
+            }),
+            None => passive_response(outcomes),
+        }
     }
 }
 
@@ -99,6 +114,7 @@ mod tests {
         let hook = PreToolUseHook::from_event(&event).unwrap();
 
         assert_eq!(hook.hook_type(), "PreToolUse");
+        assert_eq!(hook.tool_name(), "Bash");
 
         let response = hook.generate_response(&[]);
         assert_eq!(
@@ -138,6 +154,86 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_pre_tool_use_interactive_allow() {
+        use crate::hooks::{InteractiveResponse, PermissionDecision};
+
+        let event = Event::from_json(r#"{"hook_event_name": "PreToolUse", "tool_name": "Read"}"#)
+            .unwrap();
+        let hook = PreToolUseHook::from_event(&event).unwrap();
+
+        let interactive = InteractiveResponse {
+            decision: PermissionDecision::Allow,
+            reason: Some("Read-only tool".to_string()),
+        };
+
+        let response = hook.generate_response(&[HandlerOutcome::Interactive(interactive)]);
+        assert_eq!(response["hookSpecificOutput"]["permissionDecision"], "allow");
+        assert_eq!(response["hookSpecificOutput"]["permissionDecisionReason"], "Read-only tool");
+    }
+
+    #[test]
+    fn test_pre_tool_use_interactive_ask_without_reason() {
+        use crate::hooks::{InteractiveResponse, PermissionDecision};
+
+        let event = Event::from_json(r#"{"hook_event_name": "PreToolUse", "tool_name": "Write"}"#)
+            .unwrap();
+        let hook = PreToolUseHook::from_event(&event).unwrap();
+
+        let interactive = InteractiveResponse {
+            decision: PermissionDecision::Ask,
+            reason: None,
+        };
+
+        let response = hook.generate_response(&[
+            HandlerOutcome::Success,
+            HandlerOutcome::Interactive(interactive),
+        ]);
+        assert_eq!(
+            response,
+            json!({
+                "hookSpecificOutput": {
+                    "hookEventName": "PreToolUse",
+                    "permissionDecision": "ask"
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_pre_tool_use_non_interactive_outcomes_stay_passive() {
+        let event = Event::from_json(r#"{"hook_event_name": "PreToolUse", "tool_name": "Bash"}"#)
+            .unwrap();
+        let hook = PreToolUseHook::from_event(&event).unwrap();
+
+        let response = hook.generate_response(&[
+            HandlerOutcome::Success,
+            HandlerOutcome::Error("sound: failed".to_string()),
+        ]);
+        assert_eq!(response["hookSpecificOutput"]["permissionDecision"], "allow");
+        assert!(response["hookSpecificOutput"].get("permissionDecisionReason").is_none());
+    }
+
+    #[test]
+    fn test_pre_tool_use_deny_wins_over_allow() {
+        use crate::hooks::{InteractiveResponse, PermissionDecision};
+
+        let hook = PreToolUseHook { tool_name: "Bash".to_string() };
+        let decided = |decision, reason: &str| {
+            HandlerOutcome::Interactive(InteractiveResponse {
+                decision,
+                reason: Some(reason.to_string()),
+            })
+        };
+
+        let response = hook.generate_response(&[
+            decided(PermissionDecision::Allow, "allow-listed"),
+            decided(PermissionDecision::Deny, "Blocked command: rm -rf"),
+        ]);
+        assert_eq!(response["hookSpecificOutput"]["permissionDecision"], "deny");
+        assert_eq!(response["hookSpecificOutput"]["permissionDecisionReason"], "Blocked command: rm -rf");
+    }
+
     #[test]
     fn test_post_tool_use_response() {
         let hook = PostToolUseHook;
@@ -146,4 +242,52 @@ mod tests {
         let response = hook.generate_response(&[]);
         assert_eq!(response, json!({}));
     }
+
+    #[test]
+    fn test_post_tool_use_feedback() {
+        let hook = PostToolUseHook;
+
+        let response = hook.generate_response(&[
+            HandlerOutcome::Success,
+            HandlerOutcome::Block("clippy: unused variable `x`".to_string()),
+        ]);
+        assert_eq!(
+            response,
+            json!({
+                "decision": "block",
+                "reason": "clippy: unused variable `x`"
+            })
+        );
+    }
+
+    #[test]
+    fn test_post_tool_use_success_is_passive() {
+        let hook = PostToolUseHook;
+
+        let response = hook.generate_response(&[HandlerOutcome::Success]);
+        assert_eq!(response, json!({}));
+    }
+
+    #[test]
+    fn test_response_is_blocking() {
+        use crate::hooks::InteractiveResponse;
+
+        let decided = |decision| {
+            HandlerOutcome::Interactive(InteractiveResponse { decision, reason: None })
+        };
+
+        let hook = PreToolUseHook { tool_name: "Bash".to_string() };
+        assert!(hook.response_is_blocking(&[
+            decided(PermissionDecision::Allow),
+            decided(PermissionDecision::Deny),
+        ]));
+        assert!(!hook.response_is_blocking(&[decided(PermissionDecision::Ask)]));
+        assert!(!hook.response_is_blocking(&[decided(PermissionDecision::Allow)]));
+        assert!(!hook.response_is_blocking(&[]));
+
+        let hook = PostToolUseHook;
+        assert!(hook.response_is_blocking(&[HandlerOutcome::Block("tests failed".to_string())]));
+        assert!(!hook.response_is_blocking(&[HandlerOutcome::Error("sound: failed".to_string())]));
+        assert!(!hook.response_is_blocking(&[]));
+    }
 }
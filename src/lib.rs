@@ -8,7 +8,11 @@
 //! - **Event**: JSON events from Claude Code
 //! - **Config**: Configuration from `.claude/boopifier.json`
 //! - **Matcher**: Pattern matching to filter events
-//! - **Handlers**: Pluggable notification targets (desktop, sound, signal, webhook, email)
+//! - **Debounce / rate limit**: Suppression of repeated notifications
+//! - **Metrics**: Prometheus-style counters persisted across invocations
+//! - **Sessions**: Session start times, for reporting duration on `SessionEnd`
+//! - **State**: Locked JSON state files behind the above, shared by concurrent hooks
+//! - **Handlers**: Pluggable notification targets (desktop, sound, signal, webhook, email, command, audit, permission, bash_guard)
 //!
 //! # Examples
 //!
@@ -30,12 +34,19 @@
 //! ```
 
 pub mod config;
+pub mod debounce;
+pub mod digest;
 pub mod error;
 pub mod event;
 pub mod handlers;
 pub mod hooks;
 pub mod matcher;
+pub mod metrics;
+pub mod rate_limit;
+pub mod sessions;
+pub mod state;
 
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Global debug mode flag - set once at startup, read from handlers
@@ -52,39 +63,222 @@ pub fn is_debug_mode() -> bool {
 }
 
 // Re-export commonly used types at the crate root
-pub use config::{Config, HandlerConfig, MatchRules, MatchType, ProjectOverride};
-pub use error::{NotificationError, Result};
+pub use config::{
+    Config, Debounce, HandlerConfig, MatchRules, MatchType, MaxEventAge, ProjectOverride, RateLimit, Redact,
+    Route, RouteMode,
+};
+pub use error::{BoopError, NotificationError, Result};
 pub use event::Event;
 pub use handlers::HandlerRegistry;
-pub use hooks::{hook_from_event, HandlerOutcome, Hook};
-pub use matcher::matches;
+pub use hooks::{
+    blocking_feedback, hook_from_event, hook_from_event_as, is_known_hook_type, merge_responses,
+    HandlerOutcome, Hook,
+};
+pub use matcher::{matches, matches_with_case, Matcher};
+
+/// Returns the configured handlers whose match rules accept the event, in config order.
+///
+/// Handlers named by a `route` also need one of their routes to match (only
+/// the first matching route counts with `route_mode: "first"`); each handler
+/// is returned at most once however many routes select it. Handlers listed in
+/// `fallback` are returned only when no other handler matched. Returns
+/// nothing if the event's hook type is disabled in the config.
+pub fn matching_handlers<'a>(
+    event: &'a Event,
+    config: &'a Config,
+) -> impl Iterator<Item = &'a HandlerConfig> + 'a {
+    let enabled = event.event_type().is_none_or(|hook| config.hook_enabled(hook));
+    if !enabled {
+        log::info!("Hook {} is disabled; skipping handlers", event.event_type().unwrap_or_default());
+    }
+
+    let routed: HashSet<&str> = config
+        .matching_routes(event)
+        .into_iter()
+        .flat_map(|route| route.handlers.iter().map(String::as_str))
+        .collect();
+
+    let (fallback, matched): (Vec<_>, Vec<_>) = config
+        .handlers
+        .iter()
+        .filter(|h| {
+            if !enabled {
+                return false;
+            }
+
+            if config.is_routed(&h.name) && !routed.contains(h.name.as_str()) {
+                log::debug!("Handler '{}' not selected by any matching route", h.name);
+                return false;
+            }
+
+            let matched = matches_with_case(event, &h.match_rules, &h.match_type, h.ignore_case);
+            log::debug!(
+                "Handler '{}' {}",
+                h.name,
+                if matched { "matched" } else { "did not match" }
+            );
+            matched
+        })
+        .partition(|h| config.is_fallback(&h.name));
+
+    if matched.is_empty() && !fallback.is_empty() {
+        log::info!("No handler matched; running {} fallback handler(s)", fallback.len());
+        return fallback.into_iter();
+    }
+    matched.into_iter()
+}
+
+/// Describes how `matching_handlers` treats the event, one line per step:
+/// whether its hook is enabled, each route and handler with a trace of its
+/// match rules (see `Matcher::explain`), and the handlers that would run.
+pub fn explain_matching(event: &Event, config: &Config) -> Vec<String> {
+    let mut lines = Vec::new();
+    let enabled = event.event_type().is_none_or(|hook| config.hook_enabled(hook));
+    lines.push(format!(
+        "Hook {}: {}",
+        event.event_type().unwrap_or("unknown"),
+        if enabled { "enabled" } else { "disabled in config" }
+    ));
+
+    let selected = config.matching_routes(event);
+    for (i, route) in config.routes.iter().enumerate() {
+        let verdict = if selected.iter().any(|r| std::ptr::eq(*r, route)) {
+            "match"
+        } else if route.matches(event) {
+            "match, but an earlier route was chosen"
+        } else {
+            "no match"
+        };
+        lines.push(format!("Route {} ({}): {}", i + 1, route.handlers.join(", "), verdict));
+        explain_rules(event, &route.match_rules, &route.match_type, route.ignore_case, &mut lines);
+    }
+
+    for handler in &config.handlers {
+        let matched = matches_with_case(event, &handler.match_rules, &handler.match_type, handler.ignore_case);
+        let mut verdict = if matched { "match" } else { "no match" }.to_string();
+        if config.is_routed(&handler.name) && !selected.iter().any(|route| route.handlers.contains(&handler.name)) {
+            verdict.push_str(", but not selected by a matching route");
+        }
+        if config.is_fallback(&handler.name) {
+            verdict.push_str(" (fallback)");
+        }
+        lines.push(format!("Handler '{}' ({}): {}", handler.name, handler.handler_type, verdict));
+        explain_rules(event, &handler.match_rules, &handler.match_type, handler.ignore_case, &mut lines);
+    }
+
+    let running: Vec<&str> = matching_handlers(event, config).map(|h| h.name.as_str()).collect();
+    if running.is_empty() {
+        lines.push("Would run: no handlers".to_string());
+    } else {
+        lines.push(format!("Would run: {}", running.join(", ")));
+    }
+    lines
+}
+
+/// Appends the trace of `rules`, indented under its route or handler line.
+fn explain_rules(
+    event: &Event,
+    rules: &Option<MatchRules>,
+    match_type: &MatchType,
+    ignore_case: bool,
+    lines: &mut Vec<String>,
+) {
+    match rules {
+        None => lines.push("  no match_rules (matches every event)".to_string()),
+        Some(rules) => {
+            let (_, trace) = Matcher::from_rules(rules).explain(event, match_type, ignore_case);
+            lines.extend(trace.into_iter().map(|line| format!("  {}", line)));
+        }
+    }
+}
 
 /// Processes a single event through the configured handlers.
 ///
-/// # Errors
+/// Events older than `max_event_age` run no handlers and return no outcomes,
+/// so the hook responds passively. Otherwise `SessionStart` and `SessionEnd`
+/// events are tracked so handlers see how long the session lasted (see
+/// `sessions`), and every matching handler runs in parallel, each with its own
+/// outcome, so one failing handler doesn't stop the others.
+///
+/// Handlers that don't run are reported as `Skipped`: during quiet hours, once
+/// the rate limit is reached, or when `debounce` is set and they already fired
+/// for the same hook type and session within the window. Handlers with
+/// `digest` set only count events until `Stop` or `SessionEnd` fires them with
+/// a summary (see `digest`). Permission decision handlers (see
+/// `Handler::decides_permissions`) are exempt from all of these, and answer
+/// `ask` if they fail.
+///
+/// Handlers with `redact` set get a copy of the event with those fields masked
+/// or removed. With `metrics` set in the config, the event and its outcomes
+/// are added to the persisted counters.
+///
+/// State is kept in the default files under `$TMPDIR`; see `process_event_with`.
 ///
-/// Returns an error if event parsing fails. Handler execution outcomes are collected
-/// and returned as a list, allowing all handlers to run even if some fail.
+/// # Errors
 ///
-/// Handlers are executed in parallel for better performance.
+/// Returns an error if event parsing fails.
 pub async fn process_event(
     event_json: &str,
     config: &Config,
     registry: &HandlerRegistry,
 ) -> anyhow::Result<Vec<HandlerOutcome>> {
-    use futures::future::join_all;
+    process_event_with(event_json, config, registry, &state::Stores::default_files()).await
+}
 
-    let event = Event::from_json(event_json)?;
+/// Like `process_event`, keeping debounce, rate limit, session, digest and
+/// metrics state in `stores`.
+pub async fn process_event_with(
+    event_json: &str,
+    config: &Config,
+    registry: &HandlerRegistry,
+    stores: &state::Stores,
+) -> anyhow::Result<Vec<HandlerOutcome>> {
+    use debounce::Debouncer;
+    use futures::future::join_all;
+    use hooks::{InteractiveResponse, PermissionDecision};
+    use std::time::SystemTime;
 
-    // Collect futures for all matching handlers
-    let mut handler_futures = Vec::new();
+    let mut event = Event::from_json(event_json)?;
+    log::debug!("Parsed event with {} fields", event.data.len());
 
-    for handler_config in &config.handlers {
-        // Check if event matches the handler's rules
-        if !matches(&event, &handler_config.match_rules, &handler_config.match_type) {
-            continue;
+    // Replayed events are dropped before anything records them
+    if let Some(limit) = &config.max_event_age {
+        if limit.is_stale(&event, chrono::Utc::now()) {
+            log::info!("Event is older than {}s; skipping handlers", limit.seconds);
+            return Ok(Vec::new());
         }
+    }
 
+    stores.sessions.track(&mut event, SystemTime::now());
+    let matched: Vec<&HandlerConfig> = matching_handlers(&event, config).collect();
+
+    // Permission decisions are never suppressed, so only notifying handlers count below
+    let notifying = matched
+        .iter()
+        .filter(|h| !registry.get(&h.handler_type).is_some_and(|handler| handler.decides_permissions()))
+        .count();
+
+    // Quiet hours suppress everything, before the rate limit or debounce record a fire
+    let quiet = notifying > 0 && config.in_quiet_hours(chrono::Local::now().naive_local());
+    if quiet {
+        log::info!("Quiet hours; suppressing {} handler(s)", notifying);
+    }
+
+    // Each event that would notify takes one slot of the global rate limit
+    let rate_limited = notifying > 0
+        && !quiet
+        && config.rate_limit.as_ref().is_some_and(|limit| {
+            !stores.rate_limiter.try_acquire(limit, SystemTime::now())
+        });
+    if rate_limited {
+        log::info!("Rate limit reached; suppressing {} handler(s)", notifying);
+    }
+
+    // Collect futures for all matching handlers, noting which ones will really run
+    let mut handler_futures = Vec::new();
+    let mut ran = Vec::new();
+
+    for handler_config in matched {
         // Get the handler
         let handler = match registry.get(&handler_config.handler_type) {
             Some(h) => h,
@@ -96,24 +290,210 @@ pub async fn process_event(
             }
         };
 
-        // Clone data for this handler future
-        let event_clone = event.clone();
-        let config_clone = handler_config.config.clone();
+        let decides = handler.decides_permissions();
+        let quiet = quiet && !decides;
+        let rate_limited = rate_limited && !decides;
+
+        // Skip handlers that already fired for this hook and session recently
+        let suppressed = !quiet
+            && !decides
+            && !rate_limited
+            && handler_config
+                .debounce
+                .as_ref()
+                .and_then(|d| d.window())
+                .is_some_and(|window| {
+                    let key = Debouncer::key(&handler_config.name, &event);
+                    stores.debouncer.should_suppress(&key, window, SystemTime::now())
+                });
+
+        // Digest handlers only count events until Stop or SessionEnd
+        let mut event_clone = event.clone();
+        let deferred = !quiet
+            && !rate_limited
+            && !suppressed
+            && !decides
+            && handler_config.digest
+            && !stores.digests.prepare(&handler_config.name, &mut event_clone, SystemTime::now());
+        ran.push(!quiet && !rate_limited && !suppressed && !deferred);
+
+        // Hide any fields this handler must not see
+        if let Some(redact) = &handler_config.redact {
+            redact.apply(&mut event_clone);
+        }
         let name = handler_config.name.clone();
+        let timeout = handler_config.timeout();
+        let mut config_clone = handler_config.config.clone();
+        config_clone.insert(
+            handlers::TIMEOUT_BUDGET_KEY.to_string(),
+            serde_json::Value::from(timeout.as_millis() as u64),
+        );
+
+        let skipped = if quiet {
+            Some("quiet hours")
+        } else if rate_limited {
+            Some("rate limited")
+        } else if suppressed {
+            Some("debounced")
+        } else if deferred {
+            Some("deferred to digest")
+        } else {
+            None
+        };
 
         // Create a future for this handler
         let future = async move {
-            match handler.handle(&event_clone, &config_clone).await {
-                Ok(()) => HandlerOutcome::Success,
-                Err(e) => HandlerOutcome::Error(format!("{}: {}", name, e)),
+            if let Some(reason) = skipped {
+                log::info!("Handler '{}' skipped: {}", name, reason);
+                return HandlerOutcome::Skipped(format!("{}: {}", name, reason));
             }
+
+            // Dropping a timed-out handler future abandons it (and kills its child process)
+            let handled = handler.handle_outcome(&event_clone, &config_clone);
+            let result = match tokio::time::timeout(timeout, handled).await {
+                Ok(Ok(outcome)) => Ok(outcome),
+                Ok(Err(e)) => Err(format!("{}: {}", name, e)),
+                Err(_) => {
+                    log::warn!("Handler '{}' timed out after {:?}", name, timeout);
+                    Err(format!("{}: timeout", name))
+                }
+            };
+            let outcome = match result {
+                Ok(outcome) => outcome,
+                // A decision handler that can't decide must not let the tool through
+                Err(msg) if decides => {
+                    log::warn!("Decision handler failed ({}); asking the user", msg);
+                    HandlerOutcome::Interactive(InteractiveResponse {
+                        decision: PermissionDecision::Ask,
+                        reason: Some(format!("boopifier could not decide ({})", msg)),
+                    })
+                }
+                Err(msg) => HandlerOutcome::Error(msg),
+            };
+            log::debug!("Handler '{}' outcome: {:?}", name, outcome);
+            outcome
         };
 
         handler_futures.push(future);
     }
 
-    // Execute all handler futures concurrently
+    // Execute all handler futures concurrently; join_all keeps outcomes in config order
     let outcomes = join_all(handler_futures).await;
 
+    if config.records_metrics() {
+        let handled: Vec<HandlerOutcome> = outcomes
+            .iter()
+            .zip(&ran)
+            .filter(|(_, &ran)| ran)
+            .map(|(outcome, _)| outcome.clone())
+            .collect();
+        let suppressed = ran.iter().filter(|&&ran| !ran).count() as u64;
+        stores.metrics.record(event.event_type(), &handled, suppressed);
+    }
+
     Ok(outcomes)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Routes Stop to Slack, permission prompts to a sound, and both to a log.
+    const ROUTED: &str = r#"{
+        "handlers": [
+            {"name": "slack", "type": "webhook", "config": {}},
+            {"name": "boop", "type": "sound", "config": {}},
+            {"name": "log", "type": "audit", "config": {}},
+            {"name": "popup", "type": "desktop", "match_rules": {"hook_event_name": "Notification"}, "config": {}}
+        ],
+        "routes": [
+            {"match_rules": {"hook_event_name": "Stop"}, "handlers": ["slack", "log"]},
+            {"match_rules": {"hook_event_name": "Notification"}, "handlers": ["boop", "log"]},
+            {"match_rules": {"notification_type": "permission_prompt"}, "handlers": ["boop"]}
+        ]
+    }"#;
+
+    fn matched_names(event_json: &str) -> Vec<String> {
+        matched_names_with(event_json, Config::from_json(ROUTED).unwrap())
+    }
+
+    fn matched_names_with(event_json: &str, config: Config) -> Vec<String> {
+        let event = Event::from_json(event_json).unwrap();
+        matching_handlers(&event, &config).map(|h| h.name.clone()).collect()
+    }
+
+    #[test]
+    fn test_routes_select_disjoint_handlers() {
+        assert_eq!(matched_names(r#"{"hook_event_name": "Stop"}"#), ["slack", "log"]);
+        assert!(matched_names(r#"{"hook_event_name": "PreToolUse"}"#).is_empty());
+    }
+
+    #[test]
+    fn test_overlapping_routes_union_handlers() {
+        // Two routes pick "boop" and one picks "log"; each runs once, and
+        // the unrouted "popup" still matches on its own rules
+        let names = matched_names(r#"{"hook_event_name": "Notification", "notification_type": "permission_prompt"}"#);
+        assert_eq!(names, ["boop", "log", "popup"]);
+    }
+
+    #[test]
+    fn test_first_match_route_mode() {
+        let mut config = Config::from_json(ROUTED).unwrap();
+        config.route_mode = Some(RouteMode::First);
+        let event = r#"{"hook_event_name": "Notification", "notification_type": "permission_prompt"}"#;
+
+        // Only the Notification route counts, so the later permission route adds nothing
+        let names = matched_names_with(event, config.clone());
+        assert_eq!(names, ["boop", "log", "popup"]);
+
+        // Put the narrower route first and it wins on its own
+        config.routes.rotate_right(1);
+        let names = matched_names_with(event, config);
+        assert_eq!(names, ["boop", "popup"]);
+    }
+
+    /// A "done" sound for Stop, with a quiet boop for anything else.
+    const FALLBACK: &str = r#"{
+        "handlers": [
+            {"name": "done", "type": "sound", "match_rules": {"hook_event_name": "Stop"}, "config": {}},
+            {"name": "boop", "type": "sound", "config": {}}
+        ],
+        "fallback": ["boop"]
+    }"#;
+
+    #[test]
+    fn test_fallback_skipped_when_a_handler_matches() {
+        let config = Config::from_json(FALLBACK).unwrap();
+        assert_eq!(matched_names_with(r#"{"hook_event_name": "Stop"}"#, config), ["done"]);
+    }
+
+    #[test]
+    fn test_fallback_runs_when_nothing_matches() {
+        let config = Config::from_json(FALLBACK).unwrap();
+        assert_eq!(matched_names_with(r#"{"hook_event_name": "PostToolUse"}"#, config.clone()), ["boop"]);
+
+        // Disabled hooks run nothing, not even the fallback
+        let mut config = config;
+        config.disabled_hooks.push("PostToolUse".to_string());
+        assert!(matched_names_with(r#"{"hook_event_name": "PostToolUse"}"#, config).is_empty());
+    }
+
+    #[test]
+    fn test_explain_matching_routes() {
+        let config = Config::from_json(ROUTED).unwrap();
+        let event = Event::from_json(r#"{"hook_event_name": "Stop"}"#).unwrap();
+        let lines = explain_matching(&event, &config);
+
+        assert_eq!(lines[0], "Hook Stop: enabled");
+        assert_eq!(lines[1], "Route 1 (slack, log): match");
+        assert!(lines.contains(&r#"  hook_event_name == "Stop": got "Stop" -> match"#.to_string()), "{:#?}", lines);
+        assert!(lines.contains(&"Route 2 (boop, log): no match".to_string()), "{:#?}", lines);
+        assert!(
+            lines.contains(&"Handler 'boop' (sound): match, but not selected by a matching route".to_string()),
+            "{:#?}",
+            lines
+        );
+        assert!(lines.contains(&"  no match_rules (matches every event)".to_string()), "{:#?}", lines);
+        assert_eq!(lines.last().unwrap(), "Would run: slack, log");
+    }
+}
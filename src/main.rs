@@ -3,12 +3,19 @@
 //! Reads JSON events from stdin and dispatches them to configured handlers.
 
 use clap::Parser;
-use boopifier::{hook_from_event, process_event, Config, Event, HandlerOutcome, HandlerRegistry};
-use serde_json::json;
-use std::fs::OpenOptions;
-use std::io::{self, BufRead, Write};
-use std::path::PathBuf;
+use boopifier::hooks::{add_version, forced_hook_type};
+use boopifier::metrics::MetricsStore;
+use boopifier::state::Stores;
+use boopifier::{
+    blocking_feedback, explain_matching, hook_from_event, matching_handlers, merge_responses, process_event_with, Config,
+    Event, HandlerOutcome, HandlerRegistry, MaxEventAge,
+};
+use serde_json::{json, Value};
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, Read, Write};
+use std::path::{Path, PathBuf};
 use std::process;
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(name = "boopifier")]
@@ -23,9 +30,77 @@ struct Cli {
     #[arg(short, long)]
     debug: bool,
 
+    /// Log to stderr (-v info, -vv debug, -vvv trace; or set BOOPIFIER_LOG)
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
     /// List available handler types
     #[arg(long)]
     list_handlers: bool,
+
+    /// List supported hook types
+    #[arg(long)]
+    list_hooks: bool,
+
+    /// Print the counters recorded with `"metrics": true` in Prometheus text format
+    #[arg(long)]
+    metrics: bool,
+
+    /// Check the config for problems and exit non-zero if any are found
+    #[arg(long, conflicts_with_all = ["batch", "daemon", "client", "print_response_only"])]
+    validate_config: bool,
+
+    /// Print the handlers that would run to stderr instead of running them
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Print a trace of how each route's and handler's match rules evaluated
+    /// the event to stderr, then handle the event as usual
+    #[arg(long, conflicts_with = "print_response_only")]
+    explain: bool,
+
+    /// Read the event from a JSON file instead of stdin
+    #[arg(long, conflicts_with = "batch")]
+    event_file: Option<PathBuf>,
+
+    /// Read all of stdin, waiting briefly for the rest of a truncated event and
+    /// dropping junk around it, instead of reading a single line
+    #[arg(long, conflicts_with_all = ["batch", "daemon"])]
+    recover: bool,
+
+    /// Process newline-delimited JSON events from stdin until EOF, one response per line
+    #[arg(long, alias = "ndjson")]
+    batch: bool,
+
+    /// Reject events that are missing fields required for their hook type
+    #[arg(long)]
+    validate: bool,
+
+    /// Ignore events whose timestamp is more than this many seconds old
+    /// (overrides `max_event_age.seconds` in the config)
+    #[arg(long, value_name = "SECONDS")]
+    since: Option<u64>,
+
+    /// Print the hook response for the event without loading config or running handlers
+    #[arg(long, conflicts_with_all = ["dry_run", "batch"])]
+    print_response_only: bool,
+
+    /// Run as a daemon, processing events sent by `--client` over a Unix socket
+    #[arg(long, conflicts_with_all = ["client", "batch", "event_file", "print_response_only"])]
+    daemon: bool,
+
+    /// Forward the event on stdin to a running daemon and relay its response
+    #[arg(long, conflicts_with_all = ["batch", "print_response_only"])]
+    client: bool,
+
+    /// Add a "boopifier_version" field to JSON responses (off by default,
+    /// since Claude Code may reject unknown fields)
+    #[arg(long)]
+    response_version: bool,
+
+    /// Socket path for --daemon and --client (default: $XDG_RUNTIME_DIR/boopifier.sock)
+    #[arg(long)]
+    socket: Option<PathBuf>,
 }
 
 #[cfg(target_os = "linux")]
@@ -83,9 +158,39 @@ impl DebugLogger {
     }
 }
 
+/// Initializes stderr logging so stdout stays reserved for the hook response.
+///
+/// `-v` flags take precedence over `BOOPIFIER_LOG`; the default level is warn.
+fn init_logging(verbose: u8) {
+    let mut builder = env_logger::Builder::new();
+    builder
+        .filter_level(log::LevelFilter::Warn)
+        .target(env_logger::Target::Stderr);
+
+    match verbose {
+        0 => {
+            if let Ok(filter) = std::env::var("BOOPIFIER_LOG") {
+                builder.parse_filters(&filter);
+            }
+        }
+        1 => {
+            builder.filter_level(log::LevelFilter::Info);
+        }
+        2 => {
+            builder.filter_level(log::LevelFilter::Debug);
+        }
+        _ => {
+            builder.filter_level(log::LevelFilter::Trace);
+        }
+    }
+
+    builder.init();
+}
+
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
+    init_logging(cli.verbose);
     let logger = DebugLogger::new(cli.debug);
 
     // Set global debug mode for handlers
@@ -103,26 +208,58 @@ async fn main() {
         return;
     }
 
+    // List hook types if requested
+    if cli.list_hooks {
+        list_hook_types();
+        return;
+    }
+
+    // Dump metrics if requested
+    if cli.metrics {
+        print!("{}", MetricsStore::new(MetricsStore::default_path()).load().render());
+        return;
+    }
+
     logger.log("Boopifier starting");
 
-    // Resolve config file path
-    let config_path = match &cli.config {
-        Some(path) => {
-            logger.log(&format!("Using config from CLI arg: {:?}", path));
-            path.clone()
-        }
-        None => {
-            let resolved = resolve_config_path();
-            logger.log(&format!("Auto-detected config: {:?}", resolved));
-            resolved
+    // Response-only mode: no config, no handlers, nothing on stderr
+    if cli.print_response_only {
+        let mut response = match read_event_input(cli.event_file.as_deref()) {
+            Ok(event_json) => passive_response_for(&event_json),
+            Err(e) => hook_error_response(&format!("Error reading input: {}", e)),
+        };
+        if cli.response_version {
+            add_version(&mut response);
         }
-    };
+        print_response(&response);
+        process::exit(0);
+    }
+
+    #[cfg(not(unix))]
+    if cli.daemon || cli.client {
+        eprintln!("boopifier: --daemon and --client are only supported on Unix");
+        process::exit(1);
+    }
+
+    // Client mode: the daemon owns the config and handlers
+    #[cfg(unix)]
+    if cli.client {
+        let exit_code = daemon::run_client(&socket_path(&cli), cli.event_file.as_deref()).await;
+        process::exit(exit_code);
+    }
+
+    let (config_path, loaded) = load_config(&cli, &logger);
+
+    // Validate the config instead of handling an event
+    if cli.validate_config {
+        process::exit(validate_config(&config_path, loaded));
+    }
 
-    // Load configuration (secrets are resolved automatically)
-    let mut config = match Config::load(&config_path) {
+    let mut config = match loaded {
         Ok(cfg) => cfg,
         Err(e) => {
             logger.log(&format!("Failed to load config: {}", e));
+            log::warn!("Failed to load config: {}", e);
             output_hook_error(&format!("Failed to load config from {:?}: {}", config_path, e));
             process::exit(0); // Exit 0 for hook compatibility
         }
@@ -138,90 +275,623 @@ async fn main() {
         }
     }
 
+    // BOOPIFIER_<TYPE>_<KEY> environment variables override handler config values
+    if let Err(e) = config.apply_env_overrides(std::env::vars()) {
+        logger.log(&format!("Invalid environment override: {}", e));
+        log::warn!("Invalid environment override: {}", e);
+        output_hook_error(&e.to_string());
+        process::exit(0); // Exit 0 for hook compatibility
+    }
+
+    // --since sets the age limit, keeping any configured timestamp field
+    if let Some(seconds) = cli.since {
+        config.max_event_age.get_or_insert_with(|| MaxEventAge::new(seconds)).seconds = seconds;
+    }
+
     logger.log(&format!("Loaded config with {} handlers", config.handlers.len()));
+    log::info!("Loaded config from {:?} with {} handlers", config_path, config.handlers.len());
 
     // Create handler registry
     let registry = HandlerRegistry::new();
 
-    // Read one event from stdin (Claude Code sends one event per invocation)
-    let stdin = io::stdin();
-    let mut reader = stdin.lock();
-    let mut event_json = String::new();
-
-    match reader.read_line(&mut event_json) {
-        Ok(_) => {
-            if event_json.trim().is_empty() {
-                logger.log("No input received");
-                println!("{{}}");
-                return;
-            }
+    // Daemon mode: serve events from clients until killed
+    #[cfg(unix)]
+    if cli.daemon {
+        let socket = socket_path(&cli);
+        let logger = std::sync::Arc::new(logger);
+        let served = daemon::serve(&socket, config, registry, cli, logger.clone()).await;
+        if let Err(e) = served {
+            logger.log(&format!("Daemon error: {}", e));
+            eprintln!("boopifier: daemon on {:?} failed: {}", socket, e);
+            process::exit(1);
+        }
+        process::exit(0);
+    }
 
-            logger.log(&format!("Received event: {}", event_json.trim()));
+    let stores = Stores::default_files();
 
-            // Parse the event to determine hook type
-            let event = match Event::from_json(&event_json) {
-                Ok(e) => e,
+    // Batch mode: one response line per NDJSON input line, until EOF.
+    // Exits 2 if any event blocked.
+    if cli.batch {
+        let mut exit_code = 0;
+        let stdin = io::stdin();
+        for line in stdin.lock().lines() {
+            let event_json = match line {
+                Ok(line) => line,
                 Err(e) => {
-                    logger.log(&format!("Failed to parse event JSON: {}", e));
-                    output_hook_error(&format!("Invalid JSON: {}", e));
-                    return;
+                    logger.log(&format!("Error reading stdin: {}", e));
+                    output_hook_error(&format!("Error reading stdin: {}", e));
+                    break;
                 }
             };
 
-            // Create the appropriate hook type
-            let hook = match hook_from_event(&event) {
-                Ok(h) => {
-                    logger.log(&format!("Hook type: {}", h.hook_type()));
-                    h
-                }
-                Err(e) => {
-                    logger.log(&format!("Unknown hook type: {}", e));
-                    output_hook_error(&format!("Unknown hook: {}", e));
-                    return;
-                }
+            if event_json.trim().is_empty() {
+                continue;
+            }
+
+            let reply = respond_to_event(&event_json, &config, &registry, &stores, &cli, &logger).await;
+            exit_code = exit_code.max(reply.emit());
+        }
+
+        logger.log("Batch processed, exiting");
+        process::exit(exit_code);
+    }
+
+    // Read one event (Claude Code sends one event per invocation)
+    let input = if cli.recover {
+        read_event_recovering(cli.event_file.as_deref(), RECOVER_WAIT)
+    } else {
+        read_event_input(cli.event_file.as_deref())
+    };
+    match input {
+        Ok(event_json) => {
+            // Usually a misconfigured hook; exit 1 so Claude Code shows the message
+            if event_json.trim().is_empty() {
+                let message = match &cli.event_file {
+                    Some(path) => format!("no event data received in event file {:?}", path),
+                    None => "no event data received on stdin".to_string(),
+                };
+                logger.log(&format!("Error: {}", message));
+                eprintln!("boopifier: {}", message);
+                process::exit(1);
+            }
+
+            let reply = respond_to_event(&event_json, &config, &registry, &stores, &cli, &logger).await;
+            let exit_code = reply.emit();
+
+            logger.log("Event processed, exiting");
+            process::exit(exit_code);
+        }
+        Err(e) => {
+            let source = match &cli.event_file {
+                Some(path) => format!("event file {:?}", path),
+                None => "stdin".to_string(),
             };
+            logger.log(&format!("Error reading {}: {}", source, e));
+            log::warn!("Error reading {}: {}", source, e);
+            output_hook_error(&format!("Error reading {}: {}", source, e));
+        }
+    }
 
-            // Process the event through handlers
-            match process_event(&event_json, &config, &registry).await {
-                Ok(outcomes) => {
-                    // Log handler outcomes
-                    let successes = outcomes.iter().filter(|o| matches!(o, HandlerOutcome::Success)).count();
-                    let errors = outcomes.iter().filter(|o| matches!(o, HandlerOutcome::Error(_))).count();
-
-                    if errors == 0 {
-                        logger.log(&format!("Event processed successfully ({} handlers)", successes));
-                    } else {
-                        logger.log(&format!("Event processed: {} succeeded, {} failed", successes, errors));
-                        for outcome in &outcomes {
-                            if let HandlerOutcome::Error(msg) = outcome {
-                                logger.log(&format!("Handler error: {}", msg));
-                            }
-                        }
-                    }
+    // Explicitly exit to avoid hanging on background threads (rodio/tokio cleanup)
+    process::exit(0);
+}
+
+/// What boopifier sends back to Claude Code for one event.
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum HookReply {
+    /// A JSON response on stdout, exit code 0
+    Json(Value),
+    /// A blocking error: the reason on stderr, exit code 2
+    Block(String),
+}
 
-                    // Generate hook-specific response
-                    let response = hook.generate_response(&outcomes);
-                    if let Ok(json_str) = serde_json::to_string(&response) {
-                        println!("{}", json_str);
+impl HookReply {
+    /// Writes the reply and returns the exit code to use.
+    fn emit(&self) -> i32 {
+        match self {
+            HookReply::Json(response) => {
+                print_response(response);
+                0
+            }
+            HookReply::Block(reason) => {
+                eprintln!("{}", reason);
+                2
+            }
+        }
+    }
+}
+
+/// Parses one event, runs its handlers, and builds the hook response.
+///
+/// Failures still produce a valid response so Claude Code always gets a reply.
+/// Handler errors block when the hook feeds stderr back to Claude, unless
+/// `block_on_error` is false. With `--response-version`, JSON responses carry
+/// boopifier's version.
+async fn respond_to_event(
+    event_json: &str,
+    config: &Config,
+    registry: &HandlerRegistry,
+    stores: &Stores,
+    cli: &Cli,
+    logger: &DebugLogger,
+) -> HookReply {
+    let mut reply = build_reply(event_json, config, registry, stores, cli, logger).await;
+    if let (true, HookReply::Json(response)) = (cli.response_version, &mut reply) {
+        add_version(response);
+    }
+    reply
+}
+
+async fn build_reply(
+    event_json: &str,
+    config: &Config,
+    registry: &HandlerRegistry,
+    stores: &Stores,
+    cli: &Cli,
+    logger: &DebugLogger,
+) -> HookReply {
+    logger.log(&format!("Received event: {}", event_json.trim()));
+    log::debug!("Received event: {}", event_json.trim());
+
+    // Parse the event to determine hook type
+    let mut event = match Event::from_json(event_json) {
+        Ok(e) => e,
+        Err(e) => {
+            logger.log(&format!("Failed to parse event JSON: {}", e));
+            log::warn!("Failed to parse event JSON: {}", e);
+            return HookReply::Json(hook_error_response(&format!("Invalid JSON: {}", e)));
+        }
+    };
+
+    // Create the appropriate hook type
+    let hook = match hook_from_event(&event) {
+        Ok(h) => {
+            logger.log(&format!("Hook type: {}", h.hook_type()));
+            log::info!("Hook type: {}", h.hook_type());
+            h
+        }
+        Err(e) => {
+            logger.log(&format!("Unknown hook type: {}", e));
+            log::warn!("Unknown hook type: {}", e);
+            return HookReply::Json(hook_error_response(&format!("Unknown hook: {}", e)));
+        }
+    };
+
+    // A forced hook type replaces the event's own, so handlers match on it too
+    let forced_json = forced_hook_type().map(|forced| {
+        event.data.insert("hook_event_name".to_string(), Value::String(forced));
+        event.to_json()
+    });
+    let event_json = forced_json.as_deref().unwrap_or(event_json);
+
+    // Optionally reject events missing fields their hook type requires
+    if cli.validate {
+        if let Err(e) = event.validate(hook.hook_type()) {
+            logger.log(&format!("Invalid event: {}", e));
+            log::warn!("Invalid event: {}", e);
+            return HookReply::Json(hook_error_response(&format!("Invalid event: {}", e)));
+        }
+    }
+
+    if cli.explain {
+        for line in explain_matching(&event, config) {
+            eprintln!("[explain] {}", line);
+        }
+    }
+
+    // In dry-run mode, describe the matching handlers but don't run them
+    if cli.dry_run {
+        let outcomes = dry_run(&event, config, registry);
+        logger.log(&format!("Dry run: {} handlers matched", outcomes.len()));
+        return HookReply::Json(hook.generate_response(&outcomes));
+    }
+
+    // Process the event through handlers
+    match process_event_with(event_json, config, registry, stores).await {
+        Ok(outcomes) => {
+            // Log handler outcomes
+            let successes = outcomes.iter().filter(|o| matches!(o, HandlerOutcome::Success)).count();
+            let errors = outcomes.iter().filter(|o| matches!(o, HandlerOutcome::Error(_))).count();
+
+            if errors == 0 {
+                logger.log(&format!("Event processed successfully ({} handlers)", successes));
+                log::info!("Event processed successfully ({} handlers)", successes);
+            } else {
+                logger.log(&format!("Event processed: {} succeeded, {} failed", successes, errors));
+                log::info!("Event processed: {} succeeded, {} failed", successes, errors);
+                for outcome in &outcomes {
+                    if let HandlerOutcome::Error(msg) = outcome {
+                        logger.log(&format!("Handler error: {}", msg));
+                        log::warn!("Handler error: {}", msg);
                     }
                 }
-                Err(e) => {
-                    logger.log(&format!("Error processing event: {}", e));
-                    // Still output a valid response (empty object)
-                    println!("{{}}");
+            }
+            for outcome in &outcomes {
+                if let HandlerOutcome::Skipped(reason) = outcome {
+                    logger.log(&format!("Handler skipped: {}", reason));
                 }
             }
 
-            logger.log("Event processed, exiting");
+            if config.blocks_on_error() {
+                if let Some(reason) = blocking_feedback(hook.as_ref(), &outcomes) {
+                    logger.log(&format!("Blocking on handler errors: {}", reason));
+                    return HookReply::Block(format!("Boopifier handler errors: {}", reason));
+                }
+            }
+
+            // Generate hook-specific response, with handler-provided keys merged in
+            HookReply::Json(merge_responses(hook.as_ref(), &outcomes))
         }
         Err(e) => {
-            logger.log(&format!("Error reading stdin: {}", e));
-            output_hook_error(&format!("Error reading stdin: {}", e));
+            logger.log(&format!("Error processing event: {}", e));
+            // Still output a valid response (empty object)
+            HookReply::Json(json!({}))
         }
     }
+}
 
-    // Explicitly exit to avoid hanging on background threads (rodio/tokio cleanup)
-    process::exit(0);
+/// Resolves and loads the config, returning the path it was looked up at.
+///
+/// Secrets are resolved automatically. Without -c and without a JSON config,
+/// falls back to layered TOML configs.
+fn load_config(cli: &Cli, logger: &DebugLogger) -> (PathBuf, boopifier::Result<Config>) {
+    let config_path = match &cli.config {
+        Some(path) => {
+            logger.log(&format!("Using config from CLI arg: {:?}", path));
+            path.clone()
+        }
+        None => {
+            let resolved = resolve_config_path();
+            logger.log(&format!("Auto-detected config: {:?}", resolved));
+            resolved
+        }
+    };
+
+    let toml_layers = toml_config_paths();
+    let loaded = if cli.config.is_none() && !config_path.exists() {
+        match Config::load_layered(&toml_layers) {
+            Ok(Some(cfg)) => {
+                logger.log(&format!("Loaded layered TOML config: {:?}", toml_layers));
+                Ok(cfg)
+            }
+            Ok(None) => Config::load(&config_path),
+            Err(e) => Err(e),
+        }
+    } else {
+        Config::load(&config_path)
+    };
+
+    (config_path, loaded)
+}
+
+/// Prints the problems found in a loaded config and returns the exit code.
+///
+/// A config that fails to load (including invalid regex or glob patterns) is
+/// reported as a single problem. Exits 1 if there are any problems.
+fn validate_config(config_path: &Path, loaded: boopifier::Result<Config>) -> i32 {
+    let problems = match loaded {
+        Ok(config) => {
+            let registry = HandlerRegistry::new();
+            let mut problems: Vec<String> = config
+                .handlers
+                .iter()
+                .chain(config.overrides.iter().flatten().flat_map(|o| o.handlers.iter()))
+                .filter(|h| registry.get(&h.handler_type).is_none())
+                .map(|h| format!("Handler '{}' has unknown type: {}", h.name, h.handler_type))
+                .collect();
+            problems.extend(config.problems());
+            problems
+        }
+        Err(e) => vec![e.to_string()],
+    };
+
+    if problems.is_empty() {
+        println!("Config OK: {}", config_path.display());
+        return 0;
+    }
+
+    println!("Config problems in {}:", config_path.display());
+    for problem in &problems {
+        println!("  - {}", problem);
+    }
+    1
+}
+
+/// Socket path for `--daemon` and `--client`.
+///
+/// Defaults to `boopifier.sock` in `$XDG_RUNTIME_DIR`, or else in a private
+/// `boopifier-<uid>` directory under `$TMPDIR`, so other local users can't
+/// bind it before the daemon starts.
+#[cfg(unix)]
+fn socket_path(cli: &Cli) -> PathBuf {
+    cli.socket.clone().unwrap_or_else(|| {
+        let dir = std::env::var_os("XDG_RUNTIME_DIR")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| std::env::temp_dir().join(format!("boopifier-{}", daemon::uid())));
+        dir.join("boopifier.sock")
+    })
+}
+
+/// Unix socket daemon and client.
+///
+/// The client writes one event line and reads back one `HookReply` as a JSON
+/// line, then replays it as if it had handled the event itself. The daemon
+/// handles each connection in its own task, so a slow handler or a stalled
+/// client doesn't hold up other sessions' hooks. Debounce and rate limit
+/// state lives in the daemon's memory; sessions, digests and metrics still use
+/// their state files so they survive a restart.
+#[cfg(unix)]
+mod daemon {
+    use super::*;
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+    use boopifier::debounce::Debouncer;
+    use boopifier::rate_limit::RateLimiter;
+    use std::sync::Arc;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::{UnixListener, UnixStream};
+
+    /// How long a client has to send its event line.
+    const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// What every connection shares.
+    struct Shared {
+        config: Config,
+        registry: HandlerRegistry,
+        stores: Stores,
+        cli: Cli,
+        logger: Arc<DebugLogger>,
+    }
+
+    /// Effective user id of this process.
+    pub fn uid() -> u32 {
+        // SAFETY: geteuid has no preconditions and can't fail
+        unsafe { libc::geteuid() }
+    }
+
+    /// Creates the socket's directory (mode 0700) if it's missing.
+    ///
+    /// An existing directory must belong to this user or root, so another
+    /// user can't pre-create it and swap the socket out.
+    fn prepare_dir(socket: &Path) -> io::Result<()> {
+        let Some(dir) = socket.parent().filter(|dir| !dir.as_os_str().is_empty()) else {
+            return Ok(());
+        };
+        if !dir.exists() {
+            return fs::DirBuilder::new().mode(0o700).create(dir);
+        }
+
+        let owner = fs::metadata(dir)?.uid();
+        if owner != uid() && owner != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{:?} belongs to another user (uid {})", dir, owner),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fails unless `socket` belongs to this user, so a socket another local
+    /// user bound can't answer hooks (e.g. with a forged `allow`).
+    fn check_owner(socket: &Path) -> io::Result<()> {
+        let owner = fs::symlink_metadata(socket)?.uid();
+        if owner != uid() {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("socket belongs to another user (uid {})", owner),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Binds `socket` and answers events until the process is killed.
+    ///
+    /// A stale socket file is replaced; one that a live daemon is listening
+    /// on is an error.
+    pub async fn serve(
+        socket: &Path,
+        config: Config,
+        registry: HandlerRegistry,
+        cli: Cli,
+        logger: Arc<DebugLogger>,
+    ) -> io::Result<()> {
+        prepare_dir(socket)?;
+        if socket.exists() {
+            check_owner(socket)?;
+            if UnixStream::connect(socket).await.is_ok() {
+                return Err(io::Error::new(io::ErrorKind::AddrInUse, "a daemon is already listening"));
+            }
+            fs::remove_file(socket)?;
+        }
+
+        let listener = UnixListener::bind(socket)?;
+        logger.log(&format!("Daemon listening on {:?}", socket));
+        log::info!("Daemon listening on {:?}", socket);
+
+        let shared = Arc::new(Shared {
+            config,
+            registry,
+            stores: Stores {
+                debouncer: Debouncer::in_memory(),
+                rate_limiter: RateLimiter::in_memory(),
+                ..Stores::default_files()
+            },
+            cli,
+            logger,
+        });
+
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let shared = shared.clone();
+            tokio::spawn(async move {
+                if let Err(e) = handle(stream, &shared).await {
+                    shared.logger.log(&format!("Daemon connection error: {}", e));
+                    log::warn!("Daemon connection error: {}", e);
+                }
+            });
+        }
+    }
+
+    async fn handle(stream: UnixStream, shared: &Shared) -> io::Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut event_json = String::new();
+        let mut reader = BufReader::new(reader);
+        tokio::time::timeout(READ_TIMEOUT, reader.read_line(&mut event_json))
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "no event received"))??;
+
+        let Shared { config, registry, stores, cli, logger } = shared;
+        let reply = respond_to_event(&event_json, config, registry, stores, cli, logger).await;
+        let mut line = serde_json::to_string(&reply)?;
+        line.push('\n');
+        writer.write_all(line.as_bytes()).await
+    }
+
+    /// Sends the event to the daemon on `socket` and emits its reply.
+    ///
+    /// Returns the exit code to use. If the daemon can't be reached, this
+    /// prints a warning response and exits 0 like other setup failures.
+    pub async fn run_client(socket: &Path, event_file: Option<&Path>) -> i32 {
+        let event_json = match read_event_input(event_file) {
+            Ok(event_json) => event_json,
+            Err(e) => {
+                output_hook_error(&format!("Error reading input: {}", e));
+                return 0;
+            }
+        };
+
+        if event_json.trim().is_empty() {
+            eprintln!("boopifier: no event data received on stdin");
+            return 1;
+        }
+
+        match exchange(socket, &event_json).await {
+            Ok(reply) => reply.emit(),
+            Err(e) => {
+                log::warn!("Failed to reach boopifier daemon at {:?}: {}", socket, e);
+                output_hook_error(&format!("Failed to reach boopifier daemon at {:?}: {}", socket, e));
+                0
+            }
+        }
+    }
+
+    async fn exchange(socket: &Path, event_json: &str) -> io::Result<HookReply> {
+        check_owner(socket)?;
+        let stream = UnixStream::connect(socket).await?;
+        let (reader, mut writer) = stream.into_split();
+
+        writer.write_all(event_json.trim().as_bytes()).await?;
+        writer.write_all(b"\n").await?;
+        writer.shutdown().await?;
+
+        let mut line = String::new();
+        BufReader::new(reader).read_line(&mut line).await?;
+        serde_json::from_str(&line).map_err(io::Error::from)
+    }
+}
+
+/// Builds the response the event's hook gives when no handler produced an outcome.
+fn passive_response_for(event_json: &str) -> Value {
+    let hook = Event::from_json(event_json).and_then(|event| hook_from_event(&event));
+
+    match hook {
+        Ok(hook) => hook.generate_response(&[]),
+        Err(e) => hook_error_response(&e.to_string()),
+    }
+}
+
+/// Prints a hook response as a single JSON line on stdout.
+fn print_response(response: &Value) {
+    if let Ok(json_str) = serde_json::to_string(response) {
+        println!("{}", json_str);
+    }
+}
+
+/// Reads the raw event JSON from `event_file` if given, otherwise one line from stdin.
+fn read_event_input(event_file: Option<&Path>) -> io::Result<String> {
+    match event_file {
+        Some(path) => fs::read_to_string(path),
+        None => {
+            let mut event_json = String::new();
+            io::stdin().lock().read_line(&mut event_json)?;
+            Ok(event_json)
+        }
+    }
+}
+
+/// How long `--recover` waits for the rest of an incomplete event.
+const RECOVER_WAIT: Duration = Duration::from_millis(500);
+
+/// Reads the event for `--recover`.
+///
+/// Reads stdin until it holds a complete JSON object, stdin closes, or
+/// `wait` passes, then keeps just the object (see `Event::complete_json`).
+/// Empty input is returned as is, for the usual "no event data" error.
+///
+/// # Errors
+///
+/// Fails with `InvalidData` if no complete object arrived in time.
+fn read_event_recovering(event_file: Option<&Path>, wait: Duration) -> io::Result<String> {
+    let raw = match event_file {
+        Some(path) => fs::read_to_string(path)?,
+        None => read_stdin_until_complete(wait)?,
+    };
+
+    if raw.trim().is_empty() {
+        return Ok(raw);
+    }
+    match Event::complete_json(&raw) {
+        Some(json) => {
+            if json.len() != raw.trim().len() {
+                log::warn!("Recovered event JSON from {} bytes of input", raw.len());
+            }
+            Ok(json.to_string())
+        }
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("incomplete event JSON (truncated input?) after waiting {:?}", wait),
+        )),
+    }
+}
+
+/// Reads stdin on a helper thread until EOF, a complete JSON object, or `wait`.
+///
+/// A thread blocked on stdin can't be cancelled; it is left behind when the
+/// wait runs out, and the process exits soon after anyway.
+fn read_stdin_until_complete(wait: Duration) -> io::Result<String> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut stdin = io::stdin().lock();
+        let mut chunk = [0u8; 8192];
+        loop {
+            let read = stdin.read(&mut chunk).map(|n| chunk[..n].to_vec());
+            let done = !matches!(read, Ok(ref bytes) if !bytes.is_empty());
+            if sender.send(read).is_err() || done {
+                break;
+            }
+        }
+    });
+
+    let deadline = std::time::Instant::now() + wait;
+    let mut buffer = Vec::new();
+    loop {
+        let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+        match receiver.recv_timeout(remaining) {
+            Ok(Ok(bytes)) if !bytes.is_empty() => {
+                buffer.extend_from_slice(&bytes);
+                if Event::complete_json(&String::from_utf8_lossy(&buffer)).is_some() {
+                    break;
+                }
+            }
+            Ok(Err(e)) => return Err(e),
+            // EOF, or nothing more arrived in time
+            _ => break,
+        }
+    }
+
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
 }
 
 /// Resolve the config file path using Claude Code conventions.
@@ -246,6 +916,18 @@ fn resolve_config_path() -> PathBuf {
     PathBuf::from(home).join(".claude/boopifier.json")
 }
 
+/// TOML config layers, lowest precedence first.
+///
+/// 1. ~/.config/boopifier/config.toml
+/// 2. $CLAUDE_PROJECT_DIR/.boopifier.toml (if CLAUDE_PROJECT_DIR is set)
+///
+/// Only used when no JSON config is found by `resolve_config_path`.
+fn toml_config_paths() -> Vec<PathBuf> {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    let project_dir = std::env::var("CLAUDE_PROJECT_DIR").ok().map(PathBuf::from);
+    Config::default_toml_layers(Path::new(&home), project_dir.as_deref())
+}
+
 fn list_available_handlers() {
     let registry = HandlerRegistry::new();
     println!("Available notification handlers:");
@@ -254,14 +936,48 @@ fn list_available_handlers() {
     }
 }
 
-/// Output error hook response in Claude Code format (still continues)
-fn output_hook_error(error_message: &str) {
-    let response = json!({
+/// Prints one hook type per line, in `HOOK_TYPES` order so output is stable.
+fn list_hook_types() {
+    for hook_type in boopifier::hooks::HOOK_TYPES {
+        println!("{}", hook_type);
+    }
+}
+
+/// Prints one line per handler that would run to stderr and returns synthetic outcomes.
+fn dry_run(event: &Event, config: &Config, registry: &HandlerRegistry) -> Vec<HandlerOutcome> {
+    let mut outcomes = Vec::new();
+
+    for handler_config in matching_handlers(event, config) {
+        if registry.get(&handler_config.handler_type).is_some() {
+            eprintln!(
+                "[dry-run] would run handler '{}' (type: {})",
+                handler_config.name, handler_config.handler_type
+            );
+            outcomes.push(HandlerOutcome::Success);
+        } else {
+            eprintln!(
+                "[dry-run] handler '{}' has unknown type: {}",
+                handler_config.name, handler_config.handler_type
+            );
+        }
+    }
+
+    if outcomes.is_empty() {
+        eprintln!("[dry-run] no handlers matched");
+    }
+
+    outcomes
+}
+
+/// Builds an error hook response in Claude Code format (still continues)
+fn hook_error_response(error_message: &str) -> Value {
+    json!({
         "continue": true,
         "systemMessage": format!("Boopifier warning: {}", error_message)
-    });
+    })
+}
 
-    if let Ok(json_str) = serde_json::to_string(&response) {
-        println!("{}", json_str);
-    }
+/// Output error hook response in Claude Code format (still continues)
+fn output_hook_error(error_message: &str) {
+    print_response(&hook_error_response(error_message));
 }
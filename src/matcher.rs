@@ -4,9 +4,37 @@
 
 use crate::config::{MatchRules, MatchType};
 use crate::event::Event;
+use once_cell::sync::OnceCell;
 use regex::Regex;
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Compiled regex patterns, keyed by pattern source.
+static REGEX_CACHE: OnceCell<Mutex<HashMap<String, Regex>>> = OnceCell::new();
+
+/// Matches `text` against `pattern`, compiling each pattern only once.
+///
+/// Invalid patterns never match; `Config::from_json` rejects them up front.
+fn regex_is_match(pattern: &str, text: &str) -> bool {
+    let mut cache = REGEX_CACHE
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    if let Some(re) = cache.get(pattern) {
+        return re.is_match(text);
+    }
+
+    match Regex::new(pattern) {
+        Ok(re) => {
+            let matched = re.is_match(text);
+            cache.insert(pattern.to_string(), re);
+            matched
+        }
+        Err(_) => false,
+    }
+}
 
 /// Checks if an event matches the given rules.
 ///
@@ -14,7 +42,7 @@ use std::collections::HashMap;
 ///
 /// * `event` - The event to match against
 /// * `rules` - The matching rules (None matches all events)
-/// * `match_type` - The type of matching to use for strings (exact or regex)
+/// * `match_type` - The type of matching to use for strings (exact, regex, or glob)
 ///
 /// # Examples
 ///
@@ -32,116 +60,593 @@ use std::collections::HashMap;
 /// assert!(matches(&event, &Some(MatchRules::Simple(rules)), &MatchType::Exact));
 /// ```
 pub fn matches(event: &Event, rules: &Option<MatchRules>, match_type: &MatchType) -> bool {
+    matches_with_case(event, rules, match_type, false)
+}
+
+/// Like [`matches`], optionally comparing strings case-insensitively.
+///
+/// `ignore_case` applies to exact, regex and glob string comparisons.
+pub fn matches_with_case(
+    event: &Event,
+    rules: &Option<MatchRules>,
+    match_type: &MatchType,
+    ignore_case: bool,
+) -> bool {
     match rules {
         None => true, // No rules means match all
-        Some(MatchRules::Simple(simple_rules)) => {
-            // Check if this is actually a complex rule that was mis-deserialized
-            // (happens because untagged enums try Simple first)
-            if simple_rules.contains_key("any") || simple_rules.contains_key("all") || simple_rules.contains_key("not") {
-                // Extract complex rule components
-                let all = simple_rules.get("all")
-                    .and_then(|v| v.as_array())
-                    .map(|arr| arr.iter().filter_map(|v| v.as_object().map(|o| {
-                        o.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
-                    })).collect());
-
-                let any = simple_rules.get("any")
-                    .and_then(|v| v.as_array())
-                    .map(|arr| arr.iter().filter_map(|v| v.as_object().map(|o| {
-                        o.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
-                    })).collect());
-
-                let not = simple_rules.get("not")
-                    .and_then(|v| v.as_object())
-                    .map(|o| o.iter().map(|(k, v)| (k.clone(), v.clone())).collect());
-
-                matches_complex(event, all.as_ref(), any.as_ref(), not.as_ref(), match_type)
-            } else {
-                matches_simple(event, simple_rules, match_type)
-            }
+        Some(rules) => Matcher::from_rules(rules).evaluate(event, match_type, ignore_case),
+    }
+}
+
+/// Marks a rule key as a field path even if it names a reserved key.
+const FIELD_PREFIX: &str = "field:";
+
+/// A tree of match conditions built from `MatchRules`.
+///
+/// Rule maps are implicitly ANDed. The reserved keys `all`, `any` and `not`
+/// nest further rule maps, so combinators can be composed to any depth.
+/// `present` and `absent` take a field path (or a list of paths) and test
+/// only whether the field exists. `gt`, `gte`, `lt`, `lte` and `eq` map
+/// field paths to numbers for numeric comparisons. `path_prefix` and
+/// `path_glob` take a string (or a list, any of which may match) and test the
+/// file a tool acts on (see `Event::file_path`), both as given and relative
+/// to the event's `cwd`. `cwd_prefix` and `cwd_glob` work the same way on
+/// the event's `cwd` (or boopifier's own working directory if the event has
+/// none); `cwd_prefix` matches the directory itself and anything below it.
+///
+/// The reserved keys are `all`, `any`, `not`, `present`, `absent`, `gt`,
+/// `gte`, `lt`, `lte`, `eq`, `path_prefix`, `path_glob`, `cwd_prefix` and
+/// `cwd_glob`. Every other key is a field path. To match an event field
+/// with a reserved name, prefix it with `field:` (`"field:present": true`);
+/// the rest of the key is always taken as a field path:
+///
+/// ```json
+/// {
+///   "any": [{"tool_name": "bash"}, {"tool_name": "zsh"}],
+///   "not": {"tool_input.file_path": ".*test.*"},
+///   "absent": "permission_decision",
+///   "gt": {"duration_ms": 30000},
+///   "path_prefix": ["src/", "tests/"],
+///   "cwd_prefix": "~/work"
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Matcher {
+    /// Matches when every child matches (an empty list always matches)
+    All(Vec<Matcher>),
+    /// Matches when at least one child matches (an empty list never matches)
+    Any(Vec<Matcher>),
+    /// Matches when the child does not
+    Not(Box<Matcher>),
+    /// Compares the event value at `path` with `expected`
+    Field { path: String, expected: Value },
+    /// Matches when the event has a value (of any type) at the path
+    Present(String),
+    /// Matches when the event has no value at the path
+    Absent(String),
+    /// Compares the event value at `path`, parsed as a number, with `value`
+    Compare {
+        path: String,
+        op: Comparison,
+        value: f64,
+    },
+    /// Matches when the tool's file path starts with the prefix
+    PathPrefix(String),
+    /// Matches when the tool's file path matches the glob
+    PathGlob(String),
+    /// Matches when the working directory is the directory or inside it
+    CwdPrefix(String),
+    /// Matches when the working directory matches the glob
+    CwdGlob(String),
+}
+
+/// Numeric comparison operators for `Matcher::Compare`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    /// `>` (`gt`)
+    Gt,
+    /// `>=` (`gte`)
+    Gte,
+    /// `<` (`lt`)
+    Lt,
+    /// `<=` (`lte`)
+    Lte,
+    /// `==` (`eq`)
+    Eq,
+}
+
+impl Comparison {
+    /// Parses an operator from its rule key.
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "gt" => Some(Self::Gt),
+            "gte" => Some(Self::Gte),
+            "lt" => Some(Self::Lt),
+            "lte" => Some(Self::Lte),
+            "eq" => Some(Self::Eq),
+            _ => None,
         }
-        Some(MatchRules::Complex { all, any, not }) => {
-            matches_complex(event, all.as_ref(), any.as_ref(), not.as_ref(), match_type)
+    }
+
+    /// The operator's symbol, e.g. `>=`.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Gt => ">",
+            Self::Gte => ">=",
+            Self::Lt => "<",
+            Self::Lte => "<=",
+            Self::Eq => "==",
+        }
+    }
+
+    /// Applies the operator as `actual <op> expected`.
+    pub fn apply(self, actual: f64, expected: f64) -> bool {
+        match self {
+            Self::Gt => actual > expected,
+            Self::Gte => actual >= expected,
+            Self::Lt => actual < expected,
+            Self::Lte => actual <= expected,
+            Self::Eq => actual == expected,
         }
     }
 }
 
-fn matches_simple(event: &Event, rules: &HashMap<String, Value>, match_type: &MatchType) -> bool {
-    for (key, expected_value) in rules {
-        // Support nested keys with dot notation (e.g., "tool.name")
-        let actual_value = if key.contains('.') {
-            event.get_nested_str(key).map(|s| Value::String(s.to_string()))
+impl Matcher {
+    /// Builds a matcher tree from configured rules.
+    pub fn from_rules(rules: &MatchRules) -> Self {
+        match rules {
+            // Untagged deserialization always produces Simple first, so
+            // combinator keys inside a simple map are handled by from_map
+            MatchRules::Simple(map) => Self::from_map(map.iter()),
+            MatchRules::Complex { all, any, not } => {
+                let mut parts = Vec::new();
+                if let Some(rules) = all {
+                    parts.push(Matcher::All(rules.iter().map(|r| Self::from_map(r.iter())).collect()));
+                }
+                if let Some(rules) = any {
+                    parts.push(Matcher::Any(rules.iter().map(|r| Self::from_map(r.iter())).collect()));
+                }
+                if let Some(rule) = not {
+                    parts.push(Matcher::Not(Box::new(Self::from_map(rule.iter()))));
+                }
+
+                // Complex rules must have at least one field set
+                if parts.is_empty() {
+                    Matcher::Any(Vec::new())
+                } else {
+                    Matcher::All(parts)
+                }
+            }
+        }
+    }
+
+    /// Builds a matcher from one rule map, ANDing its entries.
+    fn from_map<'a>(entries: impl Iterator<Item = (&'a String, &'a Value)>) -> Self {
+        let mut parts: Vec<Matcher> = entries
+            .map(|(key, value)| {
+                if let Some(path) = key.strip_prefix(FIELD_PREFIX) {
+                    return Matcher::Field {
+                        path: path.to_string(),
+                        expected: value.clone(),
+                    };
+                }
+                if let Some(op) = Comparison::from_key(key) {
+                    return Self::comparisons(value, op);
+                }
+
+                match key.as_str() {
+                    "all" => Matcher::All(Self::nested_list(value)),
+                    "any" => Matcher::Any(Self::nested_list(value)),
+                    "not" => Matcher::Not(Box::new(Self::nested(value))),
+                    "present" => Self::for_each_path(value, Matcher::Present),
+                    "absent" => Self::for_each_path(value, Matcher::Absent),
+                    "path_prefix" => Self::any_pattern(value, Matcher::PathPrefix),
+                    "path_glob" => Self::any_pattern(value, Matcher::PathGlob),
+                    "cwd_prefix" => Self::any_pattern(value, Matcher::CwdPrefix),
+                    "cwd_glob" => Self::any_pattern(value, Matcher::CwdGlob),
+                    _ => Matcher::Field {
+                        path: key.clone(),
+                        expected: value.clone(),
+                    },
+                }
+            })
+            .collect();
+
+        if parts.len() == 1 {
+            parts.remove(0)
         } else {
-            event.data.get(key).cloned()
+            Matcher::All(parts)
+        }
+    }
+
+    /// Parses a nested rule map; anything else never matches.
+    fn nested(value: &Value) -> Self {
+        match value.as_object() {
+            Some(map) => Self::from_map(map.iter()),
+            None => Matcher::Any(Vec::new()),
+        }
+    }
+
+    /// Parses a list of nested rule maps, skipping entries that aren't maps.
+    fn nested_list(value: &Value) -> Vec<Matcher> {
+        value
+            .as_array()
+            .map(|items| items.iter().filter(|v| v.is_object()).map(Self::nested).collect())
+            .unwrap_or_default()
+    }
+
+    /// Applies `leaf` to a path or to each path in a list, ANDing the results.
+    fn for_each_path(value: &Value, leaf: fn(String) -> Matcher) -> Self {
+        match value {
+            Value::String(path) => leaf(path.clone()),
+            Value::Array(paths) => Matcher::All(
+                paths
+                    .iter()
+                    .filter_map(|p| p.as_str())
+                    .map(|p| leaf(p.to_string()))
+                    .collect(),
+            ),
+            _ => Matcher::Any(Vec::new()),
+        }
+    }
+
+    /// Applies `leaf` to a pattern or to each pattern in a list, ORing the results.
+    fn any_pattern(value: &Value, leaf: fn(String) -> Matcher) -> Self {
+        match value {
+            Value::String(pattern) => leaf(pattern.clone()),
+            Value::Array(patterns) => Matcher::Any(
+                patterns
+                    .iter()
+                    .filter_map(|p| p.as_str())
+                    .map(|p| leaf(p.to_string()))
+                    .collect(),
+            ),
+            _ => Matcher::Any(Vec::new()),
+        }
+    }
+
+    /// Parses `{"path": number, ...}` into ANDed comparisons.
+    ///
+    /// Non-numeric thresholds never match.
+    fn comparisons(value: &Value, op: Comparison) -> Self {
+        let Some(map) = value.as_object() else {
+            return Matcher::Any(Vec::new());
         };
 
-        match actual_value {
-            Some(actual) => {
-                if !values_match(&actual, expected_value, match_type) {
-                    return false;
+        Matcher::All(
+            map.iter()
+                .map(|(path, threshold)| match threshold.as_f64() {
+                    Some(value) => Matcher::Compare {
+                        path: path.clone(),
+                        op,
+                        value,
+                    },
+                    None => Matcher::Any(Vec::new()),
+                })
+                .collect(),
+        )
+    }
+
+    /// Evaluates the matcher against an event.
+    ///
+    /// `All` and `Any` short-circuit: evaluation stops at the first child
+    /// that decides the result.
+    pub fn evaluate(&self, event: &Event, match_type: &MatchType, ignore_case: bool) -> bool {
+        match self {
+            Matcher::All(children) => children.iter().all(|m| m.evaluate(event, match_type, ignore_case)),
+            Matcher::Any(children) => children.iter().any(|m| m.evaluate(event, match_type, ignore_case)),
+            Matcher::Not(child) => !child.evaluate(event, match_type, ignore_case),
+            Matcher::Field { path, expected } => {
+                field_matches(event, path, expected, match_type, ignore_case)
+            }
+            // With `*` in the path, any matching branch is enough
+            Matcher::Present(path) => event.get_nested_value(path).is_some(),
+            Matcher::Absent(path) => event.get_nested_value(path).is_none(),
+            Matcher::Compare { path, op, value } => {
+                let actual: Vec<f64> = event.get_nested_values(path).into_iter().filter_map(as_number).collect();
+                let matched = actual.iter().any(|&actual| op.apply(actual, *value));
+                log::debug!(
+                    "Rule {}: {:?} {}, got {:?} -> {}",
+                    path,
+                    op,
+                    value,
+                    actual,
+                    if matched { "match" } else { "no match" }
+                );
+                matched
+            }
+            Matcher::PathPrefix(prefix) => path_matches(event, "path_prefix", prefix, |path| {
+                if ignore_case {
+                    path.to_lowercase().starts_with(&prefix.to_lowercase())
+                } else {
+                    path.starts_with(prefix.as_str())
                 }
+            }),
+            Matcher::PathGlob(pattern) => {
+                let Ok(glob) = glob::Pattern::new(pattern) else {
+                    return false;
+                };
+                let options = glob::MatchOptions {
+                    case_sensitive: !ignore_case,
+                    ..Default::default()
+                };
+                path_matches(event, "path_glob", pattern, |path| glob.matches_with(path, options))
+            }
+            Matcher::CwdPrefix(prefix) => {
+                let prefix = shellexpand::tilde(prefix);
+                cwd_matches(event, "cwd_prefix", &prefix, |cwd| {
+                    if ignore_case {
+                        is_within(&cwd.to_lowercase(), &prefix.to_lowercase())
+                    } else {
+                        is_within(cwd, &prefix)
+                    }
+                })
+            }
+            Matcher::CwdGlob(pattern) => {
+                let pattern = shellexpand::tilde(pattern);
+                let Ok(glob) = glob::Pattern::new(&pattern) else {
+                    return false;
+                };
+                let options = glob::MatchOptions {
+                    case_sensitive: !ignore_case,
+                    ..Default::default()
+                };
+                cwd_matches(event, "cwd_glob", &pattern, |cwd| glob.matches_with(cwd, options))
             }
-            None => return false,
         }
     }
-    true
-}
 
-fn matches_complex(
-    event: &Event,
-    all: Option<&Vec<HashMap<String, Value>>>,
-    any: Option<&Vec<HashMap<String, Value>>>,
-    not: Option<&HashMap<String, Value>>,
-    match_type: &MatchType,
-) -> bool {
-    // Complex rules must have at least one field set
-    if all.is_none() && any.is_none() && not.is_none() {
-        return false;
+    /// Evaluates the matcher like `evaluate`, describing each rule it checks.
+    ///
+    /// Returns the verdict and one line per rule, indented by nesting depth,
+    /// with the event values the rule read and whether it matched. Unlike
+    /// `evaluate`, every child is checked so the trace is complete.
+    pub fn explain(&self, event: &Event, match_type: &MatchType, ignore_case: bool) -> (bool, Vec<String>) {
+        let mut lines = Vec::new();
+        let matched = self.trace(event, match_type, ignore_case, 0, &mut lines);
+        (matched, lines)
     }
 
-    // "all" - all conditions must match
-    if let Some(all_rules) = all {
-        for rule in all_rules {
-            if !matches_simple(event, rule, match_type) {
-                return false;
+    fn trace(
+        &self,
+        event: &Event,
+        match_type: &MatchType,
+        ignore_case: bool,
+        depth: usize,
+        lines: &mut Vec<String>,
+    ) -> bool {
+        let line = lines.len();
+        lines.push(String::new());
+        let mut trace_all = |children: &[Matcher]| -> Vec<bool> {
+            children
+                .iter()
+                .map(|m| m.trace(event, match_type, ignore_case, depth + 1, lines))
+                .collect()
+        };
+
+        let (matched, description) = match self {
+            Matcher::All(children) => (trace_all(children).into_iter().all(|m| m), "all of".to_string()),
+            Matcher::Any(children) if children.is_empty() => (false, "never (empty or invalid rule)".to_string()),
+            Matcher::Any(children) => (trace_all(children).into_iter().any(|m| m), "any of".to_string()),
+            Matcher::Not(child) => (!trace_all(std::slice::from_ref(child))[0], "not".to_string()),
+            Matcher::Field { path, expected } => {
+                let op = match match_type {
+                    MatchType::Exact => "==",
+                    MatchType::Regex => "matches regex",
+                    MatchType::Glob => "matches glob",
+                };
+                let case = if ignore_case { " (ignoring case)" } else { "" };
+                let got = describe_values(&field_values(event, path));
+                (self.evaluate(event, match_type, ignore_case), format!("{} {} {}{}: got {}", path, op, expected, case, got))
             }
-        }
+            Matcher::Present(path) | Matcher::Absent(path) => {
+                let rule = if matches!(self, Matcher::Present(_)) { "present" } else { "absent" };
+                let got = describe_values(&event.get_nested_values(path));
+                (self.evaluate(event, match_type, ignore_case), format!("{} {}: got {}", rule, path, got))
+            }
+            Matcher::Compare { path, op, value } => {
+                let got = describe_values(&event.get_nested_values(path));
+                (self.evaluate(event, match_type, ignore_case), format!("{} {} {}: got {}", path, op.symbol(), value, got))
+            }
+            Matcher::PathPrefix(pattern) | Matcher::PathGlob(pattern) => {
+                let rule = if matches!(self, Matcher::PathPrefix(_)) { "path_prefix" } else { "path_glob" };
+                let got = event.file_path().map_or("no file path".to_string(), |path| format!("{:?}", path));
+                (self.evaluate(event, match_type, ignore_case), format!("{} {:?}: got {}", rule, pattern, got))
+            }
+            Matcher::CwdPrefix(pattern) | Matcher::CwdGlob(pattern) => {
+                let rule = if matches!(self, Matcher::CwdPrefix(_)) { "cwd_prefix" } else { "cwd_glob" };
+                let got = match event.cwd() {
+                    Some(cwd) => format!("{:?}", cwd),
+                    None => match std::env::current_dir() {
+                        Ok(dir) => format!("{:?} (boopifier's cwd)", dir.to_string_lossy()),
+                        Err(_) => "no cwd".to_string(),
+                    },
+                };
+                (self.evaluate(event, match_type, ignore_case), format!("{} {:?}: got {}", rule, pattern, got))
+            }
+        };
+
+        lines[line] = format!(
+            "{}{} -> {}",
+            "  ".repeat(depth),
+            description,
+            if matched { "match" } else { "no match" }
+        );
+        matched
     }
 
-    // "any" - at least one condition must match
-    if let Some(any_rules) = any {
-        let mut matched = false;
-        for rule in any_rules {
-            if matches_simple(event, rule, match_type) {
-                matched = true;
-                break;
+    /// Returns the string values this matcher compares against.
+    ///
+    /// These are the values interpreted as patterns by the regex and glob
+    /// match types; used to validate them when the config is loaded.
+    pub fn patterns(&self) -> Vec<&str> {
+        let mut patterns = Vec::new();
+        self.collect_patterns(&mut patterns);
+        patterns
+    }
+
+    fn collect_patterns<'a>(&'a self, patterns: &mut Vec<&'a str>) {
+        match self {
+            Matcher::All(children) | Matcher::Any(children) => {
+                children.iter().for_each(|m| m.collect_patterns(patterns))
             }
-        }
-        if !matched {
-            return false;
+            Matcher::Not(child) => child.collect_patterns(patterns),
+            Matcher::Field { expected, .. } => collect_value_patterns(expected, patterns),
+            // Path globs are always globs, whatever the match type
+            Matcher::Present(_)
+            | Matcher::Absent(_)
+            | Matcher::Compare { .. }
+            | Matcher::PathPrefix(_)
+            | Matcher::PathGlob(_)
+            | Matcher::CwdPrefix(_)
+            | Matcher::CwdGlob(_) => {}
         }
     }
+}
 
-    // "not" - condition must not match
-    if let Some(not_rules) = not {
-        if matches_simple(event, not_rules, match_type) {
-            return false;
-        }
+/// Collects strings from an expected value the way `values_match` compares them:
+/// objects field by field, arrays by equality (so not as patterns).
+fn collect_value_patterns<'a>(value: &'a Value, patterns: &mut Vec<&'a str>) {
+    match value {
+        Value::String(s) => patterns.push(s),
+        Value::Object(map) => map.values().for_each(|v| collect_value_patterns(v, patterns)),
+        _ => {}
+    }
+}
+
+/// Tests the event's file path, as given and relative to `cwd`, with `test`.
+///
+/// Events without a file path never match.
+fn path_matches(event: &Event, rule: &str, pattern: &str, test: impl Fn(&str) -> bool) -> bool {
+    let (Some(path), Some(relative)) = (event.file_path(), event.relative_file_path()) else {
+        log::debug!("Rule {} {:?}: no file path in event -> no match", rule, pattern);
+        return false;
+    };
+
+    let matched = test(relative) || test(path);
+    log::debug!(
+        "Rule {} {:?}: got {} -> {}",
+        rule,
+        pattern,
+        path,
+        if matched { "match" } else { "no match" }
+    );
+    matched
+}
+
+/// Tests the event's `cwd`, or the process's working directory if the event
+/// has none, with `test`.
+fn cwd_matches(event: &Event, rule: &str, pattern: &str, test: impl Fn(&str) -> bool) -> bool {
+    let cwd = match event.cwd() {
+        Some(cwd) => cwd.to_string(),
+        None => match std::env::current_dir() {
+            Ok(dir) => dir.to_string_lossy().into_owned(),
+            Err(e) => {
+                log::debug!("Rule {} {:?}: no cwd in event and {} -> no match", rule, pattern, e);
+                return false;
+            }
+        },
+    };
+
+    let matched = test(&cwd);
+    log::debug!(
+        "Rule {} {:?}: got {} -> {}",
+        rule,
+        pattern,
+        cwd,
+        if matched { "match" } else { "no match" }
+    );
+    matched
+}
+
+/// Whether `dir` is `parent` or a directory below it, comparing whole path
+/// components (so `/work/app` isn't within `/work/ap`).
+fn is_within(dir: &str, parent: &str) -> bool {
+    let parent = parent.trim_end_matches('/');
+    match dir.strip_prefix(parent) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+/// Reads a JSON number, or a string holding one, as f64.
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Reads the values a field rule compares.
+///
+/// Supports nested keys with dot notation (e.g., "tool.name"); with `*`
+/// segments there may be several values, and the rule matches if any does.
+fn field_values<'a>(event: &'a Event, key: &str) -> Vec<&'a Value> {
+    if key.contains('.') {
+        event.get_nested_values(key)
+    } else {
+        event.data.get(key).into_iter().collect()
+    }
+}
+
+/// Describes the values a rule read, for `Matcher::explain`.
+fn describe_values(values: &[&Value]) -> String {
+    match values {
+        [] => "nothing".to_string(),
+        [value] => value.to_string(),
+        values => format!("[{}]", values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ")),
     }
+}
+
+fn field_matches(
+    event: &Event,
+    key: &str,
+    expected_value: &Value,
+    match_type: &MatchType,
+    ignore_case: bool,
+) -> bool {
+    let actual_values = field_values(event, key);
 
-    true
+    if actual_values.is_empty() {
+        log::debug!("Rule {}: field missing from event -> no match", key);
+        return false;
+    }
+
+    actual_values.into_iter().any(|actual| {
+        let matched = values_match(actual, expected_value, match_type, ignore_case);
+        log::debug!(
+            "Rule {}: expected {} ({:?}), got {} -> {}",
+            key,
+            expected_value,
+            match_type,
+            actual,
+            if matched { "match" } else { "no match" }
+        );
+        matched
+    })
 }
 
-fn values_match(actual: &Value, expected: &Value, match_type: &MatchType) -> bool {
+fn values_match(actual: &Value, expected: &Value, match_type: &MatchType, ignore_case: bool) -> bool {
     match (actual, expected) {
-        // String matching - exact or regex
+        // String matching - exact, regex, or glob
         (Value::String(a), Value::String(e)) => {
             match match_type {
+                MatchType::Exact if ignore_case => a.to_lowercase() == e.to_lowercase(),
                 MatchType::Exact => a == e,
-                MatchType::Regex => {
-                    // Try to compile regex, fall back to exact match on error
-                    Regex::new(e).map(|re| re.is_match(a)).unwrap_or(false)
-                }
+                MatchType::Regex if ignore_case => regex_is_match(&format!("(?i){}", e), a),
+                MatchType::Regex => regex_is_match(e, a),
+                MatchType::Glob => glob::Pattern::new(e)
+                    .map(|pattern| {
+                        pattern.matches_with(
+                            a,
+                            glob::MatchOptions {
+                                case_sensitive: !ignore_case,
+                                ..Default::default()
+                            },
+                        )
+                    })
+                    .unwrap_or(false),
             }
         }
         (Value::Number(a), Value::Number(e)) => a == e,
@@ -152,7 +657,7 @@ fn values_match(actual: &Value, expected: &Value, match_type: &MatchType) -> boo
 
         // Object: recursive matching
         (Value::Object(a), Value::Object(e)) => {
-            e.iter().all(|(k, ev)| a.get(k).is_some_and(|av| values_match(av, ev, match_type)))
+            e.iter().all(|(k, ev)| a.get(k).is_some_and(|av| values_match(av, ev, match_type, ignore_case)))
         }
 
         // Type mismatch
@@ -376,6 +881,21 @@ mod test_regex_matching {
         assert!(matches(&event, &Some(rules), &MatchType::Regex));
     }
 
+    #[test]
+    fn test_regex_reused_across_events() {
+        let mut rules = HashMap::new();
+        rules.insert("branch".to_string(), json!("^feature/.+"));
+        let rules = Some(MatchRules::Simple(rules));
+
+        for (branch, expected) in [("feature/login", true), ("main", false), ("feature/x", true)] {
+            let event = Event::from_json(&json!({"branch": branch}).to_string()).unwrap();
+            assert_eq!(matches(&event, &rules, &MatchType::Regex), expected, "{}", branch);
+        }
+
+        let cache = REGEX_CACHE.get().unwrap().lock().unwrap();
+        assert!(cache.contains_key("^feature/.+"));
+    }
+
     #[test]
     fn test_exact_match_still_works() {
         let event = Event::from_json(r#"{"status": "success"}"#).unwrap();
@@ -385,3 +905,549 @@ mod test_regex_matching {
         assert!(matches(&event, &Some(MatchRules::Simple(rules)), &MatchType::Exact));
     }
 }
+
+#[cfg(test)]
+mod test_glob_matching {
+    use super::*;
+    use serde_json::json;
+
+    fn tool_matches(tool_name: &str, pattern: &str) -> bool {
+        let event = Event::from_json(&json!({"tool_name": tool_name}).to_string()).unwrap();
+
+        let mut rules = HashMap::new();
+        rules.insert("tool_name".to_string(), json!(pattern));
+        matches(&event, &Some(MatchRules::Simple(rules)), &MatchType::Glob)
+    }
+
+    #[test]
+    fn test_glob_star() {
+        assert!(tool_matches("mcp__github__delete_repo", "mcp__*__delete*"));
+        assert!(tool_matches("mcp__fs__delete", "mcp__*__delete*"));
+    }
+
+    #[test]
+    fn test_glob_question_mark() {
+        assert!(tool_matches("Edit", "Ed?t"));
+        assert!(!tool_matches("Edit", "E?"));
+    }
+
+    #[test]
+    fn test_glob_prefix_and_suffix() {
+        assert!(tool_matches("mcp__slack__post", "mcp__*"));
+        assert!(tool_matches("NotebookEdit", "*Edit"));
+        assert!(!tool_matches("Bash", "mcp__*"));
+    }
+
+    #[test]
+    fn test_glob_no_match() {
+        assert!(!tool_matches("mcp__github__create_issue", "mcp__*__delete*"));
+    }
+
+    #[test]
+    fn test_glob_nested_field() {
+        let event = Event::from_json(r#"{"tool": {"name": "mcp__db__delete_row"}}"#).unwrap();
+
+        let mut rules = HashMap::new();
+        rules.insert("tool.name".to_string(), json!("mcp__*__delete*"));
+        assert!(matches(&event, &Some(MatchRules::Simple(rules)), &MatchType::Glob));
+    }
+}
+
+#[cfg(test)]
+mod test_matcher_composition {
+    use super::*;
+    use serde_json::json;
+
+    fn rules(value: Value) -> Option<MatchRules> {
+        Some(serde_json::from_value(value).unwrap())
+    }
+
+    #[test]
+    fn test_from_rules_builds_tree() {
+        let rules = rules(json!({"not": {"status": "error"}}));
+        assert_eq!(
+            Matcher::from_rules(rules.as_ref().unwrap()),
+            Matcher::Not(Box::new(Matcher::Field {
+                path: "status".to_string(),
+                expected: json!("error"),
+            }))
+        );
+    }
+
+    #[test]
+    fn test_any_and_not_combined() {
+        // bash OR zsh, AND NOT a test file
+        let rules = rules(json!({
+            "any": [{"tool_name": "bash"}, {"tool_name": "zsh"}],
+            "not": {"tool_input.file_path": ".*test.*"}
+        }));
+
+        let fires = |json: Value| {
+            let event = Event::from_json(&json.to_string()).unwrap();
+            matches(&event, &rules, &MatchType::Regex)
+        };
+
+        assert!(fires(json!({"tool_name": "zsh", "tool_input": {"file_path": "src/main.rs"}})));
+        assert!(!fires(json!({"tool_name": "bash", "tool_input": {"file_path": "tests/cli_test.rs"}})));
+        assert!(!fires(json!({"tool_name": "fish", "tool_input": {"file_path": "src/main.rs"}})));
+    }
+
+    #[test]
+    fn test_deeply_nested_combinators() {
+        let rules = rules(json!({
+            "all": [
+                {"hook_event_name": "PreToolUse"},
+                {"any": [
+                    {"tool_name": "Write"},
+                    {"all": [{"tool_name": "Bash"}, {"not": {"tool_input.command": "ls"}}]}
+                ]}
+            ]
+        }));
+
+        let fires = |json: Value| {
+            let event = Event::from_json(&json.to_string()).unwrap();
+            matches(&event, &rules, &MatchType::Exact)
+        };
+
+        assert!(fires(json!({"hook_event_name": "PreToolUse", "tool_name": "Write"})));
+        assert!(fires(json!({"hook_event_name": "PreToolUse", "tool_name": "Bash", "tool_input": {"command": "rm"}})));
+        assert!(!fires(json!({"hook_event_name": "PreToolUse", "tool_name": "Bash", "tool_input": {"command": "ls"}})));
+        assert!(!fires(json!({"hook_event_name": "Stop", "tool_name": "Write"})));
+    }
+
+    #[test]
+    fn test_any_short_circuits() {
+        let event = Event::from_json(r#"{"tool_name": "Bash"}"#).unwrap();
+        let matcher = Matcher::Any(vec![
+            Matcher::Field { path: "tool_name".to_string(), expected: json!("^Bash$") },
+            Matcher::Field { path: "tool_name".to_string(), expected: json!("^never-evaluated-any$") },
+        ]);
+
+        assert!(matcher.evaluate(&event, &MatchType::Regex, false));
+
+        // The second pattern was never compiled, so it was never evaluated
+        let cache = REGEX_CACHE.get().unwrap().lock().unwrap();
+        assert!(!cache.contains_key("^never-evaluated-any$"));
+    }
+
+    #[test]
+    fn test_all_short_circuits() {
+        let event = Event::from_json(r#"{"tool_name": "Bash"}"#).unwrap();
+        let matcher = Matcher::All(vec![
+            Matcher::Field { path: "tool_name".to_string(), expected: json!("^Write$") },
+            Matcher::Field { path: "tool_name".to_string(), expected: json!("^never-evaluated-all$") },
+        ]);
+
+        assert!(!matcher.evaluate(&event, &MatchType::Regex, false));
+
+        let cache = REGEX_CACHE.get().unwrap().lock().unwrap();
+        assert!(!cache.contains_key("^never-evaluated-all$"));
+    }
+}
+
+/// Whether `rules` match `event` with exact matching, for the rule tests below.
+#[cfg(test)]
+fn fires(rules: Value, event: Value) -> bool {
+    let rules = Some(serde_json::from_value(rules).unwrap());
+    let event = Event::from_json(&event.to_string()).unwrap();
+    matches(&event, &rules, &MatchType::Exact)
+}
+
+#[cfg(test)]
+mod test_field_presence {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_absent() {
+        let rules = json!({"absent": "permission_decision"});
+        assert!(fires(rules.clone(), json!({"hook_event_name": "PreToolUse"})));
+        assert!(!fires(rules, json!({"permission_decision": "allow"})));
+    }
+
+    #[test]
+    fn test_present_with_any_value() {
+        let rules = json!({"present": "permission_decision"});
+        assert!(fires(rules.clone(), json!({"permission_decision": "deny"})));
+        assert!(fires(rules.clone(), json!({"permission_decision": null})));
+        assert!(!fires(rules, json!({"hook_event_name": "PreToolUse"})));
+    }
+
+    #[test]
+    fn test_nested_absent() {
+        let rules = json!({"absent": "tool_input.file_path"});
+        assert!(fires(rules.clone(), json!({"tool_input": {"command": "ls"}})));
+        assert!(fires(rules.clone(), json!({"tool_name": "Bash"})));
+        assert!(!fires(rules, json!({"tool_input": {"file_path": "/tmp/x"}})));
+    }
+
+    #[test]
+    fn test_presence_list_combined_with_fields() {
+        let rules = json!({
+            "hook_event_name": "PreToolUse",
+            "present": ["tool_name", "tool_input.command"]
+        });
+        assert!(fires(
+            rules.clone(),
+            json!({"hook_event_name": "PreToolUse", "tool_name": "Bash", "tool_input": {"command": "ls"}})
+        ));
+        assert!(!fires(rules, json!({"hook_event_name": "PreToolUse", "tool_name": "Bash"})));
+    }
+
+    #[test]
+    fn test_field_prefix_matches_reserved_names() {
+        let rules = json!({"field:present": "yes", "field:gt": 3, "field:field:x": "y"});
+        assert!(fires(rules.clone(), json!({"present": "yes", "gt": 3, "field:x": "y"})));
+        assert!(!fires(rules, json!({"present": "no", "gt": 3, "field:x": "y"})));
+
+        // Without the prefix the key is the presence test
+        assert!(!fires(json!({"present": "yes"}), json!({"present": "yes"})));
+    }
+}
+
+#[cfg(test)]
+mod test_numeric_comparisons {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_integer_comparisons() {
+        let event = json!({"duration_ms": 45000});
+        assert!(fires(json!({"gt": {"duration_ms": 30000}}), event.clone()));
+        assert!(fires(json!({"gte": {"duration_ms": 45000}}), event.clone()));
+        assert!(fires(json!({"eq": {"duration_ms": 45000}}), event.clone()));
+        assert!(!fires(json!({"lt": {"duration_ms": 30000}}), event.clone()));
+        assert!(!fires(json!({"lte": {"duration_ms": 44999}}), event));
+    }
+
+    #[test]
+    fn test_float_comparisons() {
+        let event = json!({"stats": {"cost_usd": 0.25}});
+        assert!(fires(json!({"lt": {"stats.cost_usd": 0.5}}), event.clone()));
+        assert!(fires(json!({"gt": {"stats.cost_usd": 0.1}}), event.clone()));
+        assert!(!fires(json!({"gt": {"stats.cost_usd": 0.25}}), event));
+    }
+
+    #[test]
+    fn test_range_combines_operators() {
+        let rules = json!({"gte": {"duration_ms": 1000}, "lt": {"duration_ms": 5000}});
+        assert!(fires(rules.clone(), json!({"duration_ms": 1000})));
+        assert!(!fires(rules, json!({"duration_ms": 5000})));
+    }
+
+    #[test]
+    fn test_non_numeric_fails_cleanly() {
+        assert!(!fires(json!({"gt": {"duration_ms": 30000}}), json!({"duration_ms": "slow"})));
+        assert!(!fires(json!({"gt": {"duration_ms": 30000}}), json!({"duration_ms": [1, 2]})));
+        assert!(!fires(json!({"gt": {"duration_ms": 30000}}), json!({})));
+        assert!(!fires(json!({"gt": {"duration_ms": "30s"}}), json!({"duration_ms": 45000})));
+    }
+
+    #[test]
+    fn test_numeric_string_is_parsed() {
+        assert!(fires(json!({"gt": {"duration_ms": 30000}}), json!({"duration_ms": "45000"})));
+    }
+}
+
+#[cfg(test)]
+mod test_ignore_case {
+    use super::*;
+    use serde_json::json;
+
+    fn fires(pattern: &str, message: &str, match_type: MatchType, ignore_case: bool) -> bool {
+        let rules = Some(serde_json::from_value(json!({"message": pattern})).unwrap());
+        let event = Event::from_json(&json!({"message": message}).to_string()).unwrap();
+        matches_with_case(&event, &rules, &match_type, ignore_case)
+    }
+
+    #[test]
+    fn test_exact_ignore_case() {
+        assert!(!fires("error", "ERROR", MatchType::Exact, false));
+        assert!(fires("error", "ERROR", MatchType::Exact, true));
+        assert!(fires("error", "Error", MatchType::Exact, true));
+        assert!(!fires("error", "errors", MatchType::Exact, true));
+    }
+
+    #[test]
+    fn test_regex_contains_ignore_case() {
+        assert!(!fires("error", "Build Error in main.rs", MatchType::Regex, false));
+        assert!(fires("error", "Build Error in main.rs", MatchType::Regex, true));
+    }
+
+    #[test]
+    fn test_glob_ignore_case() {
+        assert!(!fires("*error*", "FATAL ERROR", MatchType::Glob, false));
+        assert!(fires("*error*", "FATAL ERROR", MatchType::Glob, true));
+    }
+
+    #[test]
+    fn test_matches_defaults_to_case_sensitive() {
+        let rules = Some(serde_json::from_value(json!({"message": "error"})).unwrap());
+        let event = Event::from_json(r#"{"message": "Error"}"#).unwrap();
+        assert!(!matches(&event, &rules, &MatchType::Exact));
+    }
+}
+
+#[cfg(test)]
+mod test_wildcard_paths {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_any_branch_matches() {
+        let event = json!({"tool_input": {"a": {"path": "src/main.rs"}, "b": {"path": "secrets.env"}}});
+
+        assert!(fires(json!({"tool_input.*.path": "secrets.env"}), event.clone()));
+        assert!(fires(json!({"tool_input.*.path": "src/main.rs"}), event.clone()));
+        assert!(!fires(json!({"tool_input.*.path": "Cargo.toml"}), event));
+    }
+
+    #[test]
+    fn test_wildcard_over_array_elements() {
+        let event = json!({"tool_calls": [{"name": "read"}, {"name": "bash", "timeout": 30}]});
+
+        assert!(fires(json!({"tool_calls.*.name": "bash"}), event.clone()));
+        assert!(fires(json!({"gt": {"tool_calls.*.timeout": 10}}), event.clone()));
+        assert!(!fires(json!({"lt": {"tool_calls.*.timeout": 10}}), event));
+    }
+
+    #[test]
+    fn test_presence_with_wildcards() {
+        let event = json!({"tool_input": {"a": {"path": "x"}, "b": {}}});
+
+        assert!(fires(json!({"present": "tool_input.*.path"}), event.clone()));
+        assert!(fires(json!({"absent": "tool_input.*.url"}), event.clone()));
+        assert!(!fires(json!({"absent": "tool_input.*.path"}), event));
+    }
+
+    #[test]
+    fn test_nested_non_string_values_match() {
+        let event = json!({"tool": {"exit_code": 0, "ok": true}});
+
+        assert!(fires(json!({"tool.exit_code": 0}), event.clone()));
+        assert!(fires(json!({"tool.ok": true}), event.clone()));
+        assert!(!fires(json!({"tool.exit_code": 1}), event));
+    }
+}
+
+#[cfg(test)]
+mod test_opencode_tool_events {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_claude_code_rules_match_opencode_event() {
+        let rules = json!({"tool_name": "bash", "tool_input.command": "rm -rf build"});
+
+        let claude_code = json!({
+            "hook_event_name": "PreToolUse",
+            "tool_name": "bash",
+            "tool_input": {"command": "rm -rf build"}
+        });
+        let opencode = json!({
+            "type": "tool.execute.before",
+            "tool": {"name": "bash", "args": {"command": "rm -rf build"}}
+        });
+
+        assert!(fires(rules.clone(), claude_code));
+        assert!(fires(rules.clone(), opencode));
+        assert!(!fires(rules, json!({"tool": {"name": "bash", "args": {"command": "ls"}}})));
+    }
+
+    #[test]
+    fn test_tool_name_rule_matches_bare_opencode_tool() {
+        let event = json!({"tool": "read", "args": {"filePath": "src/main.rs"}});
+
+        assert!(fires(json!({"tool_name": "read"}), event.clone()));
+        assert!(fires(json!({"tool_input.filePath": "src/main.rs"}), event));
+    }
+
+    #[test]
+    fn test_source_event_rule_tells_stops_apart() {
+        let rules = json!({"hook_event_name": "Stop", "boopifier_source_event": "session.completed"});
+        let stop = |source: &str| json!({"hook_event_name": "Stop", "boopifier_source_event": source});
+
+        assert!(fires(rules.clone(), stop("session.completed")));
+        assert!(!fires(rules.clone(), stop("session.idle")));
+        assert!(!fires(rules, json!({"hook_event_name": "Stop"})));
+    }
+}
+
+#[cfg(test)]
+mod test_file_paths {
+    use super::*;
+    use serde_json::json;
+
+    fn edit(file_path: &str) -> Value {
+        json!({
+            "hook_event_name": "PreToolUse",
+            "cwd": "/home/me/app",
+            "tool_name": "Edit",
+            "tool_input": {"file_path": file_path}
+        })
+    }
+
+    #[test]
+    fn test_path_prefix_relative_to_cwd() {
+        let deny = json!({"path_prefix": ".env"});
+        let allow = json!({"path_prefix": "src/"});
+
+        assert!(fires(deny.clone(), edit("/home/me/app/.env")));
+        assert!(fires(deny.clone(), edit("/home/me/app/.env.local")));
+        assert!(!fires(deny, edit("/home/me/app/src/main.rs")));
+
+        assert!(fires(allow.clone(), edit("/home/me/app/src/main.rs")));
+        assert!(!fires(allow, edit("/home/me/app/.env")));
+
+        // Absolute prefixes work too
+        assert!(fires(json!({"path_prefix": "/home/me/app/src"}), edit("/home/me/app/src/main.rs")));
+    }
+
+    #[test]
+    fn test_path_glob() {
+        let rules = json!({"path_glob": ["**/.env", "**/*.pem"]});
+
+        assert!(fires(rules.clone(), edit("/home/me/app/.env")));
+        assert!(fires(rules.clone(), edit("/home/me/app/config/.env")));
+        assert!(fires(rules.clone(), edit("/home/me/app/certs/server.pem")));
+        assert!(!fires(rules, edit("/home/me/app/src/main.rs")));
+    }
+
+    #[test]
+    fn test_opencode_file_paths() {
+        let event = json!({
+            "type": "tool.execute.before",
+            "cwd": "/home/me/app",
+            "tool": {"name": "write", "args": {"filePath": "/home/me/app/.env"}}
+        });
+
+        assert!(fires(json!({"path_prefix": ".env"}), event.clone()));
+        assert!(fires(json!({"tool_input.file_path": "/home/me/app/.env"}), event));
+    }
+
+    #[test]
+    fn test_no_file_path_never_matches() {
+        let event = json!({"tool_name": "Bash", "tool_input": {"command": "cat .env"}});
+
+        assert!(!fires(json!({"path_prefix": ""}), event.clone()));
+        assert!(!fires(json!({"path_glob": "*"}), event));
+    }
+}
+
+#[cfg(test)]
+mod test_working_directory {
+    use super::*;
+    use serde_json::json;
+
+    fn stop_in(cwd: &str) -> Value {
+        json!({"hook_event_name": "Stop", "cwd": cwd})
+    }
+
+    #[test]
+    fn test_cwd_prefix() {
+        let rules = json!({"cwd_prefix": ["/home/me/work", "/srv/app/"]});
+
+        assert!(fires(rules.clone(), stop_in("/home/me/work")));
+        assert!(fires(rules.clone(), stop_in("/home/me/work/boopifier")));
+        assert!(fires(rules.clone(), stop_in("/srv/app")));
+        assert!(!fires(rules.clone(), stop_in("/home/me/workshop")));
+        assert!(!fires(rules, stop_in("/home/me/personal")));
+    }
+
+    #[test]
+    fn test_cwd_glob() {
+        let rules = json!({"cwd_glob": "/home/*/work/*"});
+
+        assert!(fires(rules.clone(), stop_in("/home/me/work/boopifier")));
+        assert!(!fires(rules, stop_in("/home/me/personal/notes")));
+    }
+
+    #[test]
+    fn test_cwd_ignore_case() {
+        let rules: MatchRules = serde_json::from_value(json!({"cwd_prefix": "/Users/me/Work"})).unwrap();
+        let matcher = Matcher::from_rules(&rules);
+        let event = Event::from_value(stop_in("/users/me/work/app")).unwrap();
+
+        assert!(matcher.evaluate(&event, &MatchType::Exact, true));
+        assert!(!matcher.evaluate(&event, &MatchType::Exact, false));
+    }
+
+    #[test]
+    fn test_cwd_falls_back_to_process_cwd() {
+        let here = std::env::current_dir().unwrap();
+        let here = here.to_str().unwrap();
+        let event = json!({"hook_event_name": "Stop"});
+
+        assert!(fires(json!({"cwd_prefix": here}), event.clone()));
+        assert!(!fires(json!({"cwd_prefix": "/nonexistent/boopifier"}), event.clone()));
+
+        // The event's own cwd wins over the process's
+        assert!(!fires(json!({"cwd_prefix": here}), stop_in("/nonexistent/boopifier")));
+    }
+
+    #[test]
+    fn test_cwd_prefix_expands_home() {
+        let home = shellexpand::tilde("~").into_owned();
+
+        assert!(fires(json!({"cwd_prefix": "~/work"}), stop_in(&format!("{}/work/app", home))));
+        assert!(!fires(json!({"cwd_prefix": "~/work"}), stop_in("/srv/work/app")));
+    }
+}
+
+#[cfg(test)]
+mod test_explain {
+    use super::*;
+    use serde_json::json;
+
+    fn explain(rules: Value, event: Value) -> (bool, Vec<String>) {
+        let rules: MatchRules = serde_json::from_value(rules).unwrap();
+        let event = Event::from_value(event).unwrap();
+        Matcher::from_rules(&rules).explain(&event, &MatchType::Exact, false)
+    }
+
+    #[test]
+    fn test_explain_reports_values_and_verdicts() {
+        let (matched, lines) = explain(
+            json!({"hook_event_name": "Notification", "not": {"gt": {"duration_ms": 1000}}}),
+            json!({"hook_event_name": "Stop", "duration_ms": 250}),
+        );
+
+        assert!(!matched);
+        assert_eq!(lines[0], "all of -> no match");
+        assert!(lines.contains(&r#"  hook_event_name == "Notification": got "Stop" -> no match"#.to_string()), "{:#?}", lines);
+        assert!(lines.contains(&"  not -> match".to_string()), "{:#?}", lines);
+        // Comparison rules are wrapped in an implicit "all of"
+        assert!(lines.contains(&"      duration_ms > 1000: got 250 -> no match".to_string()), "{:#?}", lines);
+    }
+
+    #[test]
+    fn test_explain_agrees_with_evaluate() {
+        let event = json!({
+            "hook_event_name": "PreToolUse",
+            "cwd": "/work/app",
+            "tool_name": "Edit",
+            "tool_input": {"file_path": "/work/app/src/main.rs"}
+        });
+        for rules in [
+            json!({"tool_name": "Edit"}),
+            json!({"any": [{"tool_name": "Bash"}, {"path_prefix": "src/"}]}),
+            json!({"absent": "tool_input.command", "cwd_prefix": "/work"}),
+            json!({"present": "message"}),
+        ] {
+            let parsed: MatchRules = serde_json::from_value(rules.clone()).unwrap();
+            let matcher = Matcher::from_rules(&parsed);
+            let event = Event::from_value(event.clone()).unwrap();
+
+            let (matched, lines) = matcher.explain(&event, &MatchType::Exact, false);
+            assert_eq!(matched, matcher.evaluate(&event, &MatchType::Exact, false), "{}", rules);
+            assert!(lines[0].ends_with(if matched { "-> match" } else { "-> no match" }), "{:#?}", lines);
+        }
+    }
+
+    #[test]
+    fn test_explain_missing_field() {
+        let (matched, lines) = explain(json!({"tool_name": "Bash"}), json!({"hook_event_name": "Stop"}));
+        assert!(!matched);
+        assert_eq!(lines, vec![r#"tool_name == "Bash": got nothing -> no match"#]);
+    }
+}
@@ -0,0 +1,207 @@
+//! Prometheus-style counters for events and handler outcomes.
+//!
+//! The counters accumulate in a `StateFile` across invocations, and
+//! `--metrics` renders them in the Prometheus text exposition format.
+
+use crate::hooks::HandlerOutcome;
+use crate::state::StateFile;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+/// Cumulative counters across invocations.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Counters {
+    /// Events processed
+    #[serde(default)]
+    pub events: u64,
+
+    /// Events processed per hook type
+    #[serde(default)]
+    pub hooks: BTreeMap<String, u64>,
+
+    /// Handlers that ran and didn't fail
+    #[serde(default)]
+    pub handler_successes: u64,
+
+    /// Handlers that failed or timed out
+    #[serde(default)]
+    pub handler_errors: u64,
+
+    /// Handlers skipped by quiet hours, the rate limit, or debounce
+    #[serde(default)]
+    pub suppressed: u64,
+}
+
+impl Counters {
+    /// Counts one event of `hook_type` ("unknown" if `None`).
+    ///
+    /// `handled` holds the outcomes of handlers that actually ran; handlers
+    /// that were suppressed are only counted in `suppressed`.
+    pub fn record(&mut self, hook_type: Option<&str>, handled: &[HandlerOutcome], suppressed: u64) {
+        self.events += 1;
+        *self
+            .hooks
+            .entry(hook_type.unwrap_or("unknown").to_string())
+            .or_default() += 1;
+
+        for outcome in handled {
+            match outcome {
+                HandlerOutcome::Error(_) => self.handler_errors += 1,
+                HandlerOutcome::Skipped(_) => self.suppressed += 1,
+                _ => self.handler_successes += 1,
+            }
+        }
+        self.suppressed += suppressed;
+    }
+
+    /// Renders the counters in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        write_header(&mut out, "boopifier_events_total", "Events processed.");
+        let _ = writeln!(out, "boopifier_events_total {}", self.events);
+
+        write_header(&mut out, "boopifier_hook_events_total", "Events processed per hook type.");
+        for (hook, count) in &self.hooks {
+            let _ = writeln!(
+                out,
+                "boopifier_hook_events_total{{hook=\"{}\"}} {}",
+                escape_label(hook),
+                count
+            );
+        }
+
+        write_header(&mut out, "boopifier_handler_runs_total", "Handler runs by result.");
+        let _ = writeln!(out, "boopifier_handler_runs_total{{result=\"success\"}} {}", self.handler_successes);
+        let _ = writeln!(out, "boopifier_handler_runs_total{{result=\"error\"}} {}", self.handler_errors);
+
+        write_header(
+            &mut out,
+            "boopifier_suppressed_total",
+            "Handler runs suppressed by quiet hours, rate limit or debounce.",
+        );
+        let _ = writeln!(out, "boopifier_suppressed_total {}", self.suppressed);
+
+        out
+    }
+}
+
+fn write_header(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+}
+
+/// Escapes a label value: backslash, double quote and newline.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Persists `Counters` between invocations.
+pub struct MetricsStore {
+    state: StateFile,
+}
+
+impl MetricsStore {
+    /// Creates a store that persists counters to `state_path`.
+    pub fn new(state_path: impl Into<PathBuf>) -> Self {
+        Self {
+            state: StateFile::new(state_path),
+        }
+    }
+
+    /// Default state file location (`$TMPDIR/boopifier-metrics.json`).
+    pub fn default_path() -> PathBuf {
+        std::env::temp_dir().join("boopifier-metrics.json")
+    }
+
+    /// Loads the counters, starting from zero if the state file is missing or invalid.
+    pub fn load(&self) -> Counters {
+        self.state.load()
+    }
+
+    /// Adds one event to the persisted counters (see `Counters::record`).
+    ///
+    /// State file errors are ignored - metrics are best-effort.
+    pub fn record(&self, hook_type: Option<&str>, handled: &[HandlerOutcome], suppressed: u64) {
+        self.state
+            .update(|counters: &mut Counters| counters.record(hook_type, handled, suppressed));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_increments_counters() {
+        let mut counters = Counters::default();
+        counters.record(
+            Some("Stop"),
+            &[HandlerOutcome::Success, HandlerOutcome::Error("sound: failed".to_string())],
+            0,
+        );
+        counters.record(Some("Stop"), &[HandlerOutcome::Context("ctx".to_string())], 2);
+        counters.record(
+            Some("Notification"),
+            &[HandlerOutcome::Skipped("sound: rate limited".to_string())],
+            0,
+        );
+        counters.record(None, &[], 0);
+
+        assert_eq!(counters.events, 4);
+        assert_eq!(counters.hooks["Stop"], 2);
+        assert_eq!(counters.hooks["Notification"], 1);
+        assert_eq!(counters.hooks["unknown"], 1);
+        assert_eq!(counters.handler_successes, 2);
+        assert_eq!(counters.handler_errors, 1);
+        assert_eq!(counters.suppressed, 3);
+    }
+
+    #[test]
+    fn test_store_persists_across_instances() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metrics.json");
+
+        MetricsStore::new(&path).record(Some("Stop"), &[HandlerOutcome::Success], 0);
+        MetricsStore::new(&path).record(Some("Stop"), &[], 1);
+
+        let counters = MetricsStore::new(&path).load();
+        assert_eq!(counters.events, 2);
+        assert_eq!(counters.hooks["Stop"], 2);
+        assert_eq!(counters.handler_successes, 1);
+        assert_eq!(counters.suppressed, 1);
+    }
+
+    #[test]
+    fn test_render_prometheus_text() {
+        let mut counters = Counters::default();
+        counters.record(Some("Stop"), &[HandlerOutcome::Success], 0);
+        counters.record(Some("PreToolUse"), &[HandlerOutcome::Error("x".to_string())], 1);
+
+        let text = counters.render();
+        assert!(text.contains("# TYPE boopifier_events_total counter\nboopifier_events_total 2\n"));
+        assert!(text.contains("boopifier_hook_events_total{hook=\"PreToolUse\"} 1\n"));
+        assert!(text.contains("boopifier_hook_events_total{hook=\"Stop\"} 1\n"));
+        assert!(text.contains("boopifier_handler_runs_total{result=\"success\"} 1\n"));
+        assert!(text.contains("boopifier_handler_runs_total{result=\"error\"} 1\n"));
+        assert!(text.contains("boopifier_suppressed_total 1\n"));
+
+        // Every sample line follows its HELP and TYPE lines
+        for line in text.lines().filter(|l| !l.starts_with('#')) {
+            let name = line.split(['{', ' ']).next().unwrap();
+            assert!(text.contains(&format!("# TYPE {} counter", name)), "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_render_escapes_label_values() {
+        let mut counters = Counters::default();
+        counters.record(Some("a\"b\\c"), &[], 0);
+        assert!(counters.render().contains(r#"{hook="a\"b\\c"} 1"#));
+    }
+}
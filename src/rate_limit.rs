@@ -0,0 +1,122 @@
+//! Global rate limiting of notifications.
+//!
+//! Unlike debounce, which is per handler, hook and session, the rate limit is
+//! a single sliding window shared by every hook, so one `StateFile` holds the
+//! recent fire times of all of them.
+
+use crate::config::RateLimit;
+use crate::debounce::millis_since_epoch;
+use crate::state::StateFile;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Tracks recent notification times for the global rate limit.
+pub struct RateLimiter {
+    state: StateFile,
+}
+
+impl RateLimiter {
+    /// Creates a rate limiter that persists state to `state_path`.
+    pub fn new(state_path: impl Into<PathBuf>) -> Self {
+        Self {
+            state: StateFile::new(state_path),
+        }
+    }
+
+    /// Creates a rate limiter that keeps its state in memory, e.g. for the daemon.
+    pub fn in_memory() -> Self {
+        Self {
+            state: StateFile::in_memory(),
+        }
+    }
+
+    /// Default state file location (`$TMPDIR/boopifier-rate-limit.json`).
+    pub fn default_path() -> PathBuf {
+        std::env::temp_dir().join("boopifier-rate-limit.json")
+    }
+
+    /// Returns true and records `now` if fewer than `limit.max` notifications
+    /// fired within the window before `now`; otherwise returns false.
+    ///
+    /// Rejected calls aren't recorded, so capacity comes back as old entries
+    /// age out of the window. State file errors are ignored - rate limiting is
+    /// best-effort.
+    pub fn try_acquire(&self, limit: &RateLimit, now: SystemTime) -> bool {
+        let now_ms = millis_since_epoch(now);
+        let window_ms = limit.window().as_millis() as u64;
+
+        self.state.update(|fired: &mut Vec<u64>| {
+            fired.retain(|&at_ms| now_ms.saturating_sub(at_ms) < window_ms);
+
+            let allowed = fired.len() < limit.max as usize;
+            if allowed {
+                fired.push(now_ms);
+            }
+            allowed
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_700_000_000 + secs)
+    }
+
+    fn five_per_minute() -> RateLimit {
+        RateLimit {
+            max: 5,
+            window_ms: 60_000,
+        }
+    }
+
+    #[test]
+    fn test_allows_up_to_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let limiter = RateLimiter::new(dir.path().join("state.json"));
+        let limit = five_per_minute();
+
+        for secs in 0..5 {
+            assert!(limiter.try_acquire(&limit, at(secs)));
+        }
+        assert!(!limiter.try_acquire(&limit, at(5)));
+        assert!(!limiter.try_acquire(&limit, at(59)));
+    }
+
+    #[test]
+    fn test_refills_as_entries_leave_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let limiter = RateLimiter::new(dir.path().join("state.json"));
+        let limit = five_per_minute();
+
+        for secs in 0..5 {
+            assert!(limiter.try_acquire(&limit, at(secs * 10)));
+        }
+        assert!(!limiter.try_acquire(&limit, at(45)));
+
+        // The first entry (t=0) ages out at t=60, freeing exactly one slot
+        assert!(limiter.try_acquire(&limit, at(60)));
+        assert!(!limiter.try_acquire(&limit, at(61)));
+
+        // Everything has aged out a window after the last accepted entry
+        for secs in 0..5 {
+            assert!(limiter.try_acquire(&limit, at(200 + secs)));
+        }
+    }
+
+    #[test]
+    fn test_state_persists_across_instances() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let limit = RateLimit {
+            max: 1,
+            window_ms: 60_000,
+        };
+
+        assert!(RateLimiter::new(&path).try_acquire(&limit, at(0)));
+        assert!(!RateLimiter::new(&path).try_acquire(&limit, at(1)));
+    }
+}
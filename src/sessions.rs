@@ -0,0 +1,191 @@
+//! Session duration tracking.
+//!
+//! `SessionStart` records when each session began and `SessionEnd` looks it
+//! up to report how long the session lasted. The two hooks run in separate
+//! invocations, so the start times are kept in a `StateFile`.
+
+use crate::debounce::millis_since_epoch;
+use crate::event::Event;
+use crate::state::StateFile;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+/// Start times older than this are dropped, e.g. for sessions that crashed.
+const PRUNE_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Event field holding the human-readable session duration (e.g. "1h 5m").
+pub const DURATION_FIELD: &str = "session_duration";
+
+/// Event field holding the session duration in milliseconds.
+pub const DURATION_MS_FIELD: &str = "session_duration_ms";
+
+/// Tracks when each session started.
+pub struct SessionTracker {
+    state: StateFile,
+}
+
+impl SessionTracker {
+    /// Creates a tracker that persists state to `state_path`.
+    pub fn new(state_path: impl Into<PathBuf>) -> Self {
+        Self {
+            state: StateFile::new(state_path),
+        }
+    }
+
+    /// Default state file location (`$TMPDIR/boopifier-sessions.json`).
+    pub fn default_path() -> PathBuf {
+        std::env::temp_dir().join("boopifier-sessions.json")
+    }
+
+    /// Records `now` as the start of `session_id`, unless a start is already recorded.
+    ///
+    /// Claude Code sends `SessionStart` again when a session is resumed,
+    /// compacted or cleared; keeping the first start makes `SessionEnd`
+    /// report the whole session. State file errors are ignored - duration
+    /// tracking is best-effort.
+    pub fn record_start(&self, session_id: &str, now: SystemTime) {
+        let now_ms = millis_since_epoch(now);
+        let prune_ms = PRUNE_AFTER.as_millis() as u64;
+
+        self.state.update(|state: &mut HashMap<String, u64>| {
+            state.retain(|_, &mut started_ms| now_ms.saturating_sub(started_ms) < prune_ms);
+            state.entry(session_id.to_string()).or_insert(now_ms);
+        });
+    }
+
+    /// Removes the start of `session_id` and returns how long ago it was.
+    ///
+    /// Returns `None` if no start was recorded for the session.
+    pub fn finish(&self, session_id: &str, now: SystemTime) -> Option<Duration> {
+        let started_ms = self
+            .state
+            .update(|state: &mut HashMap<String, u64>| state.remove(session_id))?;
+
+        Some(Duration::from_millis(
+            millis_since_epoch(now).saturating_sub(started_ms),
+        ))
+    }
+
+    /// Records or finishes the event's session, depending on its hook type.
+    ///
+    /// For `SessionEnd` events with a recorded start, sets `session_duration`
+    /// and `session_duration_ms` on the event so handlers and templates can
+    /// report them. Events without a `session_id` are left alone.
+    pub fn track(&self, event: &mut Event, now: SystemTime) {
+        let Some(session_id) = event.get_str("session_id").map(str::to_string) else {
+            return;
+        };
+
+        match event.event_type() {
+            Some("SessionStart") => self.record_start(&session_id, now),
+            Some("SessionEnd") => match self.finish(&session_id, now) {
+                Some(elapsed) => {
+                    event.data.insert(
+                        DURATION_FIELD.to_string(),
+                        Value::String(format_duration(elapsed)),
+                    );
+                    event.data.insert(
+                        DURATION_MS_FIELD.to_string(),
+                        Value::from(elapsed.as_millis() as u64),
+                    );
+                }
+                None => log::debug!("No recorded start for session {}", session_id),
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Formats a duration as its two largest units, e.g. "1h 5m", "3m 20s" or "42s".
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_700_000_000 + secs)
+    }
+
+    fn event(hook: &str, session_id: &str) -> Event {
+        Event::from_json(&serde_json::json!({"hook_event_name": hook, "session_id": session_id}).to_string())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_start_then_finish() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.json");
+
+        SessionTracker::new(&path).record_start("abc", at(0));
+        let elapsed = SessionTracker::new(&path).finish("abc", at(125));
+        assert_eq!(elapsed, Some(Duration::from_secs(125)));
+
+        // The start is consumed by the first finish
+        assert_eq!(SessionTracker::new(&path).finish("abc", at(130)), None);
+    }
+
+    #[test]
+    fn test_track_sets_duration_on_session_end() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = SessionTracker::new(dir.path().join("sessions.json"));
+
+        tracker.track(&mut event("SessionStart", "abc"), at(0));
+        let mut end = event("SessionEnd", "abc");
+        tracker.track(&mut end, at(3_900));
+
+        assert_eq!(end.get_str(DURATION_FIELD), Some("1h 5m"));
+        assert_eq!(end.get_i64(DURATION_MS_FIELD), Some(3_900_000));
+    }
+
+    #[test]
+    fn test_compaction_keeps_original_start() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = SessionTracker::new(dir.path().join("sessions.json"));
+
+        tracker.track(&mut event("SessionStart", "abc"), at(0));
+        // Claude Code starts the session again after /compact or a resume
+        let mut restart = event("SessionStart", "abc");
+        restart.data.insert("source".to_string(), Value::from("compact"));
+        tracker.track(&mut restart, at(600));
+
+        let mut end = event("SessionEnd", "abc");
+        tracker.track(&mut end, at(900));
+        assert_eq!(end.get_str(DURATION_FIELD), Some("15m 0s"));
+    }
+
+    #[test]
+    fn test_track_without_recorded_start() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = SessionTracker::new(dir.path().join("sessions.json"));
+
+        tracker.track(&mut event("SessionStart", "other"), at(0));
+        let mut end = event("SessionEnd", "abc");
+        tracker.track(&mut end, at(60));
+
+        assert_eq!(end.get_str(DURATION_FIELD), None);
+        assert_eq!(end.get_i64(DURATION_MS_FIELD), None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(42)), "42s");
+        assert_eq!(format_duration(Duration::from_secs(200)), "3m 20s");
+        assert_eq!(format_duration(Duration::from_secs(3_900)), "1h 5m");
+        assert_eq!(format_duration(Duration::from_millis(999)), "0s");
+    }
+}
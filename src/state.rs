@@ -0,0 +1,216 @@
+//! JSON state shared between invocations.
+//!
+//! Each boopifier invocation is a fresh process, so debounce, rate limit,
+//! session, digest and metrics state lives in small JSON files in the temp
+//! directory. Hooks can run concurrently (e.g. for parallel tool calls), so
+//! every update holds an exclusive lock on a `<file>.lock` sidecar while it
+//! reads, changes and writes the state, and the new state replaces the file
+//! in one rename. The daemon can keep state in memory instead.
+
+use crate::debounce::Debouncer;
+use crate::digest::DigestStore;
+use crate::metrics::MetricsStore;
+use crate::rate_limit::RateLimiter;
+use crate::sessions::SessionTracker;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// The state `process_event_with` reads and updates for each event.
+pub struct Stores {
+    pub debouncer: Debouncer,
+    pub rate_limiter: RateLimiter,
+    pub sessions: SessionTracker,
+    pub digests: DigestStore,
+    pub metrics: MetricsStore,
+}
+
+impl Stores {
+    /// Every store in its default state file under `$TMPDIR`.
+    pub fn default_files() -> Self {
+        Self {
+            debouncer: Debouncer::new(Debouncer::default_path()),
+            rate_limiter: RateLimiter::new(RateLimiter::default_path()),
+            sessions: SessionTracker::new(SessionTracker::default_path()),
+            digests: DigestStore::new(DigestStore::default_path()),
+            metrics: MetricsStore::new(MetricsStore::default_path()),
+        }
+    }
+}
+
+/// A JSON document updated under a lock.
+///
+/// Errors reading, locking or writing the file are ignored: a missing or
+/// invalid file reads as the default state, and a failed write is dropped.
+/// All of this state is best-effort.
+#[derive(Debug, Clone)]
+pub struct StateFile {
+    backend: Backend,
+}
+
+#[derive(Debug, Clone)]
+enum Backend {
+    File(PathBuf),
+    Memory(Arc<Mutex<Value>>),
+}
+
+impl StateFile {
+    /// State persisted to `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            backend: Backend::File(path.into()),
+        }
+    }
+
+    /// State kept in memory, shared by clones of the returned `StateFile`.
+    pub fn in_memory() -> Self {
+        Self {
+            backend: Backend::Memory(Arc::new(Mutex::new(Value::Null))),
+        }
+    }
+
+    /// Reads the current state without locking.
+    ///
+    /// Writers replace the file atomically, so this never sees a partial update.
+    pub fn load<T: DeserializeOwned + Default>(&self) -> T {
+        match &self.backend {
+            Backend::File(path) => read(path),
+            Backend::Memory(value) => {
+                let value = value.lock().unwrap_or_else(PoisonError::into_inner);
+                serde_json::from_value(value.clone()).unwrap_or_default()
+            }
+        }
+    }
+
+    /// Applies `change` to the current state and saves the result, holding
+    /// the lock throughout so concurrent updates can't be lost.
+    pub fn update<T, R>(&self, change: impl FnOnce(&mut T) -> R) -> R
+    where
+        T: Serialize + DeserializeOwned + Default,
+    {
+        match &self.backend {
+            Backend::File(path) => {
+                let _lock = lock(path);
+                let mut state = read(path);
+                let result = change(&mut state);
+                write(path, &state);
+                result
+            }
+            Backend::Memory(value) => {
+                let mut value = value.lock().unwrap_or_else(PoisonError::into_inner);
+                let mut state = serde_json::from_value(value.clone()).unwrap_or_default();
+                let result = change(&mut state);
+                if let Ok(updated) = serde_json::to_value(&state) {
+                    *value = updated;
+                }
+                result
+            }
+        }
+    }
+}
+
+fn read<T: DeserializeOwned + Default>(path: &Path) -> T {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Writes `state` to a temp file next to `path`, then renames it into place.
+fn write<T: Serialize>(path: &Path, state: &T) {
+    let Ok(content) = serde_json::to_string(state) else {
+        return;
+    };
+    let temp = sibling(path, &format!(".{}.tmp", std::process::id()));
+    if fs::write(&temp, content).is_err() || fs::rename(&temp, path).is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+}
+
+/// Takes the exclusive lock for `path`, released when the file is dropped.
+///
+/// The lock is on a sidecar because renames replace the state file itself.
+fn lock(path: &Path) -> Option<File> {
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(sibling(path, ".lock"))
+        .ok()?;
+    file.lock().ok()?;
+    Some(file)
+}
+
+/// `path` with `suffix` appended to its file name.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_update_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+
+        let previous = StateFile::new(&path).update(|counts: &mut HashMap<String, u64>| {
+            counts.insert("a".to_string(), 1)
+        });
+        assert_eq!(previous, None);
+
+        let counts: HashMap<String, u64> = StateFile::new(&path).load();
+        assert_eq!(counts.get("a"), Some(&1));
+        // Only the state file and its lock are left behind
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_concurrent_updates_are_not_lost() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("counter.json");
+
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let state = StateFile::new(&path);
+                std::thread::spawn(move || {
+                    for _ in 0..25 {
+                        state.update(|count: &mut u64| *count += 1);
+                    }
+                })
+            })
+            .collect();
+        threads.into_iter().for_each(|t| t.join().unwrap());
+
+        assert_eq!(StateFile::new(&path).load::<u64>(), 200);
+    }
+
+    #[test]
+    fn test_invalid_file_reads_as_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        fs::write(&path, "{not json").unwrap();
+
+        assert_eq!(StateFile::new(&path).load::<Vec<u64>>(), Vec::<u64>::new());
+        StateFile::new(&path).update(|fired: &mut Vec<u64>| fired.push(7));
+        assert_eq!(StateFile::new(&path).load::<Vec<u64>>(), [7]);
+    }
+
+    #[test]
+    fn test_in_memory_state_is_shared_by_clones() {
+        let state = StateFile::in_memory();
+        state.clone().update(|fired: &mut Vec<u64>| fired.push(1));
+        state.update(|fired: &mut Vec<u64>| fired.push(2));
+
+        assert_eq!(state.load::<Vec<u64>>(), [1, 2]);
+        assert!(StateFile::in_memory().load::<Vec<u64>>().is_empty());
+    }
+}
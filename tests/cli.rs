@@ -0,0 +1,843 @@
+//! End-to-end tests for the boopifier binary.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Writes a config whose only handler runs `touch <marker>` via the tts handler,
+/// so a file appears if the handler really ran.
+fn write_marker_config(dir: &TempDir) -> (PathBuf, PathBuf) {
+    let marker = dir.path().join("handler-ran");
+    let config = json!({
+        "handlers": [{
+            "name": "marker",
+            "type": "tts",
+            "match_rules": {"hook_event_name": "Stop"},
+            "config": {"command": "touch", "message": marker.to_str().unwrap()}
+        }]
+    });
+
+    let config_path = dir.path().join("boopifier.json");
+    fs::write(&config_path, config.to_string()).unwrap();
+    (config_path, marker)
+}
+
+fn boopifier(config_path: &Path) -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_boopifier"));
+    cmd.env_remove("CLAUDE_PROJECT_DIR").arg("-c").arg(config_path);
+    cmd
+}
+
+#[test]
+fn test_runs_matching_handler() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+
+    boopifier(&config_path)
+        .write_stdin(r#"{"hook_event_name": "Stop"}"#)
+        .assert()
+        .success()
+        .stdout("{}\n");
+
+    assert!(marker.exists());
+}
+
+#[test]
+fn test_dry_run_does_not_run_handlers() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+
+    boopifier(&config_path)
+        .arg("--dry-run")
+        .write_stdin(r#"{"hook_event_name": "Stop"}"#)
+        .assert()
+        .success()
+        .stdout("{}\n")
+        .stderr(predicate::str::contains("would run handler 'marker' (type: tts)"));
+
+    assert!(!marker.exists());
+}
+
+#[test]
+fn test_dry_run_reports_no_matches() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+
+    boopifier(&config_path)
+        .arg("--dry-run")
+        .write_stdin(r#"{"hook_event_name": "Notification"}"#)
+        .assert()
+        .success()
+        .stdout("{}\n")
+        .stderr(predicate::str::contains("no handlers matched"));
+
+    assert!(!marker.exists());
+}
+
+#[test]
+fn test_verbose_logging_keeps_stdout_clean() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, _marker) = write_marker_config(&dir);
+
+    boopifier(&config_path)
+        .arg("-vv")
+        .write_stdin(r#"{"hook_event_name": "Stop"}"#)
+        .assert()
+        .success()
+        .stdout("{}\n")
+        .stderr(predicate::str::contains("Hook type: Stop"))
+        .stderr(predicate::str::contains("Handler 'marker' matched"));
+}
+
+#[test]
+fn test_log_level_from_env() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, _marker) = write_marker_config(&dir);
+
+    boopifier(&config_path)
+        .env("BOOPIFIER_LOG", "debug")
+        .write_stdin(r#"{"hook_event_name": "Notification"}"#)
+        .assert()
+        .success()
+        .stdout("{}\n")
+        .stderr(predicate::str::contains("Rule hook_event_name"));
+}
+
+#[test]
+fn test_force_hook_env() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+
+    boopifier(&config_path)
+        .arg("-v")
+        .env("BOOPIFIER_FORCE_HOOK", "SubagentStop")
+        .write_stdin(r#"{"hook_event_name": "Notification", "message": "hi"}"#)
+        .assert()
+        .success()
+        .stdout("{}\n")
+        .stderr(predicate::str::contains("Hook type: SubagentStop"));
+    assert!(!marker.exists());
+
+    // Handlers match on the forced hook type, not the event's own
+    boopifier(&config_path)
+        .env("BOOPIFIER_FORCE_HOOK", "Stop")
+        .write_stdin(r#"{"hook_event_name": "Notification", "message": "hi"}"#)
+        .assert()
+        .success()
+        .stdout("{}\n");
+    assert!(marker.exists());
+
+    let output = boopifier(&config_path)
+        .env("BOOPIFIER_FORCE_HOOK", "Bogus")
+        .write_stdin(r#"{"hook_event_name": "Stop"}"#)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let response: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert!(response["systemMessage"]
+        .as_str()
+        .unwrap()
+        .contains("Invalid BOOPIFIER_FORCE_HOOK \"Bogus\""));
+}
+
+#[test]
+fn test_since_skips_stale_events() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    boopifier(&config_path)
+        .args(["--since", "60"])
+        .write_stdin(json!({"hook_event_name": "Stop", "timestamp": now - 3600}).to_string())
+        .assert()
+        .success()
+        .stdout("{}\n");
+    assert!(!marker.exists());
+
+    boopifier(&config_path)
+        .args(["--since", "60"])
+        .write_stdin(json!({"hook_event_name": "Stop", "timestamp": now}).to_string())
+        .assert()
+        .success()
+        .stdout("{}\n");
+    assert!(marker.exists());
+}
+
+#[test]
+fn test_explain_traces_rules() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+
+    boopifier(&config_path)
+        .arg("--explain")
+        .write_stdin(r#"{"hook_event_name": "Notification"}"#)
+        .assert()
+        .success()
+        .stdout("{}\n")
+        .stderr(predicate::str::contains("[explain] Handler 'marker' (tts): no match"))
+        .stderr(predicate::str::contains(
+            r#"[explain]   hook_event_name == "Stop": got "Notification" -> no match"#,
+        ))
+        .stderr(predicate::str::contains("[explain] Would run: no handlers"));
+
+    // The event is still handled as usual
+    boopifier(&config_path)
+        .arg("--explain")
+        .write_stdin(r#"{"hook_event_name": "Stop"}"#)
+        .assert()
+        .success()
+        .stdout("{}\n")
+        .stderr(predicate::str::contains("[explain] Would run: marker"));
+    assert!(marker.exists());
+}
+
+#[test]
+fn test_quiet_hours_skip_is_logged() {
+    let dir = TempDir::new().unwrap();
+    let marker = dir.path().join("handler-ran");
+    // Two ranges that together cover the whole day
+    let config = json!({
+        "quiet_hours": [{"start": "00:00", "end": "12:00"}, {"start": "12:00", "end": "00:00"}],
+        "handlers": [{
+            "name": "marker",
+            "type": "tts",
+            "config": {"command": "touch", "message": marker.to_str().unwrap()}
+        }]
+    });
+    let config_path = dir.path().join("boopifier.json");
+    fs::write(&config_path, config.to_string()).unwrap();
+
+    boopifier(&config_path)
+        .arg("-v")
+        .write_stdin(r#"{"hook_event_name": "Stop"}"#)
+        .assert()
+        .success()
+        .stdout("{}\n")
+        .stderr(predicate::str::contains("Handler 'marker' skipped: quiet hours"));
+    assert!(!marker.exists());
+}
+
+#[test]
+fn test_recover_valid_payload() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+
+    boopifier(&config_path)
+        .arg("--recover")
+        .write_stdin(r#"{"hook_event_name": "Stop"}"#)
+        .assert()
+        .success()
+        .stdout("{}\n");
+    assert!(marker.exists());
+}
+
+#[test]
+fn test_recover_trims_trailing_junk() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+
+    // Split across lines, with trailing whitespace and junk: one line alone isn't the event
+    boopifier(&config_path)
+        .arg("--recover")
+        .write_stdin("{\"hook_event_name\":\n \"Stop\"}  \n\t\n\0\0")
+        .assert()
+        .success()
+        .stdout("{}\n");
+    assert!(marker.exists());
+}
+
+#[test]
+fn test_recover_reports_truncated_event() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+
+    let output = boopifier(&config_path)
+        .arg("--recover")
+        .write_stdin(r#"{"hook_event_name": "Stop", "session_id": "ab"#)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let response: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert!(response["systemMessage"]
+        .as_str()
+        .unwrap()
+        .contains("incomplete event JSON (truncated input?)"));
+    assert!(!marker.exists());
+}
+
+/// Runs `boopifier --recover`, writing `input` but holding stdin open until
+/// the process exits, as a stalled hook runner would.
+fn recover_with_open_stdin(config_path: &Path, input: &str) -> (std::process::Output, std::time::Duration) {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_boopifier"))
+        .env_remove("CLAUDE_PROJECT_DIR")
+        .arg("-c")
+        .arg(config_path)
+        .arg("--recover")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let started = std::time::Instant::now();
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    let elapsed = started.elapsed();
+    drop(stdin);
+    (output, elapsed)
+}
+
+#[test]
+fn test_recover_gives_up_on_truncated_open_stdin() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+
+    let (output, elapsed) = recover_with_open_stdin(&config_path, r#"{"hook_event_name": "Stop", "sess"#);
+
+    // Waits for the rest of the event, then reports it instead of hanging
+    assert!(output.status.success());
+    assert!(elapsed >= std::time::Duration::from_millis(500));
+    assert!(elapsed < std::time::Duration::from_secs(5));
+    let response: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(response["systemMessage"]
+        .as_str()
+        .unwrap()
+        .contains("incomplete event JSON (truncated input?)"));
+    assert!(!marker.exists());
+}
+
+#[test]
+fn test_recover_ignores_trailing_whitespace_on_open_stdin() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+
+    let padding = " \n\t".repeat(4096);
+    let (output, elapsed) =
+        recover_with_open_stdin(&config_path, &format!("{{\"hook_event_name\": \"Stop\"}}{}", padding));
+
+    // The complete object is used as soon as it arrives
+    assert!(output.status.success());
+    assert!(elapsed < std::time::Duration::from_secs(5));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "{}\n");
+    assert!(marker.exists());
+}
+
+#[test]
+fn test_event_file_matches_stdin() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("boopifier.json");
+    fs::write(&config_path, r#"{"handlers": []}"#).unwrap();
+
+    // Pretty-printed JSON works from a file since the whole file is read
+    let event_path = dir.path().join("event.json");
+    fs::write(&event_path, "{\n  \"hook_event_name\": \"PreToolUse\",\n  \"tool_name\": \"Bash\"\n}\n").unwrap();
+
+    let from_stdin = boopifier(&config_path)
+        .write_stdin(r#"{"hook_event_name": "PreToolUse", "tool_name": "Bash"}"#)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    boopifier(&config_path)
+        .arg("--event-file")
+        .arg(&event_path)
+        .assert()
+        .success()
+        .stdout(String::from_utf8(from_stdin).unwrap());
+}
+
+#[test]
+fn test_missing_event_file() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("boopifier.json");
+    fs::write(&config_path, r#"{"handlers": []}"#).unwrap();
+
+    boopifier(&config_path)
+        .arg("--event-file")
+        .arg(dir.path().join("missing.json"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Error reading event file"));
+}
+
+#[test]
+fn test_batch_continues_after_invalid_line() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("boopifier.json");
+    fs::write(&config_path, r#"{"handlers": []}"#).unwrap();
+
+    let input = concat!(
+        r#"{"hook_event_name": "Stop"}"#,
+        "\n",
+        r#"{"hook_event_name": "#,
+        "\n",
+        r#"{"hook_event_name": "PreToolUse", "tool_name": "Bash"}"#,
+        "\n",
+    );
+
+    let output = boopifier(&config_path)
+        .arg("--batch")
+        .write_stdin(input)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let lines: Vec<serde_json::Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], json!({}));
+    assert!(lines[1]["systemMessage"].as_str().unwrap().contains("Invalid JSON"));
+    assert_eq!(lines[2]["hookSpecificOutput"]["permissionDecision"], "allow");
+}
+
+#[test]
+fn test_validate_rejects_incomplete_event() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+
+    boopifier(&config_path)
+        .arg("--validate")
+        .write_stdin(r#"{"hook_event_name": "Stop"}"#)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("missing required fields: stop_hook_active"));
+
+    assert!(!marker.exists());
+}
+
+#[test]
+fn test_validate_accepts_complete_event() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+
+    boopifier(&config_path)
+        .arg("--validate")
+        .write_stdin(r#"{"hook_event_name": "Stop", "stop_hook_active": false}"#)
+        .assert()
+        .success()
+        .stdout("{}\n");
+
+    assert!(marker.exists());
+}
+
+#[test]
+fn test_project_toml_config_used_without_json_config() {
+    let home = TempDir::new().unwrap();
+    let project = TempDir::new().unwrap();
+    let marker = project.path().join("handler-ran");
+
+    // The user layer would never match; the project layer replaces it by name
+    fs::create_dir_all(home.path().join(".config/boopifier")).unwrap();
+    fs::write(
+        home.path().join(".config/boopifier/config.toml"),
+        "[[handlers]]\nname = \"marker\"\ntype = \"tts\"\nmatch_rules = { hook_event_name = \"Never\" }\nconfig = {}\n",
+    )
+    .unwrap();
+    fs::write(
+        project.path().join(".boopifier.toml"),
+        format!(
+            "[[handlers]]\nname = \"marker\"\ntype = \"tts\"\nmatch_rules = {{ hook_event_name = \"Stop\" }}\nconfig = {{ command = \"touch\", message = {:?} }}\n",
+            marker.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+
+    Command::new(env!("CARGO_BIN_EXE_boopifier"))
+        .env("HOME", home.path())
+        .env("CLAUDE_PROJECT_DIR", project.path())
+        .write_stdin(r#"{"hook_event_name": "Stop"}"#)
+        .assert()
+        .success()
+        .stdout("{}\n");
+
+    assert!(marker.exists());
+}
+
+/// Writes a config whose only handler always fails (`false` exits 1), with
+/// `block_on_error` set as given (or left out).
+fn write_failing_config(dir: &TempDir, block_on_error: Option<bool>) -> PathBuf {
+    let mut config = json!({
+        "handlers": [{
+            "name": "failing",
+            "type": "tts",
+            "config": {"command": "false", "message": "hi"}
+        }]
+    });
+    if let Some(block_on_error) = block_on_error {
+        config["block_on_error"] = json!(block_on_error);
+    }
+
+    let config_path = dir.path().join("boopifier.json");
+    fs::write(&config_path, config.to_string()).unwrap();
+    config_path
+}
+
+#[test]
+fn test_handler_errors_exit_2_for_blocking_hook_by_default() {
+    let dir = TempDir::new().unwrap();
+    let config_path = write_failing_config(&dir, None);
+
+    boopifier(&config_path)
+        .write_stdin(r#"{"hook_event_name": "Stop"}"#)
+        .assert()
+        .code(2)
+        .stdout("")
+        .stderr(predicate::str::contains("Boopifier handler errors: failing:"));
+}
+
+#[test]
+fn test_block_on_error_ignored_for_non_blocking_hook() {
+    let dir = TempDir::new().unwrap();
+    let config_path = write_failing_config(&dir, Some(true));
+
+    boopifier(&config_path)
+        .write_stdin(r#"{"hook_event_name": "Notification"}"#)
+        .assert()
+        .code(0)
+        .stdout(predicate::str::contains("Boopifier warning: failing:"));
+}
+
+#[test]
+fn test_block_on_error_false_exits_0() {
+    let dir = TempDir::new().unwrap();
+    let config_path = write_failing_config(&dir, Some(false));
+
+    boopifier(&config_path)
+        .write_stdin(r#"{"hook_event_name": "Stop"}"#)
+        .assert()
+        .code(0)
+        .stdout(predicate::str::contains("Boopifier warning: failing:"));
+}
+
+#[test]
+fn test_block_on_error_success_exits_0() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+    let mut config: serde_json::Value = serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    config["block_on_error"] = json!(true);
+    fs::write(&config_path, config.to_string()).unwrap();
+
+    boopifier(&config_path)
+        .write_stdin(r#"{"hook_event_name": "Stop"}"#)
+        .assert()
+        .code(0)
+        .stdout("{}\n");
+
+    assert!(marker.exists());
+}
+
+#[test]
+fn test_empty_stdin_reports_friendly_error() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+
+    boopifier(&config_path)
+        .write_stdin("  \n")
+        .assert()
+        .code(1)
+        .stdout("")
+        .stderr(predicate::str::contains("no event data received on stdin"))
+        .stderr(predicate::str::contains("expected value").not());
+
+    assert!(!marker.exists());
+}
+
+#[test]
+fn test_print_response_only() {
+    Command::new(env!("CARGO_BIN_EXE_boopifier"))
+        .env_remove("CLAUDE_PROJECT_DIR")
+        .env("HOME", "/nonexistent")
+        .arg("--print-response-only")
+        .write_stdin(r#"{"hook_event_name": "Stop", "session_id": "abc"}"#)
+        .assert()
+        .success()
+        .stdout("{}\n")
+        .stderr("");
+
+    Command::new(env!("CARGO_BIN_EXE_boopifier"))
+        .env_remove("CLAUDE_PROJECT_DIR")
+        .arg("--print-response-only")
+        .write_stdin(r#"{"hook_event_name": "PreToolUse", "tool_name": "Bash"}"#)
+        .assert()
+        .success()
+        .stdout("{\"hookSpecificOutput\":{\"hookEventName\":\"PreToolUse\",\"permissionDecision\":\"allow\"}}\n")
+        .stderr("");
+}
+
+#[test]
+fn test_response_version_flag() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, _marker) = write_marker_config(&dir);
+
+    // Off by default
+    boopifier(&config_path)
+        .write_stdin(r#"{"hook_event_name": "Notification"}"#)
+        .assert()
+        .success()
+        .stdout("{}\n");
+
+    for extra in [None, Some("--print-response-only")] {
+        let output = boopifier(&config_path)
+            .arg("--response-version")
+            .args(extra)
+            .write_stdin(r#"{"hook_event_name": "PreToolUse", "tool_name": "Bash"}"#)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone();
+        let response: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(response["boopifier_version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(response["hookSpecificOutput"]["permissionDecision"], "allow");
+    }
+}
+
+#[test]
+fn test_print_response_only_does_not_run_handlers() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+
+    boopifier(&config_path)
+        .arg("--print-response-only")
+        .write_stdin(r#"{"hook_event_name": "Stop"}"#)
+        .assert()
+        .success()
+        .stdout("{}\n");
+
+    assert!(!marker.exists());
+}
+
+/// Starts `boopifier --daemon --socket <socket>` and waits for the socket.
+#[cfg(unix)]
+fn spawn_daemon(config_path: &std::path::Path, socket: &std::path::Path) -> std::process::Child {
+    let daemon = std::process::Command::new(env!("CARGO_BIN_EXE_boopifier"))
+        .env_remove("CLAUDE_PROJECT_DIR")
+        .arg("-c")
+        .arg(config_path)
+        .arg("--daemon")
+        .arg("--socket")
+        .arg(socket)
+        .spawn()
+        .unwrap();
+
+    for _ in 0..100 {
+        if socket.exists() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    daemon
+}
+
+#[cfg(unix)]
+#[test]
+fn test_daemon_round_trips_event() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+    let socket = dir.path().join("boopifier.sock");
+    let mut daemon = spawn_daemon(&config_path, &socket);
+
+    let result = Command::new(env!("CARGO_BIN_EXE_boopifier"))
+        .env_remove("CLAUDE_PROJECT_DIR")
+        .arg("--client")
+        .arg("--socket")
+        .arg(&socket)
+        .write_stdin(r#"{"hook_event_name": "Stop"}"#)
+        .assert();
+
+    daemon.kill().unwrap();
+    daemon.wait().unwrap();
+
+    result.success().stdout("{}\n");
+    assert!(marker.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_stalled_daemon_client_does_not_block_others() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+    let socket = dir.path().join("boopifier.sock");
+    let mut daemon = spawn_daemon(&config_path, &socket);
+
+    // Connects but never sends an event
+    let _stalled = std::os::unix::net::UnixStream::connect(&socket).unwrap();
+
+    let started = std::time::Instant::now();
+    let result = Command::new(env!("CARGO_BIN_EXE_boopifier"))
+        .env_remove("CLAUDE_PROJECT_DIR")
+        .arg("--client")
+        .arg("--socket")
+        .arg(&socket)
+        .write_stdin(r#"{"hook_event_name": "Stop"}"#)
+        .timeout(std::time::Duration::from_secs(3))
+        .assert();
+    let elapsed = started.elapsed();
+
+    daemon.kill().unwrap();
+    daemon.wait().unwrap();
+
+    result.success().stdout("{}\n");
+    assert!(elapsed < std::time::Duration::from_secs(3), "took {:?}", elapsed);
+    assert!(marker.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_daemon_defaults_to_runtime_dir() {
+    let dir = TempDir::new().unwrap();
+    let (config_path, marker) = write_marker_config(&dir);
+    let runtime_dir = dir.path().join("run");
+    std::fs::create_dir(&runtime_dir).unwrap();
+    let socket = runtime_dir.join("boopifier.sock");
+
+    let mut daemon = std::process::Command::new(env!("CARGO_BIN_EXE_boopifier"))
+        .env_remove("CLAUDE_PROJECT_DIR")
+        .env("XDG_RUNTIME_DIR", &runtime_dir)
+        .arg("-c")
+        .arg(&config_path)
+        .arg("--daemon")
+        .spawn()
+        .unwrap();
+
+    for _ in 0..100 {
+        if socket.exists() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+
+    let result = Command::new(env!("CARGO_BIN_EXE_boopifier"))
+        .env_remove("CLAUDE_PROJECT_DIR")
+        .env("XDG_RUNTIME_DIR", &runtime_dir)
+        .arg("--client")
+        .write_stdin(r#"{"hook_event_name": "Stop"}"#)
+        .assert();
+
+    daemon.kill().unwrap();
+    daemon.wait().unwrap();
+
+    result.success().stdout("{}\n");
+    assert!(marker.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_client_without_daemon_still_responds() {
+    let dir = TempDir::new().unwrap();
+
+    Command::new(env!("CARGO_BIN_EXE_boopifier"))
+        .arg("--client")
+        .arg("--socket")
+        .arg(dir.path().join("missing.sock"))
+        .write_stdin(r#"{"hook_event_name": "Stop"}"#)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Failed to reach boopifier daemon"));
+}
+
+#[test]
+fn test_validate_config_clean() {
+    let dir = TempDir::new().unwrap();
+    let sound = dir.path().join("boop.wav");
+    fs::write(&sound, b"").unwrap();
+
+    let config_path = dir.path().join("boopifier.json");
+    let config = json!({
+        "handlers": [{
+            "name": "boop",
+            "type": "sound",
+            "match_type": "regex",
+            "match_rules": {"tool_name": "^(Bash|Edit)$"},
+            "config": {"file": sound}
+        }]
+    });
+    fs::write(&config_path, config.to_string()).unwrap();
+
+    boopifier(&config_path)
+        .arg("--validate-config")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Config OK"));
+}
+
+#[test]
+fn test_validate_config_bad_regex() {
+    let dir = TempDir::new().unwrap();
+    let config_path = dir.path().join("boopifier.json");
+    let config = json!({
+        "handlers": [{
+            "name": "broken",
+            "type": "desktop",
+            "match_type": "regex",
+            "match_rules": {"tool_name": "(Bash"},
+            "config": {}
+        }]
+    });
+    fs::write(&config_path, config.to_string()).unwrap();
+
+    boopifier(&config_path)
+        .arg("--validate-config")
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("Handler 'broken' has an invalid Regex pattern"));
+}
+
+#[test]
+fn test_validate_config_missing_sound_file() {
+    let dir = TempDir::new().unwrap();
+    let missing = dir.path().join("missing.wav");
+    let config_path = dir.path().join("boopifier.json");
+    let config = json!({
+        "enabled_hooks": ["Stop", "Stopp"],
+        "handlers": [{"name": "boop", "type": "sound", "config": {"file": missing}}]
+    });
+    fs::write(&config_path, config.to_string()).unwrap();
+
+    boopifier(&config_path)
+        .arg("--validate-config")
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains(format!(
+            "Handler 'boop' references a missing file: {}",
+            missing.display()
+        )))
+        .stdout(predicate::str::contains("Unknown hook type in enabled_hooks/disabled_hooks: Stopp"));
+}
+
+#[test]
+fn test_list_hooks() {
+    let output = Command::new(env!("CARGO_BIN_EXE_boopifier"))
+        .arg("--list-hooks")
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let hooks: Vec<String> = String::from_utf8(output).unwrap().lines().map(String::from).collect();
+    assert_eq!(hooks, boopifier::hooks::HOOK_TYPES);
+    assert!(hooks.iter().any(|h| h == "Stop"));
+    assert!(hooks.iter().any(|h| h == "PreCompact"));
+}